@@ -0,0 +1,18 @@
+use hibp_rs::HaveIBeenPwned;
+
+/// Checks a password typed at a no-echo prompt against Pwned Passwords.
+///
+/// Usage: `cargo run --example check_password --features prompt`
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Pwned Passwords does not require an API key
+    let hibp = HaveIBeenPwned::new("");
+
+    let count = hibp.prompt_and_check("Password to check: ").await?;
+    if count > 0 {
+        println!("This password was found {} times in data breaches", count);
+        std::process::exit(1);
+    }
+    println!("This password was not found in any data breach");
+    Ok(())
+}
@@ -1,9 +1,9 @@
-use hibp_rs::HaveIBeenPwned;
+use hibp_rs::{ExposeSecret, HaveIBeenPwned};
 use std::time::Instant;
 
 /// Example demonstrating concurrent operations using Clone
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Create a client with rate limiting (or use your API key from .env)
     dotenv::dotenv().ok();
     let api_key = std::env::var("HIBP_API_KEY").unwrap_or_else(|_| {
@@ -20,15 +20,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let hibp2 = hibp.clone();
     let hibp3 = hibp.clone();
 
-    println!("Original client API key: {}", hibp.api_key);
-    println!("Clone 1 API key: {}", hibp1.api_key);
-    println!("Clone 2 API key: {}", hibp2.api_key);
-    println!("Clone 3 API key: {}", hibp3.api_key);
+    println!("Original client user agent: {}", hibp.user_agent);
+    println!("Clone 1 user agent: {}", hibp1.user_agent);
+    println!("Clone 2 user agent: {}", hibp2.user_agent);
+    println!("Clone 3 user agent: {}", hibp3.user_agent);
 
     // Verify all clones have the same configuration
-    assert_eq!(hibp.api_key, hibp1.api_key);
-    assert_eq!(hibp.api_key, hibp2.api_key);
-    assert_eq!(hibp.api_key, hibp3.api_key);
+    assert_eq!(hibp.api_key.expose_secret(), hibp1.api_key.expose_secret());
+    assert_eq!(hibp.api_key.expose_secret(), hibp2.api_key.expose_secret());
+    assert_eq!(hibp.api_key.expose_secret(), hibp3.api_key.expose_secret());
 
     println!("✓ All clones have identical configuration");
 
@@ -38,21 +38,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let task1 = tokio::spawn(async move {
         // Simulate some work with the cloned client
         tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-        println!("Task 1 completed with client API key: {}", hibp1.api_key);
+        println!("Task 1 completed with client for {}", hibp1.base_url);
         "task1_result"
     });
 
     let task2 = tokio::spawn(async move {
         // Simulate some work with the cloned client
         tokio::time::sleep(std::time::Duration::from_millis(150)).await;
-        println!("Task 2 completed with client API key: {}", hibp2.api_key);
+        println!("Task 2 completed with client for {}", hibp2.base_url);
         "task2_result"
     });
 
     let task3 = tokio::spawn(async move {
         // Simulate some work with the cloned client
         tokio::time::sleep(std::time::Duration::from_millis(80)).await;
-        println!("Task 3 completed with client API key: {}", hibp3.api_key);
+        println!("Task 3 completed with client for {}", hibp3.base_url);
         "task3_result"
     });
 
@@ -72,7 +72,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("✓ Concurrent operations using Clone completed successfully!");
 
     // The original client is still usable
-    println!("Original client is still available: {}", hibp.api_key);
+    println!("Original client is still available: {}", hibp.base_url);
 
     Ok(())
 }
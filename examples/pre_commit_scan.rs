@@ -0,0 +1,49 @@
+use hibp_rs::{HaveIBeenPwned, SarifLog, SarifRule, SecretScanner};
+
+/// Example pre-commit hook that fails when staged files contain pwned secrets.
+///
+/// Usage: `git diff --cached --name-only | xargs cargo run --example pre_commit_scan -- [--sarif]`
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Pwned Passwords does not require an API key
+    dotenv::dotenv().ok();
+    let api_key = std::env::var("HIBP_API_KEY").unwrap_or_default();
+    let hibp = HaveIBeenPwned::new(api_key);
+
+    let mut sarif_output = false;
+    let mut paths = Vec::new();
+    for arg in std::env::args().skip(1) {
+        if arg == "--sarif" {
+            sarif_output = true;
+        } else {
+            paths.push(arg);
+        }
+    }
+
+    let scanner = SecretScanner::new();
+    let mut findings = Vec::new();
+    for path in &paths {
+        findings.extend(hibp.scan_file(&scanner, path).await?);
+    }
+
+    if sarif_output {
+        let mut log = SarifLog::new("hibp-pre-commit").with_rule(SarifRule::pwned_password());
+        for finding in &findings {
+            log.add_result(finding.to_sarif_result());
+        }
+        println!("{}", log.to_string_pretty()?);
+    } else {
+        for finding in &findings {
+            eprintln!(
+                "{}:{}: secret matched by '{}' appears {} times in data breaches",
+                finding.source, finding.line, finding.pattern, finding.count
+            );
+        }
+    }
+
+    if !findings.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
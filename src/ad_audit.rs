@@ -0,0 +1,265 @@
+use crate::event::password_severity;
+use crate::hashing::secret;
+use crate::password::{RangeMode, group_by_prefix, range_count};
+use crate::{
+    Event, HaveIBeenPwned, HibpError, MessageCatalog, ResultSink, SarifLevel, SarifResult,
+};
+use std::collections::HashMap;
+
+/// An account and its NT hash, as read from an Active Directory dump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NtHashEntry {
+    /// Account name, including the domain if present in the dump (e.g. `CONTOSO\alice`).
+    pub account: String,
+    /// Uppercase hex NT hash of the account's password.
+    pub nt_hash: String,
+}
+
+impl NtHashEntry {
+    /// Parses a dump of NT hashes, one account per line.
+    ///
+    /// Supported line formats:
+    /// - `account:hash` (DSInternals `HashcatNT` view)
+    /// - `account:$NT$hash` (DSInternals `JohnNT` view)
+    /// - `account:rid:lmhash:nthash:::` (pwdump / secretsdump)
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `dump` - Contents of the dump.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::NtHashEntry;
+    ///
+    /// let dump = "CONTOSO\\alice:8846f7eaee8fb117ad06bdd830b7586c\n";
+    /// let entries = NtHashEntry::parse_dump(dump).unwrap();
+    /// assert_eq!(entries[0].account, "CONTOSO\\alice");
+    /// assert_eq!(entries[0].nt_hash, "8846F7EAEE8FB117AD06BDD830B7586C");
+    /// ```
+    pub fn parse_dump(dump: &str) -> Result<Vec<NtHashEntry>, HibpError> {
+        let mut entries = Vec::new();
+
+        for (index, line) in dump.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split(':').collect();
+            let (account, hash) = match parts.as_slice() {
+                [account, hash] => (*account, hash.trim_start_matches("$NT$")),
+                [account, _rid, _lm, hash, ..] => (*account, *hash),
+                _ => {
+                    return Err(HibpError::InvalidInput(format!(
+                        "Unrecognised NT hash dump format on line {}",
+                        index + 1
+                    )));
+                }
+            };
+
+            if hash.len() != 32 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(HibpError::InvalidInput(format!(
+                    "Invalid NT hash on line {}",
+                    index + 1
+                )));
+            }
+
+            entries.push(NtHashEntry {
+                account: account.to_string(),
+                nt_hash: hash.to_ascii_uppercase(),
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Audit result for a single Active Directory account.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NtHashAuditEntry {
+    /// Account name as it appeared in the dump.
+    pub account: String,
+    /// Number of times the account's password appears in the Pwned Passwords corpus.
+    pub count: u64,
+    /// Number of other accounts in the dump sharing the same password.
+    pub shared_with: usize,
+}
+
+impl NtHashAuditEntry {
+    /// Whether the account's password appears in the Pwned Passwords corpus.
+    pub fn is_breached(&self) -> bool {
+        self.count > 0
+    }
+
+    /// Converts the entry into a SARIF result with an English message.
+    pub fn to_sarif_result(&self) -> SarifResult {
+        self.to_sarif_result_with(&MessageCatalog::english())
+    }
+
+    /// Converts the entry into a SARIF result, taking the message from the given catalog.
+    pub fn to_sarif_result_with(&self, catalog: &MessageCatalog) -> SarifResult {
+        SarifResult::new(
+            "hibp/pwned-password".to_string(),
+            SarifLevel::Error,
+            catalog.format(
+                "report.nt_hash.breached",
+                &[("account", &self.account), ("count", &self.count)],
+            ),
+        )
+    }
+
+    /// Converts the entry into an `nt-hash-exposed` event with an English message.
+    pub fn to_event(&self) -> Event {
+        Event::new(
+            "nt-hash-exposed",
+            password_severity(self.count),
+            MessageCatalog::english().format(
+                "report.nt_hash.breached",
+                &[("account", &self.account), ("count", &self.count)],
+            ),
+        )
+        .with_account(self.account.clone())
+        .with_count(self.count)
+    }
+}
+
+/// Report of an Active Directory NT hash audit.
+#[derive(Debug, Clone, Default)]
+pub struct NtHashAuditReport {
+    /// One result per audited account, in input order.
+    pub entries: Vec<NtHashAuditEntry>,
+}
+
+impl NtHashAuditReport {
+    /// Returns the accounts whose password appears in the Pwned Passwords corpus.
+    pub fn breached(&self) -> impl Iterator<Item = &NtHashAuditEntry> {
+        self.entries.iter().filter(|e| e.is_breached())
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Audits Active Directory NT hashes against Pwned Passwords in NTLM mode.
+    ///
+    /// Hashes are grouped by prefix so each range is fetched only once, which keeps the
+    /// number of requests bounded even for domains with tens of thousands of accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - Accounts and hashes, e.g. from [`NtHashEntry::parse_dump`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{HaveIBeenPwned, NtHashEntry};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let dump = std::fs::read_to_string("ntds-hashes.txt")?;
+    /// let entries = NtHashEntry::parse_dump(&dump)?;
+    /// let report = hibp.audit_nt_hashes(&entries).await?;
+    /// for entry in report.breached() {
+    ///     println!("{} uses a breached password ({} times)", entry.account, entry.count);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn audit_nt_hashes(
+        &self,
+        entries: &[NtHashEntry],
+    ) -> Result<NtHashAuditReport, HibpError> {
+        let hashes = secret(
+            entries
+                .iter()
+                .map(|e| e.nt_hash.clone())
+                .collect::<Vec<String>>(),
+        );
+
+        let mut usage: HashMap<&str, usize> = HashMap::new();
+        for hash in hashes.iter() {
+            *usage.entry(hash.as_str()).or_default() += 1;
+        }
+
+        let counts = self.count_hashes(&hashes, RangeMode::Ntlm).await?;
+
+        let entries = entries
+            .iter()
+            .zip(counts)
+            .map(|(entry, count)| NtHashAuditEntry {
+                account: entry.account.clone(),
+                count,
+                shared_with: usage[entry.nt_hash.as_str()] - 1,
+            })
+            .collect();
+
+        Ok(NtHashAuditReport { entries })
+    }
+
+    /// Audits Active Directory NT hashes, writing each result to a sink as soon as its range
+    /// is fetched.
+    ///
+    /// Like [`HaveIBeenPwned::audit_nt_hashes`], but results are not collected in memory,
+    /// so large domains can be audited straight into a file or another [`ResultSink`].
+    /// Results arrive grouped by hash prefix rather than in input order. The sink is
+    /// flushed but not closed. Returns the number of breached accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - Accounts and hashes, e.g. from [`NtHashEntry::parse_dump`].
+    /// * `sink` - Destination of the results.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{CsvSink, HaveIBeenPwned, NtHashEntry, ResultSink};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let entries = NtHashEntry::parse_dump(&std::fs::read_to_string("ntds-hashes.txt")?)?;
+    /// let mut sink = CsvSink::new(std::fs::File::create("audit.csv")?);
+    /// let breached = hibp.audit_nt_hashes_into(&entries, &mut sink).await?;
+    /// sink.close().await?;
+    /// eprintln!("{} accounts use a breached password", breached);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn audit_nt_hashes_into<S: ResultSink<NtHashAuditEntry>>(
+        &self,
+        entries: &[NtHashEntry],
+        sink: &mut S,
+    ) -> Result<usize, HibpError> {
+        let hashes = secret(
+            entries
+                .iter()
+                .map(|e| e.nt_hash.clone())
+                .collect::<Vec<String>>(),
+        );
+
+        let mut usage: HashMap<&str, usize> = HashMap::new();
+        for hash in hashes.iter() {
+            *usage.entry(hash.as_str()).or_default() += 1;
+        }
+
+        let mut breached = 0;
+        for (prefix, indices) in group_by_prefix(&hashes) {
+            let range = self
+                .fetch_compact_range(prefix, RangeMode::Ntlm, true)
+                .await?;
+            for index in indices {
+                let entry = &entries[index];
+                let result = NtHashAuditEntry {
+                    account: entry.account.clone(),
+                    count: range_count(&range, &entry.nt_hash[5..]),
+                    shared_with: usage[entry.nt_hash.as_str()] - 1,
+                };
+                if result.is_breached() {
+                    breached += 1;
+                }
+                sink.write(result).await?;
+            }
+        }
+        sink.flush().await?;
+
+        Ok(breached)
+    }
+}
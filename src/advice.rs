@@ -0,0 +1,116 @@
+use crate::MessageCatalog;
+
+/// How serious a password's exposure is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AdviceSeverity {
+    /// The password was not found in any breach.
+    None,
+    /// The password was found a handful of times.
+    Low,
+    /// The password was found repeatedly.
+    Medium,
+    /// The password is one of the most commonly breached passwords.
+    High,
+}
+
+/// Breach-count thresholds used to turn a count into [`PasswordAdvice`].
+///
+/// The defaults hard-block any password that appears in a breach, as recommended by
+/// NIST SP 800-63B, and grade severity at 10 and 1,000 occurrences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdviceThresholds {
+    /// Count from which the severity is [`AdviceSeverity::Medium`].
+    pub medium: u64,
+    /// Count from which the severity is [`AdviceSeverity::High`].
+    pub high: u64,
+    /// Count from which the password should be rejected outright.
+    pub block: u64,
+}
+
+impl Default for AdviceThresholds {
+    fn default() -> Self {
+        AdviceThresholds {
+            medium: 10,
+            high: 1_000,
+            block: 1,
+        }
+    }
+}
+
+impl AdviceThresholds {
+    /// Sets the count from which the password should be rejected outright.
+    ///
+    /// Use a higher value to only warn about rarely breached passwords.
+    #[must_use]
+    pub fn with_block(mut self, block: u64) -> Self {
+        self.block = block;
+        self
+    }
+}
+
+/// Structured guidance for a signup or password-change flow.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{AdviceSeverity, AdviceThresholds, PasswordAdvice};
+///
+/// let advice = PasswordAdvice::from_count(3, &AdviceThresholds::default().with_block(10));
+/// assert_eq!(advice.severity, AdviceSeverity::Low);
+/// assert!(!advice.hard_block);
+/// assert_eq!(advice.message_key, "password.breached.low");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordAdvice {
+    /// Number of times the password appears in the Pwned Passwords corpus.
+    pub count: u64,
+    /// How serious the exposure is.
+    pub severity: AdviceSeverity,
+    /// Identifier of the message to show the user.
+    pub message_key: &'static str,
+    /// Whether the password should be rejected instead of merely warned about.
+    pub hard_block: bool,
+}
+
+impl PasswordAdvice {
+    /// Builds guidance from a breach count.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Number of times the password appears in the Pwned Passwords corpus.
+    /// * `thresholds` - Thresholds for grading severity and blocking.
+    pub fn from_count(count: u64, thresholds: &AdviceThresholds) -> Self {
+        let severity = if count == 0 {
+            AdviceSeverity::None
+        } else if count >= thresholds.high {
+            AdviceSeverity::High
+        } else if count >= thresholds.medium {
+            AdviceSeverity::Medium
+        } else {
+            AdviceSeverity::Low
+        };
+
+        let message_key = match severity {
+            AdviceSeverity::None => "password.not_breached",
+            AdviceSeverity::Low => "password.breached.low",
+            AdviceSeverity::Medium => "password.breached.medium",
+            AdviceSeverity::High => "password.breached.high",
+        };
+
+        PasswordAdvice {
+            count,
+            severity,
+            message_key,
+            hard_block: count > 0 && count >= thresholds.block,
+        }
+    }
+
+    /// Renders the user-facing message for this advice from the given catalog.
+    ///
+    /// # Arguments
+    ///
+    /// * `catalog` - Catalog to take the message from, e.g. [`MessageCatalog::english`].
+    pub fn message(&self, catalog: &MessageCatalog) -> String {
+        catalog.format(self.message_key, &[("count", &self.count)])
+    }
+}
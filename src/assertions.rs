@@ -0,0 +1,48 @@
+//! Compile-time checks that the client and its futures can be shared across threads.
+//!
+//! Multi-threaded servers keep one client in an `Arc` and call it from spawned tasks.
+//! A non-`Send` field or a non-`Send` value held across an `.await` would silently break
+//! that, so these checks fail the build instead.
+
+use crate::{
+    BatchExecutor, BreachCatalogue, ClientStats, CompactPwnedPassword, HaveIBeenPwned, HibpError,
+    RequestBlocked, ResponseTooLarge, SubscriptionRequired,
+};
+
+const fn assert_send_sync<T: Send + Sync>() {}
+
+const _: () = {
+    assert_send_sync::<HaveIBeenPwned>();
+    assert_send_sync::<HibpError>();
+    assert_send_sync::<std::sync::Arc<HaveIBeenPwned>>();
+    assert_send_sync::<BatchExecutor>();
+    assert_send_sync::<BreachCatalogue>();
+    assert_send_sync::<ClientStats>();
+    assert_send_sync::<CompactPwnedPassword>();
+    assert_send_sync::<RequestBlocked>();
+    assert_send_sync::<ResponseTooLarge>();
+    assert_send_sync::<SubscriptionRequired>();
+};
+
+fn assert_send<T: Send>(_: &T) {}
+
+/// Never called; only type-checks that the futures of common calls are `Send`.
+#[allow(dead_code, clippy::let_underscore_future)]
+fn futures_are_send(hibp: &HaveIBeenPwned) {
+    assert_send(&hibp.get_breaches_for_account(""));
+    assert_send(&hibp.get_all_breaches());
+    assert_send(&hibp.get_breach_by_name(""));
+    assert_send(&hibp.get_latest_breach());
+    assert_send(&hibp.get_breaches_added_since(Default::default()));
+    assert_send(&hibp.get_most_recent_breaches(0));
+    assert_send(&hibp.get_breach_catalogue());
+    assert_send(&hibp.get_pastes_for_account(""));
+    assert_send(&hibp.get_subscription_status());
+    assert_send(&hibp.check_password(""));
+    assert_send(&hibp.check_password_padded(""));
+    assert_send(&hibp.check_password_bytes(b""));
+    assert_send(&hibp.search_password_range(""));
+    assert_send(&hibp.search_password_range_compact(""));
+    #[cfg(feature = "stream")]
+    assert_send(&hibp.stream_all_breaches());
+}
@@ -0,0 +1,323 @@
+use crate::Paste;
+use crate::breach::now_utc;
+use crate::{Breach, HaveIBeenPwned, HibpError, Migrations};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// State persisted between runs of a scheduled audit.
+///
+/// Timestamps are whole seconds in UTC, like [`Breach::added_date`], and are saved in the
+/// `YYYY-MM-DDTHH:MM:SSZ` format HIBP uses. The start of the previous run can be passed
+/// straight to [`HaveIBeenPwned::get_breaches_for_account_since`].
+///
+/// Saved files are stamped with [`AuditState::SCHEMA_VERSION`], and files written by older
+/// versions of the crate are upgraded when loaded, see [`Migrations`]. Keep the state
+/// somewhere else than a JSON file through a [`StateStore`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use hibp_rs::{AuditState, HaveIBeenPwned};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let hibp = HaveIBeenPwned::new("your_api_key");
+/// let mut state = AuditState::load("audit-state.json")?;
+/// let since = state.start_run();
+///
+/// let new_breaches = hibp
+///     .get_breaches_for_account_since("test@example.com", since)
+///     .await?;
+/// println!("{} new breaches since the last run", new_breaches.len());
+///
+/// state.save("audit-state.json")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AuditState {
+    /// When the previous audit run started, if there was one.
+    pub last_run: Option<DateTime<Utc>>,
+    /// When each breach was first observed for an account, keyed by account and breach name.
+    #[serde(default)]
+    pub first_seen: BTreeMap<String, BTreeMap<String, DateTime<Utc>>>,
+    /// Pastes observed for each account, keyed by account, as `Source/Id`.
+    #[serde(default)]
+    pub seen_pastes: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Field of saved state files holding their schema version.
+const SCHEMA_VERSION_FIELD: &str = "schema_version";
+
+/// Storage for [`AuditState`] between runs.
+///
+/// Paths keep the state in a JSON file, like [`AuditState::load`] and
+/// [`AuditState::save`]. `SqliteStore` (`sqlite` feature) keeps it in a database next to
+/// cached responses.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{AuditState, StateStore};
+/// use std::path::PathBuf;
+///
+/// # fn example() -> Result<(), hibp_rs::HibpError> {
+/// let store: Box<dyn StateStore> = Box::new(PathBuf::from("audit-state.json"));
+/// let mut state = store.load_state()?;
+/// state.start_run();
+/// store.save_state(&state)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait StateStore: std::fmt::Debug + Send + Sync {
+    /// Loads the saved state, or an empty state if none was saved yet.
+    fn load_state(&self) -> Result<AuditState, HibpError>;
+
+    /// Saves the state, replacing the saved one.
+    fn save_state(&self, state: &AuditState) -> Result<(), HibpError>;
+}
+
+impl StateStore for PathBuf {
+    fn load_state(&self) -> Result<AuditState, HibpError> {
+        AuditState::load(self)
+    }
+
+    fn save_state(&self, state: &AuditState) -> Result<(), HibpError> {
+        state.save(self)
+    }
+}
+
+/// Lets callers keep a handle to a store they also use elsewhere, e.g. as a cache.
+impl<S: StateStore + ?Sized> StateStore for std::sync::Arc<S> {
+    fn load_state(&self) -> Result<AuditState, HibpError> {
+        (**self).load_state()
+    }
+
+    fn save_state(&self, state: &AuditState) -> Result<(), HibpError> {
+        (**self).save_state(state)
+    }
+}
+
+/// A breach together with the time it was first observed for an account.
+#[derive(Debug)]
+pub struct ObservedBreach {
+    /// The breach as returned by the API.
+    pub breach: Breach,
+    /// When the breach was first observed for the account.
+    ///
+    /// Compare with [`Breach::added_date`] to measure how quickly new listings are picked up.
+    pub first_seen: DateTime<Utc>,
+    /// Whether this is the first time the breach was observed for the account.
+    pub is_new: bool,
+}
+
+impl AuditState {
+    /// Schema version of saved state files. Files without a version are version 0.
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    /// Returns the migrations the crate applies to state files of older schema versions.
+    ///
+    /// Add steps to them and load with [`AuditState::load_with`] to customize upgrades.
+    pub fn migrations() -> Migrations<serde_json::Value> {
+        // Version 1 only added the version stamp
+        Migrations::new()
+    }
+
+    /// Loads the state from a JSON file, returning an empty state if the file does not exist.
+    ///
+    /// Files of older schema versions are upgraded with [`AuditState::migrations`], and
+    /// files of newer versions are rejected with [`HibpError::InvalidInput`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the state file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, HibpError> {
+        Self::load_with(path, &Self::migrations())
+    }
+
+    /// Loads the state from a JSON file like [`AuditState::load`], upgrading files of
+    /// older schema versions with custom migrations.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the state file.
+    /// * `migrations` - Steps upgrading the JSON document of older versions.
+    pub fn load_with<P: AsRef<Path>>(
+        path: P,
+        migrations: &Migrations<serde_json::Value>,
+    ) -> Result<Self, HibpError> {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(AuditState::default());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Self::from_json(&data, migrations)
+    }
+
+    /// Parses a JSON document written by [`AuditState::to_json`], upgrading it from older
+    /// schema versions.
+    pub(crate) fn from_json(
+        data: &[u8],
+        migrations: &Migrations<serde_json::Value>,
+    ) -> Result<Self, HibpError> {
+        let mut document: serde_json::Value = serde_json::from_slice(data)?;
+        let version = document
+            .as_object_mut()
+            .and_then(|fields| fields.remove(SCHEMA_VERSION_FIELD))
+            .and_then(|version| version.as_u64())
+            .unwrap_or(0);
+        let version = u32::try_from(version).unwrap_or(u32::MAX);
+        migrations.migrate(&mut document, version, Self::SCHEMA_VERSION)?;
+        Ok(serde_json::from_value(document)?)
+    }
+
+    /// Returns the state as a JSON document stamped with [`AuditState::SCHEMA_VERSION`].
+    pub(crate) fn to_json(&self) -> Result<Vec<u8>, HibpError> {
+        let mut document = serde_json::to_value(self)?;
+        if let Some(fields) = document.as_object_mut() {
+            fields.insert(SCHEMA_VERSION_FIELD.into(), Self::SCHEMA_VERSION.into());
+        }
+        Ok(serde_json::to_vec_pretty(&document)?)
+    }
+
+    /// Writes the state to a JSON file, stamped with [`AuditState::SCHEMA_VERSION`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the state file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), HibpError> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Records the start of a new run and returns when the previous one started.
+    ///
+    /// The start time rather than the end time is stored, so breaches added while a run is
+    /// in progress are reported by the next one.
+    pub fn start_run(&mut self) -> Option<DateTime<Utc>> {
+        self.last_run.replace(now_utc())
+    }
+
+    /// Returns when a breach was first observed for an account, if it was observed before.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account the breach was observed for.
+    /// * `breach_name` - The [`Breach::name`] of the breach.
+    pub fn first_seen(&self, account: &str, breach_name: &str) -> Option<DateTime<Utc>> {
+        self.first_seen
+            .get(account)
+            .and_then(|breaches| breaches.get(breach_name))
+            .copied()
+    }
+
+    /// Records the breaches of an account, keeping the first-seen time of known breaches.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account the breaches were returned for.
+    /// * `breaches` - Breaches returned by the API for the account.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{AuditState, HaveIBeenPwned};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let mut state = AuditState::load("audit-state.json")?;
+    /// let breaches = hibp.get_breaches_for_account("test@example.com").await?;
+    /// for observed in state.observe("test@example.com", breaches) {
+    ///     println!(
+    ///         "{} added {}, first seen {}",
+    ///         observed.breach.name, observed.breach.added_date, observed.first_seen
+    ///     );
+    /// }
+    /// state.save("audit-state.json")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn observe(&mut self, account: &str, breaches: Vec<Breach>) -> Vec<ObservedBreach> {
+        let now = now_utc();
+        let seen = self.first_seen.entry(account.to_string()).or_default();
+
+        breaches
+            .into_iter()
+            .map(|breach| {
+                let is_new = !seen.contains_key(&breach.name);
+                let first_seen = *seen.entry(breach.name.clone()).or_insert(now);
+                ObservedBreach {
+                    breach,
+                    first_seen,
+                    is_new,
+                }
+            })
+            .collect()
+    }
+
+    /// Records the pastes of an account and returns those not observed before.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The account the pastes were returned for.
+    /// * `pastes` - Pastes returned by the API for the account.
+    pub fn observe_pastes(&mut self, account: &str, pastes: Vec<Paste>) -> Vec<Paste> {
+        let seen = self.seen_pastes.entry(account.to_string()).or_default();
+        pastes
+            .into_iter()
+            .filter(|paste| seen.insert(format!("{}/{}", paste.source, paste.id)))
+            .collect()
+    }
+}
+
+impl Breach {
+    /// Whether the breach was added to HIBP after the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - The time to compare [`Breach::added_date`] with.
+    pub fn added_after(&self, since: DateTime<Utc>) -> bool {
+        self.added_date > since
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Gets the breaches for an account that were added to HIBP after the given time.
+    ///
+    /// Filtering happens client-side on [`Breach::added_date`], so recurring audits only
+    /// report new findings. With `since` set to `None` all breaches are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The email address to search for.
+    /// * `since` - Only return breaches added after this time, e.g. from [`AuditState::start_run`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let since = "2024-01-01T00:00:00Z".parse()?;
+    /// let breaches = hibp
+    ///     .get_breaches_for_account_since("test@example.com", Some(since))
+    ///     .await?;
+    /// println!("{:?}", breaches);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_breaches_for_account_since(
+        &self,
+        account: &str,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Breach>, HibpError> {
+        let breaches = self.get_breaches_for_account(account).await?;
+        Ok(match since {
+            Some(since) => breaches
+                .into_iter()
+                .filter(|b| b.added_after(since))
+                .collect(),
+            None => breaches,
+        })
+    }
+}
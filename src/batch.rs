@@ -0,0 +1,197 @@
+use crate::progress::{Progress, ProgressHook, ProgressTracker};
+use crate::{HaveIBeenPwned, HibpError};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use tokio::task::JoinSet;
+
+/// How a [`BatchExecutor`] reacts to a failing item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatchMode {
+    /// Abort all remaining work and return the first error.
+    FailFast,
+    /// Run every item and report errors per item.
+    #[default]
+    CollectAll,
+}
+
+/// Error of a single batch item.
+///
+/// Keeps the error returned by the operation, so callers can still match on it, e.g. on
+/// [`HibpError::RateLimited`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchError<E = HibpError> {
+    /// Position of the failing item in the input.
+    pub index: usize,
+    /// The error returned by the operation for the item.
+    pub error: E,
+}
+
+impl<E: Display> Display for BatchError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Batch item {} failed: {}", self.index, self.error)
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for BatchError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Runs HIBP operations over many inputs with bounded concurrency.
+///
+/// Every task gets a clone of the client, so all of them share its rate limiter.
+/// Results are returned in input order regardless of completion order.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{BatchExecutor, BatchMode, HaveIBeenPwned};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let hibp = HaveIBeenPwned::new_with_rate_limit("your_api_key", 100);
+/// let executor = BatchExecutor::new(hibp)
+///     .with_max_in_flight(8)
+///     .with_mode(BatchMode::CollectAll);
+///
+/// let accounts = vec!["user1@example.com", "user2@example.com"];
+/// let results = executor
+///     .run(accounts, |hibp, account| async move {
+///         hibp.get_breaches_for_account(account).await
+///     })
+///     .await?;
+/// for result in results {
+///     match result {
+///         Ok(breaches) => println!("{} breaches", breaches.len()),
+///         Err(e) => println!("{}", e),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatchExecutor {
+    client: HaveIBeenPwned,
+    max_in_flight: usize,
+    mode: BatchMode,
+    progress: Option<ProgressHook>,
+}
+
+impl BatchExecutor {
+    /// Creates an executor running at most 4 items at a time in [`BatchMode::CollectAll`] mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client every task receives a clone of.
+    pub fn new(client: HaveIBeenPwned) -> Self {
+        BatchExecutor {
+            client,
+            max_in_flight: 4,
+            mode: BatchMode::CollectAll,
+            progress: None,
+        }
+    }
+
+    /// Sets the maximum number of items in flight at once. Values below 1 are treated as 1.
+    #[must_use]
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Sets how failing items are handled.
+    #[must_use]
+    pub fn with_mode(mut self, mode: BatchMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Calls `hook` with the [`Progress`] of a run after every finished item.
+    ///
+    /// The total is known if the inputs report an exact size, e.g. a `Vec`.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Called with the progress so far; should return quickly.
+    #[must_use]
+    pub fn with_progress<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Progress) + Send + Sync + 'static,
+    {
+        self.progress = Some(ProgressHook::new(hook));
+        self
+    }
+
+    /// Runs `operation` for every input.
+    ///
+    /// In [`BatchMode::FailFast`] mode the first failure aborts all outstanding tasks and is
+    /// returned as `Err`. In [`BatchMode::CollectAll`] mode the outer result is always `Ok`
+    /// and failures are reported per item. A panicking operation panics the whole run.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - Items to process.
+    /// * `operation` - Called with a client clone and one input; runs as a tokio task.
+    pub async fn run<I, T, F, Fut, R, E>(
+        &self,
+        inputs: I,
+        operation: F,
+    ) -> Result<Vec<Result<R, BatchError<E>>>, BatchError<E>>
+    where
+        I: IntoIterator<Item = T>,
+        T: Send + 'static,
+        F: Fn(HaveIBeenPwned, T) -> Fut,
+        Fut: Future<Output = Result<R, E>> + Send + 'static,
+        R: Send + 'static,
+        E: Send + 'static,
+    {
+        let mut inputs = inputs.into_iter().enumerate();
+        let total = match inputs.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower as u64),
+            _ => None,
+        };
+        let mut tracker = ProgressTracker::new(self.progress.clone(), total);
+        let mut results: Vec<Option<Result<R, BatchError<E>>>> = Vec::new();
+        let mut tasks = JoinSet::new();
+        let mut task_indices = HashMap::new();
+
+        loop {
+            while tasks.len() < self.max_in_flight {
+                let Some((index, input)) = inputs.next() else {
+                    break;
+                };
+                results.push(None);
+                let future = operation(self.client.clone(), input);
+                let handle = tasks.spawn(async move {
+                    future.await.map_err(|error| BatchError { index, error })
+                });
+                task_indices.insert(handle.id(), index);
+            }
+
+            let Some(joined) = tasks.join_next_with_id().await else {
+                break;
+            };
+            let (index, result) = match joined {
+                Ok((id, result)) => (task_indices[&id], result),
+                Err(e) => match e.try_into_panic() {
+                    Ok(panic) => std::panic::resume_unwind(panic),
+                    // Tasks are only aborted right before returning
+                    Err(e) => unreachable!("batch task was cancelled: {}", e),
+                },
+            };
+
+            let result = match (self.mode, result) {
+                (BatchMode::FailFast, Err(error)) => {
+                    tasks.abort_all();
+                    return Err(error);
+                }
+                (_, result) => result,
+            };
+            results[index] = Some(result);
+            tracker.complete(0);
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+}
@@ -0,0 +1,142 @@
+use hibp_rs::{Breach, HaveIBeenPwned, HibpError};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: hibp <command>
+
+Commands:
+  breach account <email>   List the breaches an account appears in
+  breach latest            Show the most recently added breach
+  password check           Check a password typed at a no-echo prompt
+  subscription status      Show the subscription of the API key
+
+The API key is read from the HIBP_API_KEY environment variable, or from an
+`api_key = ...` line in the config file: $HIBP_CONFIG, else
+$XDG_CONFIG_HOME/hibp/config, else ~/.config/hibp/config. `breach latest` and
+`password check` work without a key.";
+
+/// A command-line client for Have I Been Pwned.
+///
+/// Usage: `cargo run --features cli --bin hibp -- breach account test@example.com`
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    if matches!(args.as_slice(), [] | ["help" | "-h" | "--help", ..]) {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match runtime.block_on(run(&args)) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs a command and returns the exit code.
+async fn run(args: &[&str]) -> Result<ExitCode, HibpError> {
+    match args {
+        ["breach", "account", account] => {
+            let breaches = client()?.get_breaches_for_account(account).await?;
+            if breaches.is_empty() {
+                println!("{} was not found in any breach", account);
+            }
+            for breach in &breaches {
+                print_breach(breach);
+            }
+        }
+        ["breach", "latest"] => print_breach(&client()?.get_latest_breach().await?),
+        ["password", "check"] => {
+            let count = client()?.prompt_and_check("Password to check: ").await?;
+            if count > 0 {
+                println!("This password was found {} times in data breaches", count);
+                // Lets scripts reject the password
+                return Ok(ExitCode::from(1));
+            }
+            println!("This password was not found in any data breach");
+        }
+        ["subscription", "status"] => {
+            let status = client()?.get_subscription_status().await?;
+            println!("Subscription: {}", status.subscription_name);
+            println!("Description: {}", status.description);
+            println!("Subscribed until: {}", status.subscribed_until);
+            println!("Requests per minute: {}", status.rpm);
+            println!(
+                "Domain search limit: {} breached accounts",
+                status.domain_search_max_breached_accounts
+            );
+            println!("Stealer logs: {}", status.includes_stealer_logs);
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            return Ok(ExitCode::from(2));
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Builds a client with the configured API key, or a free-tier client without one.
+fn client() -> Result<HaveIBeenPwned, HibpError> {
+    let builder = HaveIBeenPwned::builder().with_user_agent("hibp-rs-cli");
+    match api_key()? {
+        Some(api_key) => builder.with_api_key(api_key).build(),
+        None => builder.with_free_tier().build(),
+    }
+}
+
+/// Reads the API key from the environment or the config file.
+fn api_key() -> Result<Option<String>, HibpError> {
+    if let Ok(api_key) = std::env::var("HIBP_API_KEY")
+        && !api_key.trim().is_empty()
+    {
+        return Ok(Some(api_key.trim().to_string()));
+    }
+    let Some(path) = config_path() else {
+        return Ok(None);
+    };
+    let config = match std::fs::read_to_string(&path) {
+        Ok(config) => config,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(config.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "api_key").then(|| value.trim().trim_matches('"').to_string())
+    }))
+}
+
+/// Returns where the config file is expected.
+fn config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("HIBP_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("hibp").join("config"))
+}
+
+fn print_breach(breach: &Breach) {
+    println!(
+        "{} ({}): breached {}, added {}, {} accounts",
+        breach.title,
+        breach.domain,
+        breach.breach_date,
+        breach.added_date.format("%Y-%m-%d"),
+        breach.pwn_count
+    );
+    println!("  Exposed: {}", breach.data_classes.join(", "));
+}
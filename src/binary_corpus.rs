@@ -0,0 +1,214 @@
+#[cfg(feature = "mmap")]
+use crate::hash::{NTLM_HEX_LEN, PREFIX_LEN, SHA1_HEX_LEN};
+#[cfg(feature = "mmap")]
+use crate::password::pack_hex;
+#[cfg(feature = "mmap")]
+use crate::{HibpError, PwnedPassword};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// First bytes of a binary corpus file.
+pub(crate) const MAGIC: &[u8; 8] = b"HIBPCRP1";
+
+#[cfg(feature = "mmap")]
+/// Size of the header: magic, suffix length in hex characters and reserved bytes.
+pub(crate) const HEADER_LEN: usize = 16;
+
+#[cfg(feature = "mmap")]
+/// Number of hash prefixes, and so of ranges (16^5).
+pub(crate) const PREFIXES: usize = 1 << (4 * PREFIX_LEN);
+
+#[cfg(feature = "mmap")]
+/// Size of the index: the number of records before each prefix, plus the total.
+pub(crate) const INDEX_LEN: usize = (PREFIXES + 1) * 8;
+
+#[cfg(feature = "mmap")]
+/// Returns the size of a record: the packed suffix and a little-endian `u32` count.
+pub(crate) fn record_len(suffix_len: usize) -> usize {
+    suffix_len.div_ceil(2) + 4
+}
+
+/// Returns the header of a corpus whose hash suffixes are `suffix_len` hex characters long.
+#[cfg(feature = "full")]
+pub(crate) fn header(suffix_len: usize) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[..MAGIC.len()].copy_from_slice(MAGIC);
+    header[MAGIC.len()] = suffix_len as u8;
+    header
+}
+
+/// Converts a range body of `SUFFIX:COUNT` lines into sorted records.
+///
+/// Lines with another suffix length or an invalid count are skipped. Returns the number
+/// of records and their bytes.
+#[cfg(feature = "full")]
+pub(crate) fn encode_range(suffix_len: usize, body: &[u8]) -> (u64, Vec<u8>) {
+    let width = suffix_len.div_ceil(2);
+    let mut records: Vec<([u8; 18], u32)> = body
+        .split(|&b| b == b'\n')
+        .filter_map(|line| {
+            let (suffix, count) = std::str::from_utf8(line).ok()?.trim().split_once(':')?;
+            if suffix.len() != suffix_len {
+                return None;
+            }
+            let count = count.parse::<u64>().ok()?.min(u32::MAX as u64) as u32;
+            Some((pack_hex(suffix)?, count))
+        })
+        .collect();
+    records.sort_unstable();
+
+    let mut bytes = Vec::with_capacity(records.len() * record_len(suffix_len));
+    for (suffix, count) in &records {
+        bytes.extend_from_slice(&suffix[..width]);
+        bytes.extend_from_slice(&count.to_le_bytes());
+    }
+    (records.len() as u64, bytes)
+}
+
+/// Whether a file starts like a binary corpus.
+pub(crate) fn detect(path: &Path) -> std::io::Result<bool> {
+    let mut magic = [0u8; MAGIC.len()];
+    match File::open(path)?.read_exact(&mut magic) {
+        Ok(()) => Ok(&magic == MAGIC),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// A memory-mapped binary corpus, as written by
+/// [`crate::CorpusDownloader::download_to_binary`].
+///
+/// The file holds a header, an index with the number of records before each prefix, and
+/// fixed-width records of a packed hash suffix and a count, sorted by suffix per prefix.
+#[cfg(feature = "mmap")]
+#[derive(Debug)]
+pub(crate) struct BinaryCorpus {
+    map: memmap2::Mmap,
+    suffix_len: usize,
+}
+
+#[cfg(feature = "mmap")]
+impl BinaryCorpus {
+    /// Maps a binary corpus file, checking that its size matches its index.
+    pub(crate) fn open(path: &Path) -> Result<Self, HibpError> {
+        let file = File::open(path)?;
+        // SAFETY: the map is only read. Like any memory-mapped file, it must not be
+        // truncated or rewritten while the store is open, which the downloader avoids by
+        // writing new corpora to a new file.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        let corrupt =
+            || HibpError::InvalidInput(format!("Corrupt binary corpus: {}", path.display()));
+
+        let suffix_len = *map.get(MAGIC.len()).ok_or_else(corrupt)? as usize;
+        if suffix_len != SHA1_HEX_LEN - PREFIX_LEN && suffix_len != NTLM_HEX_LEN - PREFIX_LEN {
+            return Err(corrupt());
+        }
+        let corpus = BinaryCorpus { map, suffix_len };
+        let records = corpus.index(PREFIXES).ok_or_else(corrupt)?;
+        let expected =
+            (HEADER_LEN + INDEX_LEN) as u64 + records.saturating_mul(record_len(suffix_len) as u64);
+        if corpus.map.len() as u64 != expected {
+            return Err(corrupt());
+        }
+        Ok(corpus)
+    }
+
+    /// Returns the number of records before a prefix.
+    fn index(&self, prefix: usize) -> Option<u64> {
+        let start = HEADER_LEN + prefix * 8;
+        let bytes = self.map.get(start..start + 8)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Returns the records of a prefix, given as a number.
+    fn records(&self, prefix: usize) -> &[u8] {
+        let width = record_len(self.suffix_len) as u64;
+        let offset = |records: u64| {
+            usize::try_from((HEADER_LEN + INDEX_LEN) as u64 + records.saturating_mul(width))
+                .unwrap_or(usize::MAX)
+        };
+        let first = self.index(prefix).unwrap_or_default();
+        let last = self.index(prefix + 1).unwrap_or_default();
+        self.map
+            .get(offset(first)..offset(last))
+            .unwrap_or_default()
+    }
+
+    /// Returns how often a hash appears, binary searching the records of its prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The uppercase 5-character hash prefix.
+    /// * `suffix` - The uppercase rest of the hash.
+    pub(crate) fn count(&self, prefix: &str, suffix: &str) -> Result<u64, HibpError> {
+        if suffix.len() != self.suffix_len {
+            return Err(HibpError::InvalidInput(
+                "Hash type doesn't match the binary corpus".into(),
+            ));
+        }
+        let Some(packed) = pack_hex(suffix) else {
+            return Ok(0);
+        };
+        let width = record_len(self.suffix_len);
+        let target = &packed[..width - 4];
+        let records = self.records(prefix_number(prefix)?);
+        let (mut lo, mut hi) = (0, records.len() / width);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let (suffix, count) = records[mid * width..(mid + 1) * width].split_at(width - 4);
+            match suffix.cmp(target) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Ok(read_count(count)),
+            }
+        }
+        Ok(0)
+    }
+
+    /// Returns all hashes of a prefix.
+    pub(crate) fn range(&self, prefix: &str) -> Result<Vec<PwnedPassword>, HibpError> {
+        let width = record_len(self.suffix_len);
+        Ok(self
+            .records(prefix_number(prefix)?)
+            .chunks_exact(width)
+            .map(|record| {
+                let (suffix, count) = record.split_at(width - 4);
+                PwnedPassword {
+                    hash_suffix: unpack_hex(suffix, self.suffix_len),
+                    count: read_count(count),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(feature = "mmap")]
+/// Reads the little-endian count of a record.
+fn read_count(bytes: &[u8]) -> u64 {
+    u32::from_le_bytes(bytes.try_into().unwrap_or_default()) as u64
+}
+
+#[cfg(feature = "mmap")]
+/// Parses a hex hash prefix into its number.
+fn prefix_number(prefix: &str) -> Result<usize, HibpError> {
+    usize::from_str_radix(prefix, 16)
+        .ok()
+        .filter(|&prefix| prefix < PREFIXES)
+        .ok_or_else(|| {
+            HibpError::InvalidInput("Hash prefix must be exactly 5 hex characters".into())
+        })
+}
+
+#[cfg(feature = "mmap")]
+/// Formats the first `len` nibbles of packed hex as uppercase hex.
+fn unpack_hex(packed: &[u8], len: usize) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+    (0..len)
+        .map(|i| {
+            let byte = packed[i / 2];
+            let nibble = if i % 2 == 0 { byte >> 4 } else { byte & 0x0F };
+            DIGITS[nibble as usize] as char
+        })
+        .collect()
+}
@@ -0,0 +1,102 @@
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, SERVER};
+use std::fmt::Display;
+
+/// Why a request was blocked before reaching the HIBP API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockReason {
+    /// HIBP rejected the User-Agent header, usually because it is missing or generic.
+    UserAgent,
+    /// Cloudflare answered with a bot challenge or block page.
+    Cloudflare,
+}
+
+/// Error returned when HIBP or Cloudflare blocks a request regardless of the API key.
+///
+/// These 403 responses are easily mistaken for an invalid API key. The error message
+/// carries remediation hints. Returned as [`crate::HibpError::Blocked`]:
+///
+/// ```no_run
+/// # use hibp_rs::{BlockReason, HaveIBeenPwned, HibpError};
+/// # async fn example() {
+/// let hibp = HaveIBeenPwned::new("your_api_key");
+/// if let Err(e) = hibp.get_breaches_for_account("test@example.com").await {
+///     if let HibpError::Blocked(blocked) = e {
+///         if blocked.reason == BlockReason::UserAgent {
+///             println!("Set a descriptive user agent with with_user_agent");
+///         }
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestBlocked {
+    /// Endpoint the request was sent to, e.g. `breachedaccount`.
+    pub endpoint: String,
+    /// Response status, usually 403 Forbidden.
+    pub status: u16,
+    /// Why the request was blocked.
+    pub reason: BlockReason,
+}
+
+impl Display for RequestBlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.reason {
+            BlockReason::UserAgent => write!(
+                f,
+                "HIBP rejected the user agent of the {} request (status {}). This is not an \
+                 API key problem: set a descriptive user agent naming your application with \
+                 HaveIBeenPwned::with_user_agent",
+                self.endpoint, self.status
+            ),
+            BlockReason::Cloudflare => write!(
+                f,
+                "Cloudflare blocked the {} request with a bot challenge (status {}). This is \
+                 not an API key problem: use a descriptive user agent, avoid VPN or hosting \
+                 IP ranges Cloudflare distrusts, and reduce the request rate",
+                self.endpoint, self.status
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RequestBlocked {}
+
+/// Markers of Cloudflare challenge and block pages.
+const CLOUDFLARE_MARKERS: &[&str] = &[
+    "cf-chl",
+    "challenge-platform",
+    "Just a moment...",
+    "Attention Required! | Cloudflare",
+];
+
+/// Recognises user-agent blocks and Cloudflare challenges among error responses.
+pub(crate) fn detect(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Option<BlockReason> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::SERVICE_UNAVAILABLE {
+        return None;
+    }
+
+    let header_is = |name: &str, value: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case(value))
+    };
+    let is_html = headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    let body = String::from_utf8_lossy(body);
+
+    if header_is("cf-mitigated", "challenge")
+        || (header_is(SERVER.as_str(), "cloudflare")
+            && is_html
+            && CLOUDFLARE_MARKERS.iter().any(|m| body.contains(m)))
+    {
+        return Some(BlockReason::Cloudflare);
+    }
+    if status == StatusCode::FORBIDDEN && body.to_ascii_lowercase().contains("user agent") {
+        return Some(BlockReason::UserAgent);
+    }
+    None
+}
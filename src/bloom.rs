@@ -0,0 +1,298 @@
+use crate::{HaveIBeenPwned, HibpError, hash};
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::BufRead;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+/// First bytes of a persisted filter.
+const MAGIC: &[u8; 8] = b"HIBPBLM1";
+
+/// Length of the header of a persisted filter: magic, bit count and hash count.
+const HEADER_LEN: usize = 8 + 8 + 4;
+
+/// Compact probabilistic pre-filter for the Pwned Passwords corpus.
+///
+/// Answers "definitely not pwned" locally for most passwords, so high-traffic signup flows
+/// only query the range API when the filter reports a possible hit. False positives
+/// happen at the configured rate; false negatives can't, as long as the filter was built
+/// from an up-to-date corpus.
+///
+/// Build it once from a corpus downloaded with [`crate::CorpusDownloader`], save it, and
+/// load it at startup. At a 1% false positive rate the filter takes about 1.2 bytes per
+/// hash. [`PasswordBloomFilter::maybe_pwned`] expects a filter built from a SHA-1 corpus;
+/// look up hashes in a filter built from an NTLM corpus with
+/// [`PasswordBloomFilter::contains_hash`].
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{HaveIBeenPwned, PasswordBloomFilter};
+///
+/// # async fn example() -> Result<(), hibp_rs::HibpError> {
+/// let filter = PasswordBloomFilter::from_corpus("pwnedpasswords.txt", 0.01)?;
+/// filter.save("pwnedpasswords.bloom")?;
+///
+/// let filter = PasswordBloomFilter::load("pwnedpasswords.bloom")?;
+/// let hibp = HaveIBeenPwned::new_free_tier();
+/// let count = hibp.check_password_prefiltered(&filter, "correct horse").await?;
+/// println!("This password was found {} times in data breaches", count);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordBloomFilter {
+    words: Vec<u64>,
+    bits: u64,
+    hashes: u32,
+}
+
+impl PasswordBloomFilter {
+    /// Creates an empty filter sized for a number of hashes and a false positive rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_hashes` - Number of hashes that will be inserted.
+    /// * `false_positive_rate` - Acceptable rate of false positives, e.g. `0.01`. Clamped
+    ///   to between `1e-9` and `0.5`.
+    pub fn new(expected_hashes: u64, false_positive_rate: f64) -> Self {
+        let rate = false_positive_rate.clamp(1e-9, 0.5);
+        let n = expected_hashes.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let bits = (-n * rate.ln() / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let hashes = ((bits as f64 / n) * ln2).round().clamp(1.0, 32.0) as u32;
+        PasswordBloomFilter {
+            words: vec![0; bits.div_ceil(64) as usize],
+            bits,
+            hashes,
+        }
+    }
+
+    /// Adds a hex SHA-1 or NTLM hash. Invalid hashes are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The full hex digest, in any case
+    pub fn insert_hash(&mut self, hash: &str) {
+        if let Some((h1, h2)) = keys(hash) {
+            for i in 0..self.hashes {
+                let bit = self.bit(h1, h2, i);
+                self.words[(bit / 64) as usize] |= 1 << (bit % 64);
+            }
+        }
+    }
+
+    /// Whether a hex SHA-1 or NTLM hash may be in the corpus.
+    ///
+    /// `false` means the hash is definitely not in it. Invalid hashes return `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The full hex digest, in any case
+    pub fn contains_hash(&self, hash: &str) -> bool {
+        let Some((h1, h2)) = keys(hash) else {
+            return false;
+        };
+        (0..self.hashes).all(|i| {
+            let bit = self.bit(h1, h2, i);
+            self.words[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+        })
+    }
+
+    /// Whether a password may be in a SHA-1 corpus. `false` means it definitely isn't.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to check
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::{PasswordBloomFilter, hash};
+    /// let mut filter = PasswordBloomFilter::new(1000, 0.01);
+    /// filter.insert_hash(&hash::sha1_hex("password"));
+    /// assert!(filter.maybe_pwned("password"));
+    /// ```
+    pub fn maybe_pwned(&self, password: &str) -> bool {
+        self.contains_hash(&hash::sha1_hex(password))
+    }
+
+    /// Returns the size of the filter in bits.
+    pub fn bit_count(&self) -> u64 {
+        self.bits
+    }
+
+    /// Serializes the filter, e.g. to embed it in an edge worker.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.words.len() * 8);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.bits.to_le_bytes());
+        bytes.extend_from_slice(&self.hashes.to_le_bytes());
+        for word in &self.words {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserializes a filter written by [`PasswordBloomFilter::to_bytes`].
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized filter.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, HibpError> {
+        let invalid = || HibpError::InvalidInput("Not a password bloom filter".into());
+        let header = bytes.get(..HEADER_LEN).ok_or_else(invalid)?;
+        if &header[..8] != MAGIC {
+            return Err(invalid());
+        }
+        let bits = u64::from_le_bytes(header[8..16].try_into().map_err(|_| invalid())?);
+        let hashes = u32::from_le_bytes(header[16..20].try_into().map_err(|_| invalid())?);
+        let body = &bytes[HEADER_LEN..];
+        if bits == 0 || hashes == 0 || body.len() as u64 != bits.div_ceil(64) * 8 {
+            return Err(invalid());
+        }
+        let words = body
+            .chunks_exact(8)
+            .map(|word| u64::from_le_bytes(word.try_into().unwrap_or_default()))
+            .collect();
+        Ok(PasswordBloomFilter {
+            words,
+            bits,
+            hashes,
+        })
+    }
+
+    /// Builds a filter from a corpus written by [`crate::CorpusDownloader`].
+    ///
+    /// Reads the corpus twice: once to count its hashes and once to insert them.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The corpus file or directory of range files.
+    /// * `false_positive_rate` - Acceptable rate of false positives, e.g. `0.01`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_corpus<P: AsRef<Path>>(
+        path: P,
+        false_positive_rate: f64,
+    ) -> Result<Self, HibpError> {
+        let path = path.as_ref();
+        let mut count = 0;
+        for_each_corpus_hash(path, |_| count += 1)?;
+        let mut filter = PasswordBloomFilter::new(count, false_positive_rate);
+        for_each_corpus_hash(path, |hash| filter.insert_hash(hash))?;
+        Ok(filter)
+    }
+
+    /// Writes the filter to a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), HibpError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Reads a filter written by [`PasswordBloomFilter::save`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, HibpError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Returns the bit probed by the `i`-th hash function, using double hashing.
+    fn bit(&self, h1: u64, h2: u64, i: u32) -> u64 {
+        h1.wrapping_add((i as u64).wrapping_mul(h2)) % self.bits
+    }
+}
+
+/// Derives the two base hashes of the filter from the first 32 hex characters of a digest.
+///
+/// Digests are uniformly distributed already, so they need no further hashing.
+fn keys(hash: &str) -> Option<(u64, u64)> {
+    if !matches!(hash.len(), hash::SHA1_HEX_LEN | hash::NTLM_HEX_LEN) {
+        return None;
+    }
+    let h1 = u64::from_str_radix(hash.get(..16)?, 16).ok()?;
+    let h2 = u64::from_str_radix(hash.get(16..32)?, 16).ok()?;
+    // A zero step would probe the same bit for every hash function
+    Some((h1, h2 | 1))
+}
+
+/// Calls `f` with the full hex hash of every line of a corpus file or range directory.
+#[cfg(not(target_arch = "wasm32"))]
+fn for_each_corpus_hash<F: FnMut(&str)>(path: &Path, mut f: F) -> Result<(), HibpError> {
+    let mut read_lines = |file: &Path, prefix: &str| -> Result<(), HibpError> {
+        let reader = std::io::BufReader::new(std::fs::File::open(file)?);
+        let mut hash = String::new();
+        for line in reader.lines() {
+            let line = line?;
+            if let Some((hex, _)) = line.trim().split_once(':') {
+                hash.clear();
+                hash.push_str(prefix);
+                hash.push_str(hex);
+                f(&hash);
+            }
+        }
+        Ok(())
+    };
+
+    if !std::fs::metadata(path)?.is_dir() {
+        return read_lines(path, "");
+    }
+    let mut files: Vec<_> = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    files.sort();
+    for file in files {
+        let Some(prefix) = file
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".txt"))
+            .filter(|prefix| prefix.len() == hash::PREFIX_LEN)
+        else {
+            continue;
+        };
+        read_lines(&file, prefix)?;
+    }
+    Ok(())
+}
+
+impl HaveIBeenPwned {
+    /// Check a password against a bloom filter first, querying the API only on a possible hit.
+    ///
+    /// Passwords the filter rules out return 0 without any request. Possible hits are
+    /// confirmed with a padded range request, so false positives of the filter never
+    /// show up in the result.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - A filter built from a SHA-1 corpus
+    /// * `password` - The password to check
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{HaveIBeenPwned, PasswordBloomFilter};
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let filter = PasswordBloomFilter::load("pwnedpasswords.bloom").unwrap();
+    /// let count = hibp.check_password_prefiltered(&filter, "password123").await.unwrap();
+    /// println!("This password was found {} times in data breaches", count);
+    /// # }
+    /// ```
+    pub async fn check_password_prefiltered(
+        &self,
+        filter: &PasswordBloomFilter,
+        password: &str,
+    ) -> Result<u64, HibpError> {
+        let hash_str = self.sha1_hex(password);
+        if !filter.contains_hash(&hash_str) {
+            return Ok(0);
+        }
+        self.count_sha1_hash(&hash_str).await
+    }
+}
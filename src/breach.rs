@@ -1,8 +1,11 @@
-use crate::HaveIBeenPwned;
-use urlencoding;
+use crate::runtime::SystemTime;
+use crate::{HaveIBeenPwned, HibpError};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 /// Represents a breach returned by the HIBP API.
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct Breach {
     /// Name of the breach.
     #[serde(rename = "Name")]
@@ -15,13 +18,13 @@ pub struct Breach {
     pub domain: String,
     /// Date the breach occurred.
     #[serde(rename = "BreachDate")]
-    pub breach_date: String,
-    /// Date the breach was added to HIBP.
+    pub breach_date: NaiveDate,
+    /// Time the breach was added to HIBP.
     #[serde(rename = "AddedDate")]
-    pub added_date: String,
-    /// Date the breach was last modified.
+    pub added_date: DateTime<Utc>,
+    /// Time the breach was last modified.
     #[serde(rename = "ModifiedDate")]
-    pub modified_date: String,
+    pub modified_date: DateTime<Utc>,
     /// Number of accounts affected.
     #[serde(rename = "PwnCount")]
     pub pwn_count: u64,
@@ -60,92 +63,360 @@ pub struct Breach {
     pub is_subscription_free: bool,
 }
 
+/// Last breach list of each query by URL, shared by all clones of a client so later
+/// requests can revalidate it.
+pub(crate) type EtaggedBreachList = Arc<Mutex<HashMap<String, EtaggedBreaches>>>;
+
+/// A breach list with its `ETag`.
+#[derive(Debug)]
+pub(crate) struct EtaggedBreaches {
+    etag: String,
+    breaches: Vec<Breach>,
+}
+
+/// Formats a time like HIBP timestamps, e.g. `2013-12-04T00:00:00Z`.
+pub(crate) fn format_timestamp(time: &DateTime<Utc>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// Returns the current time in whole seconds, like HIBP timestamps.
+pub(crate) fn now_utc() -> DateTime<Utc> {
+    let secs = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    DateTime::from_timestamp(secs as i64, 0).unwrap_or_default()
+}
+
+/// Name of a breach, as returned by truncated account lookups.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize)]
+pub struct BreachName {
+    /// Name of the breach, e.g. `Adobe`.
+    #[serde(rename = "Name")]
+    pub name: String,
+}
+
+/// Server-side filters for account lookups like
+/// [`HaveIBeenPwned::get_breaches_for_account_with`].
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::BreachedAccountOptions;
+/// let options = BreachedAccountOptions::default()
+///     .with_include_unverified(false)
+///     .with_domain("adobe.com");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct BreachedAccountOptions {
+    /// Whether to include unverified breaches. The API includes them by default.
+    pub include_unverified: Option<bool>,
+    /// Only return breaches of this domain.
+    pub domain: Option<String>,
+}
+
+impl BreachedAccountOptions {
+    /// Includes or excludes unverified breaches.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_unverified` - `false` to only return verified breaches.
+    pub fn with_include_unverified(mut self, include_unverified: bool) -> Self {
+        self.include_unverified = Some(include_unverified);
+        self
+    }
+
+    /// Only returns breaches of a domain, e.g. `adobe.com`.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain the breaches belong to.
+    pub fn with_domain<S: Into<String>>(mut self, domain: S) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Returns the query parameters, each with a leading `&`.
+    fn to_query_params(&self) -> String {
+        let mut params = String::new();
+        if let Some(include_unverified) = self.include_unverified {
+            params.push_str(&format!("&includeUnverified={}", include_unverified));
+        }
+        if let Some(domain) = &self.domain {
+            params.push_str(&format!("&domain={}", urlencoding::encode(domain.trim())));
+        }
+        params
+    }
+}
+
+/// Server-side filters for [`HaveIBeenPwned::get_all_breaches_with`].
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::BreachesQuery;
+/// let query = BreachesQuery::default()
+///     .with_domain("adobe.com")
+///     .with_spam_list(false);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct BreachesQuery {
+    /// Only return breaches of this domain.
+    pub domain: Option<String>,
+    /// Only return spam lists (`true`) or only other breaches (`false`).
+    pub is_spam_list: Option<bool>,
+}
+
+impl BreachesQuery {
+    /// Only returns breaches of a domain, e.g. `adobe.com`.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain the breaches belong to.
+    pub fn with_domain<S: Into<String>>(mut self, domain: S) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Only returns spam lists, or excludes them.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_spam_list` - `true` for spam lists only, `false` to exclude them.
+    pub fn with_spam_list(mut self, is_spam_list: bool) -> Self {
+        self.is_spam_list = Some(is_spam_list);
+        self
+    }
+
+    /// Returns the query string, including the leading `?`, or an empty string.
+    fn to_query_string(&self) -> String {
+        let mut params = Vec::new();
+        if let Some(domain) = &self.domain {
+            params.push(format!("Domain={}", urlencoding::encode(domain.trim())));
+        }
+        if let Some(is_spam_list) = self.is_spam_list {
+            params.push(format!("IsSpamList={}", is_spam_list));
+        }
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", params.join("&"))
+        }
+    }
+}
+
 impl HaveIBeenPwned {
     /// Gets all breaches for a given account (email address).
-    pub async fn get_breaches_for_account(
+    pub async fn get_breaches_for_account(&self, account: &str) -> Result<Vec<Breach>, HibpError> {
+        self.get_breaches_for_account_with(account, &BreachedAccountOptions::default())
+            .await
+    }
+
+    /// Gets the breaches of an account matching the options, with all breach details.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The email address to look up.
+    /// * `options` - Filters to apply, see [`BreachedAccountOptions`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{BreachedAccountOptions, HaveIBeenPwned};
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let options = BreachedAccountOptions::default().with_include_unverified(false);
+    /// let breaches = hibp
+    ///     .get_breaches_for_account_with("test@example.com", &options)
+    ///     .await
+    ///     .unwrap();
+    /// println!("{} verified breaches", breaches.len());
+    /// # }
+    /// ```
+    pub async fn get_breaches_for_account_with(
         &self,
         account: &str,
-    ) -> Result<Vec<Breach>, Box<dyn std::error::Error>> {
-        if let Some(rate_limiter) = &self.rate_limiter {
-            rate_limiter.wait_if_needed().await;
-        }
+        options: &BreachedAccountOptions,
+    ) -> Result<Vec<Breach>, HibpError> {
+        self.fetch_breached_account(account, options, false).await
+    }
 
+    /// Gets only the names of the breaches of an account matching the options.
+    ///
+    /// Truncated responses are much smaller than full breach details, which makes them
+    /// faster and cheaper when only the names are needed, e.g. to compare against a
+    /// stored list. Look up details of new names with
+    /// [`HaveIBeenPwned::get_breach_by_name`].
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The email address to look up.
+    /// * `options` - Filters to apply, see [`BreachedAccountOptions`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{BreachedAccountOptions, HaveIBeenPwned};
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let names = hibp
+    ///     .get_breach_names_for_account("test@example.com", &BreachedAccountOptions::default())
+    ///     .await
+    ///     .unwrap();
+    /// for breach in names {
+    ///     println!("{}", breach.name);
+    /// }
+    /// # }
+    /// ```
+    pub async fn get_breach_names_for_account(
+        &self,
+        account: &str,
+        options: &BreachedAccountOptions,
+    ) -> Result<Vec<BreachName>, HibpError> {
+        self.fetch_breached_account(account, options, true).await
+    }
+
+    /// Fetches the breaches of an account, treating an unknown account as unbreached.
+    async fn fetch_breached_account<T: serde::de::DeserializeOwned>(
+        &self,
+        account: &str,
+        options: &BreachedAccountOptions,
+        truncate: bool,
+    ) -> Result<Vec<T>, HibpError> {
         let encoded_account = urlencoding::encode(account.trim());
         let url = format!(
-            "{}/breachedaccount/{}?truncateResponse=false",
-            self.base_url, encoded_account
+            "{}/breachedaccount/{}?truncateResponse={}{}",
+            self.base_url,
+            encoded_account,
+            truncate,
+            options.to_query_params()
         );
 
-        let headers = self.create_headers()?;
-        let resp = self.client.get(&url).headers(headers).send().await?;
+        let resp = self.send_api_get("breachedaccount", &url).await?;
 
-        if resp.status().is_success() {
-            let breaches: Vec<Breach> = resp.json().await?;
+        if resp.status.is_success() {
+            let breaches: Vec<T> = self.decode_json(&resp)?;
             Ok(breaches)
-        } else if resp.status().as_u16() == 404 {
+        } else if resp.status.as_u16() == 404 {
             Ok(vec![])
         } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+            Err(resp.error())
         }
     }
 
     /// Gets all breaches in the system.
-    pub async fn get_all_breaches(&self) -> Result<Vec<Breach>, Box<dyn std::error::Error>> {
-        if let Some(rate_limiter) = &self.rate_limiter {
-            rate_limiter.wait_if_needed().await;
-        }
+    pub async fn get_all_breaches(&self) -> Result<Vec<Breach>, HibpError> {
+        self.get_all_breaches_with(&BreachesQuery::default()).await
+    }
 
-        let url = format!("{}/breaches", self.base_url);
-        let headers = self.create_headers()?;
-        let resp = self.client.get(&url).headers(headers).send().await?;
+    /// Gets the breaches in the system matching a query, filtered by the API.
+    ///
+    /// The client keeps the last list of each query with its `ETag` and revalidates it with
+    /// `If-None-Match`, so if the list hasn't changed the API answers `304 Not Modified`
+    /// without a body and the kept copy is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Filters to apply, see [`BreachesQuery`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{BreachesQuery, HaveIBeenPwned};
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let query = BreachesQuery::default().with_domain("adobe.com");
+    /// let breaches = hibp.get_all_breaches_with(&query).await.unwrap();
+    /// println!("{} breaches of adobe.com", breaches.len());
+    /// # }
+    /// ```
+    pub async fn get_all_breaches_with(
+        &self,
+        query: &BreachesQuery,
+    ) -> Result<Vec<Breach>, HibpError> {
+        let url = format!("{}/breaches{}", self.base_url, query.to_query_string());
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(etag) = self.kept_breach_list(&url, |kept| kept.etag.clone())
+            && let Ok(value) = reqwest::header::HeaderValue::from_str(&etag)
+        {
+            headers.insert(reqwest::header::IF_NONE_MATCH, value);
+        }
+        let resp = self.send_api_get_with("breaches", &url, headers).await?;
 
-        if resp.status().is_success() {
-            let breaches: Vec<Breach> = resp.json().await?;
+        if resp.status == reqwest::StatusCode::NOT_MODIFIED
+            && let Some(breaches) = self.kept_breach_list(&url, |kept| kept.breaches.clone())
+        {
+            Ok(breaches)
+        } else if resp.status.is_success() {
+            let breaches: Vec<Breach> = self.decode_json(&resp)?;
+            if let Some(etag) = resp.etag
+                && let Ok(mut kept) = self.breach_list.lock()
+            {
+                kept.insert(
+                    url,
+                    EtaggedBreaches {
+                        etag,
+                        breaches: breaches.clone(),
+                    },
+                );
+            }
             Ok(breaches)
         } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+            Err(resp.error())
         }
     }
 
-    /// Gets a single breach by its name.
-    pub async fn get_breach_by_name(
+    /// Reads the kept breach list requested from `url`, if any.
+    fn kept_breach_list<T>(
         &self,
-        name: &str,
-    ) -> Result<Breach, Box<dyn std::error::Error>> {
-        if let Some(rate_limiter) = &self.rate_limiter {
-            rate_limiter.wait_if_needed().await;
-        }
+        url: &str,
+        read: impl FnOnce(&EtaggedBreaches) -> T,
+    ) -> Option<T> {
+        let kept = self.breach_list.lock().ok()?;
+        kept.get(url).map(read)
+    }
 
+    /// Gets a single breach by its name.
+    pub async fn get_breach_by_name(&self, name: &str) -> Result<Breach, HibpError> {
         let encoded_name = urlencoding::encode(name.trim());
         let url = format!("{}/breach/{}", self.base_url, encoded_name);
-        let headers = self.create_headers()?;
-        let resp = self.client.get(&url).headers(headers).send().await?;
+        let resp = self.send_api_get("breach", &url).await?;
 
-        if resp.status().is_success() {
-            let breach: Breach = resp.json().await?;
+        if resp.status.is_success() {
+            let breach: Breach = self.decode_json(&resp)?;
             Ok(breach)
-        } else if resp.status().as_u16() == 404 {
-            Err("Breach not found".into())
         } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+            Err(resp.error())
         }
     }
 
     /// Gets the most recently added breach in the system.
-    pub async fn get_latest_breach(&self) -> Result<Breach, Box<dyn std::error::Error>> {
-        if let Some(rate_limiter) = &self.rate_limiter {
-            rate_limiter.wait_if_needed().await;
-        }
-
+    pub async fn get_latest_breach(&self) -> Result<Breach, HibpError> {
         let url = format!("{}/latestbreach", self.base_url);
-        let headers = self.create_headers()?;
-        let resp = self.client.get(&url).headers(headers).send().await?;
+        let resp = self.send_api_get("latestbreach", &url).await?;
 
-        if resp.status().is_success() {
-            let breach: Breach = resp.json().await?;
+        if resp.status.is_success() {
+            let breach: Breach = self.decode_json(&resp)?;
             Ok(breach)
         } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+            Err(resp.error())
+        }
+    }
+
+    /// Gets all data classes used in the system, e.g. `Email addresses` or `Passwords`.
+    ///
+    /// Use this list to present the available classes or to validate filters against
+    /// [`Breach::data_classes`]. Available in free-tier mode.
+    pub async fn get_all_data_classes(&self) -> Result<Vec<String>, HibpError> {
+        let url = format!("{}/dataclasses", self.base_url);
+        let resp = self.send_api_get("dataclasses", &url).await?;
+
+        if resp.status.is_success() {
+            let data_classes: Vec<String> = self.decode_json(&resp)?;
+            Ok(data_classes)
+        } else {
+            Err(resp.error())
         }
     }
 }
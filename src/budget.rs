@@ -0,0 +1,188 @@
+use crate::runtime::Instant;
+use crate::{HaveIBeenPwned, HibpError};
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Error returned when a client's request budget is used up.
+///
+/// Returned before any request is sent, as [`crate::HibpError::BudgetExceeded`], until the
+/// budget period ends. See [`HaveIBeenPwned::with_request_budget`].
+///
+/// ```no_run
+/// # use hibp_rs::{HaveIBeenPwned, HibpError};
+/// # async fn example() {
+/// let hibp = HaveIBeenPwned::new("your_api_key");
+/// if let Err(HibpError::BudgetExceeded(exceeded)) = hibp.get_breaches_for_account("test@example.com").await {
+///     println!("Budget of {} requests used up, resets in {:?}", exceeded.limit, exceeded.resets_in);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    /// Endpoint of the rejected request, e.g. `breachedaccount`.
+    pub endpoint: String,
+    /// Number of requests allowed per period.
+    pub limit: u64,
+    /// Length of the budget period.
+    pub period: Duration,
+    /// Time left until the budget is refilled.
+    pub resets_in: Duration,
+}
+
+impl Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The request budget of {} requests per {:?} is used up, refused the {} request \
+             (resets in {:?})",
+            self.limit, self.period, self.endpoint, self.resets_in
+        )
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// Requests counted in the current budget period.
+#[derive(Debug)]
+struct BudgetWindow {
+    /// When the current period started, at its first request.
+    start: Option<Instant>,
+    /// Requests sent in the current period.
+    used: u64,
+}
+
+/// Hard cap on the number of requests per period, shared by all clones of a client.
+#[derive(Debug, Clone)]
+pub(crate) struct RequestBudget {
+    limit: u64,
+    period: Duration,
+    window: Arc<Mutex<BudgetWindow>>,
+}
+
+impl RequestBudget {
+    pub(crate) fn new(limit: u64, period: Duration) -> Self {
+        RequestBudget {
+            limit,
+            period,
+            window: Arc::new(Mutex::new(BudgetWindow {
+                start: None,
+                used: 0,
+            })),
+        }
+    }
+
+    /// Counts a request against the budget, or fails if the budget is used up.
+    pub(crate) fn acquire(&self, endpoint: &str) -> Result<(), BudgetExceeded> {
+        self.take(endpoint, 1)
+    }
+
+    /// Fails if the budget is used up, without counting a request.
+    pub(crate) fn check(&self, endpoint: &str) -> Result<(), BudgetExceeded> {
+        self.take(endpoint, 0)
+    }
+
+    /// Counts `count` requests against the budget unless it is already used up.
+    fn take(&self, endpoint: &str, count: u64) -> Result<(), BudgetExceeded> {
+        let mut window = self.lock();
+        let now = Instant::now();
+        let start = match window.start {
+            Some(start) if now.duration_since(start) < self.period => start,
+            // A new period starts with the first request after the previous one ended
+            _ => {
+                window.used = 0;
+                *window.start.insert(now)
+            }
+        };
+        if window.used >= self.limit {
+            return Err(BudgetExceeded {
+                endpoint: endpoint.to_string(),
+                limit: self.limit,
+                period: self.period,
+                resets_in: self.period.saturating_sub(now.duration_since(start)),
+            });
+        }
+        window.used += count;
+        Ok(())
+    }
+
+    /// Returns how many requests are left in the current period.
+    pub(crate) fn remaining(&self) -> u64 {
+        let window = self.lock();
+        match window.start {
+            Some(start) if start.elapsed() < self.period => self.limit.saturating_sub(window.used),
+            _ => self.limit,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BudgetWindow> {
+        // The window is updated without anything that can panic in between, so a poisoned
+        // lock still holds consistent counts.
+        self.window.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Caps the number of requests per period, e.g. 5,000 per day.
+    ///
+    /// Once the budget is used up, calls fail with [`BudgetExceeded`] without contacting
+    /// the API until the period ends, so a runaway batch job can't burn through a
+    /// subscription. Every request sent counts, including retries and Pwned Passwords
+    /// lookups; responses served from the response cache don't. The period starts with the
+    /// first request and the next one with the first request after it ended. Clones of a
+    /// client share the budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of requests per period.
+    /// * `period` - Length of the budget period.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero, which would start a new period with every request.
+    /// [`crate::HaveIBeenPwnedBuilder::with_request_budget`] reports it as an error from `build`
+    /// instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// use std::time::Duration;
+    ///
+    /// let hibp = HaveIBeenPwned::new_with_rate_limit("your_api_key", 10)
+    ///     .with_request_budget(5_000, Duration::from_secs(24 * 60 * 60));
+    /// assert_eq!(hibp.remaining_request_budget(), Some(5_000));
+    /// ```
+    #[must_use]
+    pub fn with_request_budget(mut self, limit: u64, period: Duration) -> Self {
+        assert!(!period.is_zero(), "Request budget period must be positive");
+        self.request_budget = Some(RequestBudget::new(limit, period));
+        self
+    }
+
+    /// Returns how many requests are left in the current budget period, or `None` if no
+    /// budget was set with [`HaveIBeenPwned::with_request_budget`].
+    pub fn remaining_request_budget(&self) -> Option<u64> {
+        self.request_budget.as_ref().map(RequestBudget::remaining)
+    }
+
+    /// Fails if the budget is used up, so exhausted budgets fail before any rate limiter
+    /// wait.
+    pub(crate) fn check_request_budget(&self, endpoint: &str) -> Result<(), HibpError> {
+        match &self.request_budget {
+            Some(budget) => budget.check(endpoint).map_err(|e| {
+                self.stats.record_error("budget");
+                e.into()
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Counts a request against the budget, if one was set.
+    pub(crate) fn acquire_request_budget(&self, endpoint: &str) -> Result<(), BudgetExceeded> {
+        match &self.request_budget {
+            Some(budget) => budget.acquire(endpoint),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,466 @@
+#[cfg(feature = "full")]
+use crate::{CacheStore, RateLimiter, RateLimiting, RetryPolicy};
+use crate::{HaveIBeenPwned, HibpError};
+use secrecy::{ExposeSecret, SecretString};
+#[cfg(feature = "full")]
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+/// How a client built with [`HaveIBeenPwnedBuilder`] limits its request rate.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitMode {
+    /// Requests are not limited on the client side.
+    #[default]
+    None,
+    /// At most the given number of requests per minute.
+    Fixed(i32),
+    /// The limit of the subscription, fetched once right before the first request.
+    FromSubscription,
+}
+
+/// Fluent builder for a validated [`HaveIBeenPwned`] client.
+///
+/// Created with [`HaveIBeenPwned::builder`]. Unlike the constructors, [`build`](Self::build)
+/// checks the settings and fails with [`HibpError::InvalidInput`] instead of producing a
+/// client whose requests fail later.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{HaveIBeenPwned, RateLimitMode, RetryPolicy};
+/// # fn example() -> Result<(), hibp_rs::HibpError> {
+/// let hibp = HaveIBeenPwned::builder()
+///     .with_api_key("your_api_key")
+///     .with_user_agent("my-app/1.0")
+///     .with_rate_limit(RateLimitMode::FromSubscription)
+///     .with_retry_policy(RetryPolicy::default().with_max_retries(5))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct HaveIBeenPwnedBuilder {
+    api_key: Option<SecretString>,
+    free_tier: bool,
+    user_agent: Option<String>,
+    base_url: Option<String>,
+    passwords_base_url: Option<String>,
+    http_client: Option<reqwest::Client>,
+    #[cfg(not(target_arch = "wasm32"))]
+    connect_timeout: Option<Duration>,
+    #[cfg(not(target_arch = "wasm32"))]
+    request_timeout: Option<Duration>,
+    #[cfg(feature = "full")]
+    rate_limit: RateLimitMode,
+    #[cfg(feature = "full")]
+    rate_limiter: Option<Arc<dyn RateLimiting>>,
+    #[cfg(feature = "full")]
+    rate_limit_burst: Option<u32>,
+    #[cfg(feature = "full")]
+    passwords_rate_limit: Option<i32>,
+    #[cfg(feature = "full")]
+    retry_policy: Option<RetryPolicy>,
+    #[cfg(feature = "full")]
+    retry_hook: Option<crate::retry::RetryHook>,
+    #[cfg(feature = "full")]
+    response_cache_ttl: Option<std::time::Duration>,
+    #[cfg(feature = "full")]
+    cache_store: Option<Arc<dyn CacheStore>>,
+    #[cfg(feature = "full")]
+    stale_while_revalidate: Option<std::time::Duration>,
+    #[cfg(feature = "full")]
+    request_budget: Option<(u64, std::time::Duration)>,
+}
+
+impl HaveIBeenPwnedBuilder {
+    /// Sets the HIBP API key.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Your HIBP API key.
+    pub fn with_api_key<S: Into<String>>(mut self, api_key: S) -> Self {
+        self.api_key = Some(SecretString::from(api_key.into()));
+        self
+    }
+
+    /// Builds a keyless client limited to the free endpoints, like
+    /// [`HaveIBeenPwned::new_free_tier`].
+    pub fn with_free_tier(mut self) -> Self {
+        self.free_tier = true;
+        self
+    }
+
+    /// Sets the User-Agent sent with requests, e.g. `my-app/1.0`.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_agent` - The User-Agent string to use.
+    pub fn with_user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Sets the base URL of the HIBP API, e.g. to point the client at a proxy.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - An `http` or `https` URL. A trailing slash is removed.
+    pub fn with_base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sets the base URL of the Pwned Passwords range API, e.g. to point password checks
+    /// at an internal mirror while breach endpoints still use the base URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `passwords_base_url` - An `http` or `https` URL. A trailing slash is removed.
+    pub fn with_passwords_base_url<S: Into<String>>(mut self, passwords_base_url: S) -> Self {
+        self.passwords_base_url = Some(passwords_base_url.into());
+        self
+    }
+
+    /// Sends requests with the given HTTP client, e.g. one shared across the application.
+    ///
+    /// See [`HaveIBeenPwned::with_http_client`].
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The HTTP client to send requests with.
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Sets the time allowed to establish a connection, 10 seconds by default.
+    ///
+    /// Can't be combined with [`with_http_client`](Self::with_http_client); configure the
+    /// connect timeout of your own client instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Maximum duration of a connection attempt.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the time allowed for each request, 30 seconds by default.
+    ///
+    /// See [`HaveIBeenPwned::with_request_timeout`].
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Maximum duration of a request.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how the client limits its request rate to the HIBP API. Not limited by default.
+    ///
+    /// Pwned Passwords requests are not affected, see
+    /// [`with_passwords_rate_limit`](Self::with_passwords_rate_limit).
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The rate limit mode.
+    #[cfg(feature = "full")]
+    pub fn with_rate_limit(mut self, mode: RateLimitMode) -> Self {
+        self.rate_limit = mode;
+        self
+    }
+
+    /// Limits the request rate with a custom [`RateLimiting`] implementation.
+    ///
+    /// See [`HaveIBeenPwned::with_rate_limiter`]. Can't be combined with
+    /// [`with_rate_limit`](Self::with_rate_limit).
+    ///
+    /// # Arguments
+    ///
+    /// * `rate_limiter` - The rate limiter HIBP API requests wait for.
+    #[cfg(feature = "full")]
+    pub fn with_rate_limiter<R: RateLimiting + 'static>(mut self, rate_limiter: R) -> Self {
+        self.rate_limiter = Some(Arc::new(rate_limiter));
+        self
+    }
+
+    /// Lets short bursts of requests through the rate limit. 1 by default.
+    ///
+    /// See [`HaveIBeenPwned::with_rate_limit_burst`].
+    ///
+    /// # Arguments
+    ///
+    /// * `burst` - Maximum number of requests sent without waiting.
+    #[cfg(feature = "full")]
+    pub fn with_rate_limit_burst(mut self, burst: u32) -> Self {
+        self.rate_limit_burst = Some(burst);
+        self
+    }
+
+    /// Limits requests to the Pwned Passwords API, which are not limited by default.
+    ///
+    /// See [`HaveIBeenPwned::with_passwords_rate_limit`].
+    ///
+    /// # Arguments
+    ///
+    /// * `rpm` - Maximum number of password range requests per minute.
+    #[cfg(feature = "full")]
+    pub fn with_passwords_rate_limit(mut self, rpm: i32) -> Self {
+        self.passwords_rate_limit = Some(rpm);
+        self
+    }
+
+    /// Sets when failed requests are retried. Defaults to [`RetryPolicy::default`].
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The retry policy to use.
+    #[cfg(feature = "full")]
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Calls `hook` before each retry. Not set by default.
+    ///
+    /// See [`HaveIBeenPwned::with_retry_hook`].
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Called with the retry number, delay and cause of each retry.
+    #[cfg(feature = "full")]
+    pub fn with_retry_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(u32, std::time::Duration, &HibpError) + Send + Sync + 'static,
+    {
+        self.retry_hook = Some(crate::retry::RetryHook::new(hook));
+        self
+    }
+
+    /// Serves repeated requests from memory for a while. Not cached by default.
+    ///
+    /// See [`HaveIBeenPwned::with_response_cache`].
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - How long a response is reused.
+    #[cfg(feature = "full")]
+    pub fn with_response_cache(mut self, ttl: std::time::Duration) -> Self {
+        self.response_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Caches responses in a custom store for a while.
+    ///
+    /// See [`HaveIBeenPwned::with_cache_store`].
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Where responses are kept.
+    /// * `ttl` - How long a response is reused.
+    #[cfg(feature = "full")]
+    pub fn with_cache_store<C: CacheStore + 'static>(
+        mut self,
+        store: C,
+        ttl: std::time::Duration,
+    ) -> Self {
+        self.cache_store = Some(Arc::new(store));
+        self.response_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Serves cached responses for a while after they expire while refreshing them in the
+    /// background. Stale responses are fetched again by default.
+    ///
+    /// See [`HaveIBeenPwned::with_stale_while_revalidate`].
+    ///
+    /// # Arguments
+    ///
+    /// * `grace` - How long stale responses are served while they are refreshed.
+    #[cfg(feature = "full")]
+    pub fn with_stale_while_revalidate(mut self, grace: std::time::Duration) -> Self {
+        self.stale_while_revalidate = Some(grace);
+        self
+    }
+
+    /// Caps the number of requests per period. Unlimited by default.
+    ///
+    /// See [`HaveIBeenPwned::with_request_budget`].
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of requests per period.
+    /// * `period` - Length of the budget period.
+    #[cfg(feature = "full")]
+    pub fn with_request_budget(mut self, limit: u64, period: std::time::Duration) -> Self {
+        self.request_budget = Some((limit, period));
+        self
+    }
+
+    /// Validates the settings and builds the client.
+    ///
+    /// Fails if no API key was set outside of free-tier mode, if the API key or user agent
+    /// can't be sent as a header, if a base URL is not an `http` or `https` URL, if a
+    /// connect timeout is combined with a provided HTTP client, if a custom rate limiter is
+    /// combined with a rate limit mode, or if a fixed rate limit or the passwords rate limit
+    /// isn't positive, if the burst capacity or the request budget period is 0, or if stale
+    /// responses are served without a response cache.
+    pub fn build(self) -> Result<HaveIBeenPwned, HibpError> {
+        let invalid = |message: &str| Err(HibpError::InvalidInput(message.to_string()));
+
+        let mut client = match (self.api_key, self.free_tier) {
+            (Some(_), true) => return invalid("A free-tier client can't have an API key"),
+            (None, true) => HaveIBeenPwned::new_free_tier(),
+            (Some(api_key), false) if !api_key.expose_secret().trim().is_empty() => {
+                if reqwest::header::HeaderValue::from_str(api_key.expose_secret()).is_err() {
+                    return invalid("API key is not a valid header value");
+                }
+                HaveIBeenPwned {
+                    api_key,
+                    ..HaveIBeenPwned::new("")
+                }
+            }
+            _ => return invalid("An API key is required outside of free-tier mode"),
+        };
+
+        if let Some(user_agent) = self.user_agent {
+            if user_agent.trim().is_empty()
+                || reqwest::header::HeaderValue::from_str(&user_agent).is_err()
+            {
+                return invalid("User agent must be a non-empty header value");
+            }
+            client.user_agent = user_agent;
+        }
+
+        if let Some(base_url) = self.base_url {
+            client.base_url = validate_url(&base_url, "Base URL")?;
+        }
+        if let Some(passwords_base_url) = self.passwords_base_url {
+            client.passwords_base_url = validate_url(&passwords_base_url, "Passwords base URL")?;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(timeout) = self.request_timeout {
+            client.timeouts.request = timeout;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(timeout) = self.connect_timeout {
+            if self.http_client.is_some() {
+                return invalid("A connect timeout can't be applied to a provided HTTP client");
+            }
+            client.timeouts.connect = timeout;
+            client.client = crate::timeouts::http_client(timeout)?;
+        }
+
+        if let Some(http_client) = self.http_client {
+            client.client = http_client;
+        }
+
+        #[cfg(feature = "full")]
+        match self.rate_limit {
+            RateLimitMode::None => {}
+            RateLimitMode::Fixed(rpm) if rpm > 0 => {
+                client.rate_limiter = Some(RateLimiter::new(rpm));
+            }
+            RateLimitMode::Fixed(_) => return invalid("Rate limit must be positive"),
+            RateLimitMode::FromSubscription => {
+                client.lazy_rate_limiter = Some(Arc::new(tokio::sync::OnceCell::new()));
+            }
+        }
+
+        #[cfg(feature = "full")]
+        if let Some(rate_limiter) = self.rate_limiter {
+            if self.rate_limit != RateLimitMode::None {
+                return invalid("A custom rate limiter can't be combined with a rate limit mode");
+            }
+            client.custom_rate_limiter = Some(rate_limiter);
+        }
+
+        #[cfg(feature = "full")]
+        match self.rate_limit_burst {
+            Some(0) => return invalid("Rate limit burst must be positive"),
+            Some(burst) => client = client.with_rate_limit_burst(burst),
+            None => {}
+        }
+
+        #[cfg(feature = "full")]
+        match self.passwords_rate_limit {
+            Some(rpm) if rpm > 0 => client.passwords_rate_limiter = Some(RateLimiter::new(rpm)),
+            Some(_) => return invalid("Passwords rate limit must be positive"),
+            None => {}
+        }
+
+        #[cfg(feature = "full")]
+        if let Some(policy) = self.retry_policy {
+            client.retry_policy = policy;
+        }
+        #[cfg(feature = "full")]
+        {
+            client.retry_hook = self.retry_hook;
+        }
+
+        #[cfg(feature = "full")]
+        if let Some(ttl) = self.response_cache_ttl {
+            let store = self
+                .cache_store
+                .unwrap_or_else(|| Arc::new(crate::MemoryCacheStore::new()));
+            let cache = crate::cache::ResponseCache::new(ttl, store);
+            client.response_cache = Some(match self.stale_while_revalidate {
+                Some(grace) => cache.with_stale_for(grace),
+                None => cache,
+            });
+        } else if self.stale_while_revalidate.is_some() {
+            return invalid("Serving stale responses needs a response cache");
+        }
+
+        #[cfg(feature = "full")]
+        match self.request_budget {
+            Some((_, period)) if period.is_zero() => {
+                return invalid("Request budget period must be positive");
+            }
+            Some((limit, period)) => client = client.with_request_budget(limit, period),
+            None => {}
+        }
+
+        Ok(client)
+    }
+}
+
+/// Checks that a URL uses `http` or `https` and removes a trailing slash.
+fn validate_url(url: &str, name: &str) -> Result<String, HibpError> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {
+            Ok(url.trim_end_matches('/').to_string())
+        }
+        _ => Err(HibpError::InvalidInput(format!(
+            "{} must be an http or https URL",
+            name
+        ))),
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Returns a builder to configure a client in one place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// let hibp = HaveIBeenPwned::builder()
+    ///     .with_api_key("your_api_key")
+    ///     .with_user_agent("my-app/1.0")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(hibp.user_agent, "my-app/1.0");
+    /// ```
+    pub fn builder() -> HaveIBeenPwnedBuilder {
+        HaveIBeenPwnedBuilder::default()
+    }
+}
@@ -0,0 +1,574 @@
+use crate::limits::BodySink;
+use crate::runtime::{Instant, SystemTime};
+use crate::{ApiResponse, HaveIBeenPwned, HibpError, hash};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A cached HIBP API response.
+///
+/// Stores that keep responses as bytes encode them with a [`crate::CacheSerializer`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CachedResponse {
+    /// HTTP status code, e.g. `200`, or `404` for unknown accounts.
+    pub status: u16,
+    /// Raw response body.
+    #[serde(with = "crate::cache_serializer::body")]
+    pub body: Vec<u8>,
+    /// When the response goes stale, in milliseconds since the Unix epoch, for clients
+    /// that serve stale responses while refreshing them, see
+    /// [`HaveIBeenPwned::with_stale_while_revalidate`]. `None` if it stays fresh until
+    /// the store expires it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fresh_until: Option<u64>,
+}
+
+/// Storage for cached HIBP API responses.
+///
+/// The client caches in memory with [`MemoryCacheStore`] when enabled with
+/// [`HaveIBeenPwned::with_response_cache`]. Set another store with
+/// [`HaveIBeenPwned::with_cache_store`], e.g. `DiskCacheStore` so CLI runs and
+/// short-lived functions reuse responses across process restarts.
+///
+/// Keys are request URLs, with `#padded` appended for padded Pwned Passwords ranges.
+/// Caching is best effort: stores should ignore failures rather
+/// than fail requests. Stores that block, e.g. on file or network I/O, return `true` from
+/// [`CacheStore::is_blocking`] and are called on tokio's blocking pool; others must be
+/// quick, as they are called from async code.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{CacheStore, CachedResponse};
+/// use std::collections::HashMap;
+/// use std::sync::Mutex;
+/// use std::time::Duration;
+///
+/// /// Keeps responses until the process exits, ignoring the TTL.
+/// #[derive(Debug, Default)]
+/// struct ForeverStore(Mutex<HashMap<String, CachedResponse>>);
+///
+/// impl CacheStore for ForeverStore {
+///     fn get(&self, key: &str) -> Option<CachedResponse> {
+///         self.0.lock().ok()?.get(key).cloned()
+///     }
+///
+///     fn put(&self, key: &str, response: CachedResponse, _ttl: Duration) {
+///         if let Ok(mut entries) = self.0.lock() {
+///             entries.insert(key.to_string(), response);
+///         }
+///     }
+///
+///     fn invalidate(&self, key: &str) {
+///         if let Ok(mut entries) = self.0.lock() {
+///             entries.remove(key);
+///         }
+///     }
+///
+///     fn invalidate_matching(&self, matches: &dyn Fn(&str) -> bool) {
+///         if let Ok(mut entries) = self.0.lock() {
+///             entries.retain(|key, _| !matches(key));
+///         }
+///     }
+///
+///     fn clear(&self) {
+///         if let Ok(mut entries) = self.0.lock() {
+///             entries.clear();
+///         }
+///     }
+/// }
+/// ```
+pub trait CacheStore: std::fmt::Debug + Send + Sync {
+    /// Returns the response stored for a key, unless it expired.
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// Stores a response for a key, to be returned for `ttl`.
+    fn put(&self, key: &str, response: CachedResponse, ttl: Duration);
+
+    /// Removes the response stored for a key.
+    fn invalidate(&self, key: &str);
+
+    /// Removes the responses whose key matches, e.g. all lookups of an account.
+    ///
+    /// Defaults to [`CacheStore::clear`] for stores that can't list their keys, as
+    /// removing more responses than asked only costs extra requests.
+    fn invalidate_matching(&self, matches: &dyn Fn(&str) -> bool) {
+        let _ = matches;
+        self.clear();
+    }
+
+    /// Removes all stored responses.
+    fn clear(&self);
+
+    /// Whether calls block the calling thread, e.g. on file or network I/O.
+    ///
+    /// The client then calls the store on tokio's blocking pool on native targets instead
+    /// of its async tasks. Defaults to `false`.
+    fn is_blocking(&self) -> bool {
+        false
+    }
+}
+
+/// Lets callers keep a handle to a store they share with the client.
+impl<C: CacheStore + ?Sized> CacheStore for Arc<C> {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        (**self).get(key)
+    }
+
+    fn put(&self, key: &str, response: CachedResponse, ttl: Duration) {
+        (**self).put(key, response, ttl)
+    }
+
+    fn invalidate(&self, key: &str) {
+        (**self).invalidate(key)
+    }
+
+    fn invalidate_matching(&self, matches: &dyn Fn(&str) -> bool) {
+        (**self).invalidate_matching(matches)
+    }
+
+    fn clear(&self) {
+        (**self).clear()
+    }
+
+    fn is_blocking(&self) -> bool {
+        (**self).is_blocking()
+    }
+}
+
+/// Keeps cached responses in memory. The default [`CacheStore`].
+#[derive(Debug, Default)]
+pub struct MemoryCacheStore {
+    entries: Mutex<HashMap<String, (Instant, CachedResponse)>>,
+}
+
+impl MemoryCacheStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CacheStore for MemoryCacheStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let entries = self.entries.lock().ok()?;
+        let (expires, response) = entries.get(key)?;
+        (Instant::now() < *expires).then(|| response.clone())
+    }
+
+    fn put(&self, key: &str, response: CachedResponse, ttl: Duration) {
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        entries.retain(|_, (expires, _)| now < *expires);
+        entries.insert(key.to_string(), (now + ttl, response));
+    }
+
+    fn invalidate(&self, key: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.remove(key);
+        }
+    }
+
+    fn invalidate_matching(&self, matches: &dyn Fn(&str) -> bool) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|key, _| !matches(key));
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// Milliseconds since the Unix epoch.
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Response cache of a client: a store and how long responses are kept in it.
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseCache {
+    ttl: Duration,
+    store: Arc<dyn CacheStore>,
+    /// How long stale responses are served while they are refreshed.
+    stale_for: Duration,
+    /// Keys of stale responses being refreshed in the background.
+    #[cfg(not(target_arch = "wasm32"))]
+    refreshing: Arc<Mutex<HashSet<String>>>,
+}
+
+/// A response found in the cache.
+pub(crate) struct CacheHit<T> {
+    pub(crate) value: T,
+    /// Set if the response is stale and the caller should refresh it in the background.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) refresh: Option<RefreshClaim>,
+}
+
+/// The right to refresh a stale response, released when dropped, so each stale response
+/// is refreshed by one request at a time.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct RefreshClaim {
+    refreshing: Arc<Mutex<HashSet<String>>>,
+    key: String,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RefreshClaim {
+    /// Runs the refresh on the current tokio runtime, keeping the claim until it's done.
+    pub(crate) fn spawn(self, refresh: impl Future<Output = ()> + Send + 'static) {
+        tokio::spawn(async move {
+            refresh.await;
+            drop(self);
+        });
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for RefreshClaim {
+    fn drop(&mut self) {
+        if let Ok(mut refreshing) = self.refreshing.lock() {
+            refreshing.remove(&self.key);
+        }
+    }
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttl: Duration, store: Arc<dyn CacheStore>) -> Self {
+        ResponseCache {
+            ttl,
+            store,
+            stale_for: Duration::ZERO,
+            #[cfg(not(target_arch = "wasm32"))]
+            refreshing: Arc::default(),
+        }
+    }
+
+    /// Serves responses for `stale_for` after they go stale while they are refreshed.
+    pub(crate) fn with_stale_for(mut self, stale_for: Duration) -> Self {
+        self.stale_for = stale_for;
+        self
+    }
+
+    /// Runs a store operation, on tokio's blocking pool if the store blocks.
+    async fn run<T: Send + 'static>(
+        &self,
+        operation: impl FnOnce(&dyn CacheStore) -> T + Send + 'static,
+    ) -> T {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.store.is_blocking() {
+            let store = Arc::clone(&self.store);
+            return tokio::task::spawn_blocking(move || operation(store.as_ref()))
+                .await
+                .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+        }
+        operation(self.store.as_ref())
+    }
+
+    /// Returns the response cached under a key.
+    ///
+    /// Stale responses are only returned when they can be refreshed in the background,
+    /// which needs a tokio runtime, and otherwise count as missing.
+    async fn lookup(&self, key: &str) -> Option<CacheHit<CachedResponse>> {
+        let owned_key = key.to_string();
+        let cached = self.run(move |store| store.get(&owned_key)).await?;
+        if cached
+            .fresh_until
+            .is_none_or(|fresh_until| unix_now() < fresh_until)
+        {
+            return Some(CacheHit {
+                value: cached,
+                #[cfg(not(target_arch = "wasm32"))]
+                refresh: None,
+            });
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if !self.stale_for.is_zero() && tokio::runtime::Handle::try_current().is_ok() {
+            let claimed = self
+                .refreshing
+                .lock()
+                .is_ok_and(|mut refreshing| refreshing.insert(key.to_string()));
+            return Some(CacheHit {
+                value: cached,
+                refresh: claimed.then(|| RefreshClaim {
+                    refreshing: Arc::clone(&self.refreshing),
+                    key: key.to_string(),
+                }),
+            });
+        }
+        None
+    }
+
+    /// Stores a response, keeping it for the grace period after it goes stale.
+    async fn put(&self, key: &str, status: u16, body: Vec<u8>) {
+        let cached = CachedResponse {
+            status,
+            body,
+            fresh_until: (!self.stale_for.is_zero())
+                .then(|| unix_now().saturating_add(self.ttl.as_millis() as u64)),
+        };
+        let (key, ttl) = (key.to_string(), self.ttl.saturating_add(self.stale_for));
+        self.run(move |store| store.put(&key, cached, ttl)).await;
+    }
+
+    /// Returns the response cached for a URL.
+    pub(crate) async fn get(&self, url: &str) -> Option<CacheHit<ApiResponse>> {
+        let hit = self.lookup(url).await?;
+        Some(CacheHit {
+            value: ApiResponse {
+                status: reqwest::StatusCode::from_u16(hit.value.status).ok()?,
+                retry_after: None,
+                message: None,
+                body: hit.value.body,
+                etag: None,
+                #[cfg(all(feature = "proxy", not(target_arch = "wasm32")))]
+                forwarded_headers: reqwest::header::HeaderMap::new(),
+            },
+            #[cfg(not(target_arch = "wasm32"))]
+            refresh: hit.refresh,
+        })
+    }
+
+    /// Caches a response for a URL.
+    ///
+    /// Only successful responses and 404s, which mean "not found" for lookups, are cached.
+    pub(crate) async fn insert(&self, url: &str, resp: &ApiResponse) {
+        if !resp.status.is_success() && resp.status != reqwest::StatusCode::NOT_FOUND {
+            return;
+        }
+        self.put(url, resp.status.as_u16(), resp.body.clone()).await;
+    }
+
+    /// Returns the body of a Pwned Passwords range cached under a key, see [`range_key`].
+    pub(crate) async fn get_range(&self, key: &str) -> Option<CacheHit<Vec<u8>>> {
+        let hit = self.lookup(key).await?;
+        (hit.value.status == 200).then_some(CacheHit {
+            value: hit.value.body,
+            #[cfg(not(target_arch = "wasm32"))]
+            refresh: hit.refresh,
+        })
+    }
+
+    /// Caches the body of a Pwned Passwords range under a key, see [`range_key`].
+    pub(crate) async fn insert_range(&self, key: &str, body: Vec<u8>) {
+        self.put(key, 200, body).await;
+    }
+
+    /// Removes the responses whose key matches.
+    async fn invalidate_matching(&self, matches: impl Fn(&str) -> bool + Send + 'static) {
+        self.run(move |store| store.invalidate_matching(&matches))
+            .await;
+    }
+}
+
+/// Returns the cache key of a range URL, marking padded responses so they are kept apart.
+pub(crate) fn range_key(url: &str, padded: bool) -> String {
+    if padded {
+        format!("{}#padded", url)
+    } else {
+        url.to_string()
+    }
+}
+
+/// Whether a cache key is `base` itself or `base` with a query or `#padded` marker.
+fn key_matches(key: &str, base: &str) -> bool {
+    key.strip_prefix(base)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(['?', '#']))
+}
+
+/// Passes a body on to another sink while keeping a copy to cache.
+pub(crate) struct CopyingSink<'a, S> {
+    pub(crate) sink: &'a mut S,
+    pub(crate) copy: Vec<u8>,
+}
+
+impl<S: BodySink> BodySink for CopyingSink<'_, S> {
+    fn write(&mut self, chunk: &[u8]) {
+        self.copy.extend_from_slice(chunk);
+        self.sink.write(chunk);
+    }
+
+    fn reset(&mut self) {
+        self.copy.clear();
+        self.sink.reset();
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Serves repeated HIBP API requests from memory for a while.
+    ///
+    /// Identical requests within `ttl` of a successful response, e.g. dashboards refreshing
+    /// [`HaveIBeenPwned::get_all_breaches`] or the same account lookup, are answered
+    /// without a request and without waiting for the rate limiter, saving the
+    /// subscription's budget. Unknown accounts and Pwned Passwords ranges are cached as
+    /// well; errors never are. Clones of the client share the cache. Remove stale entries,
+    /// e.g. after a new breach load, with [`HaveIBeenPwned::purge_account`],
+    /// [`HaveIBeenPwned::purge_range`] and [`HaveIBeenPwned::purge_all`].
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - How long a response is reused.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// use std::time::Duration;
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_response_cache(Duration::from_secs(300));
+    /// ```
+    #[must_use]
+    pub fn with_response_cache(self, ttl: Duration) -> Self {
+        self.with_cache_store(MemoryCacheStore::new(), ttl)
+    }
+
+    /// Caches HIBP API responses in a custom [`CacheStore`], like
+    /// [`HaveIBeenPwned::with_response_cache`] does in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - Where responses are kept.
+    /// * `ttl` - How long a response is reused.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hibp_rs::{DiskCacheStore, HaveIBeenPwned};
+    /// use std::time::Duration;
+    ///
+    /// # fn example() -> Result<(), hibp_rs::HibpError> {
+    /// let store = DiskCacheStore::new("/tmp/hibp-cache")?;
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_cache_store(store, Duration::from_secs(3600));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_cache_store<C: CacheStore + 'static>(mut self, store: C, ttl: Duration) -> Self {
+        self.response_cache = Some(ResponseCache::new(ttl, Arc::new(store)));
+        self
+    }
+
+    /// Serves cached responses for up to `grace` after they expire while refreshing them
+    /// in the background, keeping lookup latency flat, e.g. on a login path, when entries
+    /// expire.
+    ///
+    /// Breach lookups and Pwned Passwords ranges change rarely, so a response a little
+    /// past its TTL is usually still right. The first request after a response goes stale
+    /// gets it immediately and starts one refresh, and later requests keep getting it
+    /// until the refresh stored a new one. Failed refreshes are retried by the next
+    /// request. Responses older than the TTL plus `grace` are fetched like uncached ones.
+    ///
+    /// Refreshes run on the current tokio runtime. Without one, and on WebAssembly, stale
+    /// responses are fetched again instead. Has no effect without a response cache, so call
+    /// it after [`HaveIBeenPwned::with_response_cache`] or
+    /// [`HaveIBeenPwned::with_cache_store`].
+    ///
+    /// # Arguments
+    ///
+    /// * `grace` - How long stale responses are served while they are refreshed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// use std::time::Duration;
+    ///
+    /// let hibp = HaveIBeenPwned::new("your_api_key")
+    ///     .with_response_cache(Duration::from_secs(300))
+    ///     .with_stale_while_revalidate(Duration::from_secs(3600));
+    /// ```
+    #[must_use]
+    pub fn with_stale_while_revalidate(mut self, grace: Duration) -> Self {
+        self.response_cache = self.response_cache.map(|cache| cache.with_stale_for(grace));
+        self
+    }
+
+    /// Drops all cached responses, e.g. after a new breach was announced.
+    ///
+    /// Calls the store on the current thread, see [`HaveIBeenPwned::purge_all`] for stores
+    /// that block.
+    pub fn clear_response_cache(&self) {
+        if let Some(cache) = &self.response_cache {
+            cache.store.clear();
+        }
+    }
+
+    /// Drops the cached breach and paste lookups of an account, so the next lookups fetch
+    /// them again, e.g. after a breach load that includes it.
+    ///
+    /// Accounts are matched case-insensitively. Does nothing without a response cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The email address or username.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_response_cache(std::time::Duration::from_secs(3600));
+    /// hibp.purge_account("test@example.com").await;
+    /// let breaches = hibp.get_breaches_for_account("test@example.com").await;
+    /// # }
+    /// ```
+    pub async fn purge_account(&self, account: &str) {
+        let Some(cache) = &self.response_cache else {
+            return;
+        };
+        let encoded = urlencoding::encode(account.trim()).to_lowercase();
+        let bases: Vec<String> = ["breachedaccount", "pasteaccount"]
+            .iter()
+            .map(|endpoint| format!("{}/{}/{}", self.base_url, endpoint, encoded).to_lowercase())
+            .collect();
+        cache
+            .invalidate_matching(move |key| {
+                let key = key.to_lowercase();
+                bases.iter().any(|base| key_matches(&key, base))
+            })
+            .await;
+    }
+
+    /// Drops the cached Pwned Passwords range of a hash prefix, in SHA-1 and NTLM mode
+    /// and with or without padding.
+    ///
+    /// Does nothing without a response cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_prefix` - First 5 characters of a password hash, in any case.
+    pub async fn purge_range(&self, hash_prefix: &str) -> Result<(), HibpError> {
+        if hash_prefix.len() != hash::PREFIX_LEN
+            || !hash_prefix.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(HibpError::InvalidInput(
+                "Hash prefix must be exactly 5 hex characters".into(),
+            ));
+        }
+        let Some(cache) = &self.response_cache else {
+            return Ok(());
+        };
+        let base = format!("{}/range/{}", self.passwords_base_url, hash_prefix).to_lowercase();
+        cache
+            .invalidate_matching(move |key| key_matches(&key.to_lowercase(), &base))
+            .await;
+        Ok(())
+    }
+
+    /// Drops all cached responses, like [`HaveIBeenPwned::clear_response_cache`], calling
+    /// blocking stores on tokio's blocking pool.
+    pub async fn purge_all(&self) {
+        if let Some(cache) = &self.response_cache {
+            cache.run(|store| store.clear()).await;
+        }
+    }
+}
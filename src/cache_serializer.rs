@@ -0,0 +1,140 @@
+use crate::{CachedResponse, HibpError};
+use std::sync::Arc;
+
+/// Encodes cached responses for stores that keep them as bytes, e.g. `DiskCacheStore`.
+///
+/// Responses are stored as JSON by default with [`JsonCacheSerializer`]. Choose
+/// `MessagePackCacheSerializer` (`msgpack` feature) for smaller entries, or implement the
+/// trait to encrypt entries or match a format your schema registry knows. Entries a
+/// serializer can't read, e.g. ones written with another serializer, are treated as
+/// missing and fetched again.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{CacheSerializer, CachedResponse, HibpError, JsonCacheSerializer};
+///
+/// /// Obfuscates entries on top of JSON, standing in for real encryption.
+/// #[derive(Debug)]
+/// struct XorSerializer(u8);
+///
+/// impl CacheSerializer for XorSerializer {
+///     fn serialize(&self, response: &CachedResponse) -> Result<Vec<u8>, HibpError> {
+///         let bytes = JsonCacheSerializer.serialize(response)?;
+///         Ok(bytes.into_iter().map(|b| b ^ self.0).collect())
+///     }
+///
+///     fn deserialize(&self, bytes: &[u8]) -> Result<CachedResponse, HibpError> {
+///         let bytes: Vec<u8> = bytes.iter().map(|b| b ^ self.0).collect();
+///         JsonCacheSerializer.deserialize(&bytes)
+///     }
+/// }
+/// ```
+pub trait CacheSerializer: std::fmt::Debug + Send + Sync {
+    /// Encodes a response.
+    fn serialize(&self, response: &CachedResponse) -> Result<Vec<u8>, HibpError>;
+
+    /// Decodes a response encoded by [`CacheSerializer::serialize`].
+    fn deserialize(&self, bytes: &[u8]) -> Result<CachedResponse, HibpError>;
+}
+
+/// Lets several stores share a serializer.
+impl<S: CacheSerializer + ?Sized> CacheSerializer for Arc<S> {
+    fn serialize(&self, response: &CachedResponse) -> Result<Vec<u8>, HibpError> {
+        (**self).serialize(response)
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<CachedResponse, HibpError> {
+        (**self).deserialize(bytes)
+    }
+}
+
+/// Stores responses as JSON objects with the status and the body as text. The default
+/// [`CacheSerializer`].
+///
+/// HIBP responses are text, so bodies that aren't UTF-8 fail to serialize and aren't
+/// cached.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCacheSerializer;
+
+impl CacheSerializer for JsonCacheSerializer {
+    fn serialize(&self, response: &CachedResponse) -> Result<Vec<u8>, HibpError> {
+        Ok(serde_json::to_vec(response)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<CachedResponse, HibpError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// Stores responses as MessagePack maps with the body as binary, which is smaller and
+/// faster to read than JSON.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessagePackCacheSerializer;
+
+#[cfg(feature = "msgpack")]
+impl CacheSerializer for MessagePackCacheSerializer {
+    fn serialize(&self, response: &CachedResponse) -> Result<Vec<u8>, HibpError> {
+        rmp_serde::to_vec_named(response).map_err(HibpError::other)
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<CachedResponse, HibpError> {
+        rmp_serde::from_slice(bytes).map_err(HibpError::other)
+    }
+}
+
+/// Serde format of response bodies: text in human-readable formats, bytes otherwise.
+pub(crate) mod body {
+    use serde::de::Visitor;
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(body: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(std::str::from_utf8(body).map_err(S::Error::custom)?)
+        } else {
+            serializer.serialize_bytes(body)
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        if deserializer.is_human_readable() {
+            String::deserialize(deserializer).map(String::into_bytes)
+        } else {
+            deserializer.deserialize_byte_buf(BytesVisitor)
+        }
+    }
+
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("a response body")
+        }
+
+        fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(bytes.to_vec())
+        }
+
+        fn visit_byte_buf<E: serde::de::Error>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+            Ok(bytes)
+        }
+
+        fn visit_str<E: serde::de::Error>(self, text: &str) -> Result<Vec<u8>, E> {
+            Ok(text.as_bytes().to_vec())
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            Ok(bytes)
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{Breach, HaveIBeenPwned, HibpError};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Deduplicates strings, handing out shared references to one allocation per distinct value.
+#[derive(Debug, Default)]
+pub(crate) struct Interner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    /// Returns the shared copy of a string, storing it on first use.
+    pub(crate) fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(existing) = self.strings.get(value) {
+            return existing.clone();
+        }
+        let value: Arc<str> = Arc::from(value);
+        self.strings.insert(value.clone());
+        value
+    }
+}
+
+/// A breach of a [`BreachCatalogue`], with repeated strings shared between breaches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogueBreach {
+    /// Name of the breach.
+    pub name: Box<str>,
+    /// Title of the breach.
+    pub title: Box<str>,
+    /// Domain associated with the breach, shared with other breaches of the domain.
+    pub domain: Arc<str>,
+    /// Date the breach occurred.
+    pub breach_date: NaiveDate,
+    /// Time the breach was added to HIBP.
+    pub added_date: DateTime<Utc>,
+    /// Time the breach was last modified.
+    pub modified_date: DateTime<Utc>,
+    /// Number of accounts affected.
+    pub pwn_count: u64,
+    /// Description of the breach.
+    pub description: Box<str>,
+    /// Path to the breach logo.
+    pub logo_path: Box<str>,
+    /// Data classes compromised in the breach, shared between all breaches.
+    pub data_classes: Box<[Arc<str>]>,
+    /// Whether the breach is verified.
+    pub is_verified: bool,
+    /// Whether the breach is fabricated.
+    pub is_fabricated: bool,
+    /// Whether the breach is sensitive.
+    pub is_sensitive: bool,
+    /// Whether the breach is retired.
+    pub is_retired: bool,
+    /// Whether the breach is a spam list.
+    pub is_spam_list: bool,
+    /// Whether the breach is related to malware.
+    pub is_malware: bool,
+    /// Whether the breach is a stealer log.
+    pub is_stealer_log: bool,
+    /// Whether the breach is subscription-free.
+    pub is_subscription_free: bool,
+}
+
+/// A long-lived snapshot of all breaches in HIBP.
+///
+/// Domains and data class names repeat across hundreds of breaches. The
+/// catalogue stores each distinct value once and shares it, and keeps the remaining
+/// strings without spare capacity, so a cached catalogue needs noticeably less memory
+/// than the `Vec<Breach>` it was built from.
+///
+/// # Example
+///
+/// ```no_run
+/// # use hibp_rs::HaveIBeenPwned;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let hibp = HaveIBeenPwned::new("your_api_key");
+/// let catalogue = hibp.get_breach_catalogue().await?;
+/// println!(
+///     "{} breaches with {} data classes",
+///     catalogue.len(),
+///     catalogue.data_classes().len()
+/// );
+/// if let Some(adobe) = catalogue.get("adobe") {
+///     println!("{} accounts", adobe.pwn_count);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct BreachCatalogue {
+    breaches: Vec<CatalogueBreach>,
+    interner: Interner,
+}
+
+impl BreachCatalogue {
+    /// Builds a catalogue from breaches returned by the API.
+    ///
+    /// # Arguments
+    ///
+    /// * `breaches` - The breaches to store.
+    pub fn from_breaches(breaches: Vec<Breach>) -> Self {
+        let mut catalogue = BreachCatalogue::default();
+        catalogue.extend(breaches);
+        catalogue
+    }
+
+    /// Adds breaches to the catalogue, sharing strings with the breaches already stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `breaches` - The breaches to add.
+    pub fn extend(&mut self, breaches: Vec<Breach>) {
+        self.breaches.reserve(breaches.len());
+        for breach in breaches {
+            let entry = CatalogueBreach {
+                name: breach.name.into_boxed_str(),
+                title: breach.title.into_boxed_str(),
+                domain: self.interner.intern(&breach.domain),
+                breach_date: breach.breach_date,
+                added_date: breach.added_date,
+                modified_date: breach.modified_date,
+                pwn_count: breach.pwn_count,
+                description: breach.description.into_boxed_str(),
+                logo_path: breach.logo_path.into_boxed_str(),
+                data_classes: breach
+                    .data_classes
+                    .iter()
+                    .map(|class| self.interner.intern(class))
+                    .collect(),
+                is_verified: breach.is_verified,
+                is_fabricated: breach.is_fabricated,
+                is_sensitive: breach.is_sensitive,
+                is_retired: breach.is_retired,
+                is_spam_list: breach.is_spam_list,
+                is_malware: breach.is_malware,
+                is_stealer_log: breach.is_stealer_log,
+                is_subscription_free: breach.is_subscription_free,
+            };
+            self.breaches.push(entry);
+        }
+    }
+
+    /// Returns the number of breaches in the catalogue.
+    pub fn len(&self) -> usize {
+        self.breaches.len()
+    }
+
+    /// Whether the catalogue contains no breaches.
+    pub fn is_empty(&self) -> bool {
+        self.breaches.is_empty()
+    }
+
+    /// Returns an iterator over all breaches.
+    pub fn iter(&self) -> std::slice::Iter<'_, CatalogueBreach> {
+        self.breaches.iter()
+    }
+
+    /// Returns the breach with the given name, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The [`Breach::name`] of the breach.
+    pub fn get(&self, name: &str) -> Option<&CatalogueBreach> {
+        self.breaches
+            .iter()
+            .find(|b| b.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the distinct data classes of all breaches, sorted by name.
+    pub fn data_classes(&self) -> Vec<Arc<str>> {
+        let mut classes: Vec<Arc<str>> = self
+            .breaches
+            .iter()
+            .flat_map(|b| b.data_classes.iter().cloned())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        classes.sort();
+        classes
+    }
+}
+
+impl<'a> IntoIterator for &'a BreachCatalogue {
+    type Item = &'a CatalogueBreach;
+    type IntoIter = std::slice::Iter<'a, CatalogueBreach>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Gets all breaches in the system as a memory-efficient [`BreachCatalogue`].
+    ///
+    /// Prefer this over [`HaveIBeenPwned::get_all_breaches`] when keeping the breach list in
+    /// memory for a long time, e.g. as a cached snapshot in a service.
+    pub async fn get_breach_catalogue(&self) -> Result<BreachCatalogue, HibpError> {
+        let breaches = self.get_all_breaches().await?;
+        Ok(BreachCatalogue::from_breaches(breaches))
+    }
+}
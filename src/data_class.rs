@@ -0,0 +1,200 @@
+use crate::Breach;
+use std::fmt;
+
+/// Defines [`DataClass`] with the name HIBP uses for each known class.
+macro_rules! data_classes {
+    ($($(#[$doc:meta])* $variant:ident => $name:literal,)*) => {
+        /// A kind of data exposed in a breach, like `Email addresses` or `Passwords`.
+        ///
+        /// Covers the classes HIBP lists most often; see
+        /// [`crate::HaveIBeenPwned::get_all_data_classes`] for the full list. Classes without
+        /// a variant are kept as [`DataClass::Other`], so new classes added by HIBP never
+        /// fail to parse. Converts from and to the name used by the API.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use hibp_rs::DataClass;
+        /// assert_eq!(DataClass::from("Passwords"), DataClass::Passwords);
+        /// assert_eq!(DataClass::from("Astrological signs").as_str(), "Astrological signs");
+        /// ```
+        #[derive(
+            Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+        )]
+        #[serde(from = "String", into = "String")]
+        #[non_exhaustive]
+        pub enum DataClass {
+            $($(#[$doc])* $variant,)*
+            /// Any other class, with its name as sent by the API.
+            Other(String),
+        }
+
+        impl DataClass {
+            /// Returns the name HIBP uses for the class, e.g. `Email addresses`.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(DataClass::$variant => $name,)*
+                    DataClass::Other(name) => name,
+                }
+            }
+        }
+
+        impl From<&str> for DataClass {
+            fn from(name: &str) -> Self {
+                match name {
+                    $($name => DataClass::$variant,)*
+                    other => DataClass::Other(other.to_string()),
+                }
+            }
+        }
+    };
+}
+
+data_classes! {
+    /// Account balances.
+    AccountBalances => "Account balances",
+    /// Authentication tokens.
+    AuthTokens => "Auth tokens",
+    /// Bank account numbers.
+    BankAccountNumbers => "Bank account numbers",
+    /// Browser user agent details.
+    BrowserUserAgentDetails => "Browser user agent details",
+    /// Credit card CVVs.
+    CreditCardCvv => "Credit card CVV",
+    /// Credit card numbers.
+    CreditCards => "Credit cards",
+    /// Dates of birth.
+    DatesOfBirth => "Dates of birth",
+    /// Device information.
+    DeviceInformation => "Device information",
+    /// Education levels.
+    EducationLevels => "Education levels",
+    /// Email addresses.
+    EmailAddresses => "Email addresses",
+    /// Employers.
+    Employers => "Employers",
+    /// Ethnicities.
+    Ethnicities => "Ethnicities",
+    /// Genders.
+    Genders => "Genders",
+    /// Geographic locations.
+    GeographicLocations => "Geographic locations",
+    /// Government issued IDs.
+    GovernmentIssuedIds => "Government issued IDs",
+    /// Passwords no longer in use.
+    HistoricalPasswords => "Historical passwords",
+    /// IP addresses.
+    IpAddresses => "IP addresses",
+    /// Job titles.
+    JobTitles => "Job titles",
+    /// Marital statuses.
+    MaritalStatuses => "Marital statuses",
+    /// Names.
+    Names => "Names",
+    /// Nationalities.
+    Nationalities => "Nationalities",
+    /// Partial credit card data, e.g. the last digits.
+    PartialCreditCardData => "Partial credit card data",
+    /// Passport numbers.
+    PassportNumbers => "Passport numbers",
+    /// Password hints.
+    PasswordHints => "Password hints",
+    /// Passwords, in plain text or hashed.
+    Passwords => "Passwords",
+    /// Payment histories.
+    PaymentHistories => "Payment histories",
+    /// Phone numbers.
+    PhoneNumbers => "Phone numbers",
+    /// Physical addresses.
+    PhysicalAddresses => "Physical addresses",
+    /// Private messages.
+    PrivateMessages => "Private messages",
+    /// Purchases.
+    Purchases => "Purchases",
+    /// Religions.
+    Religions => "Religions",
+    /// Salutations.
+    Salutations => "Salutations",
+    /// Security questions and answers.
+    SecurityQuestionsAndAnswers => "Security questions and answers",
+    /// Sexual orientations.
+    SexualOrientations => "Sexual orientations",
+    /// Social media profiles.
+    SocialMediaProfiles => "Social media profiles",
+    /// Social security numbers.
+    SocialSecurityNumbers => "Social security numbers",
+    /// Spoken languages.
+    SpokenLanguages => "Spoken languages",
+    /// Time zones.
+    TimeZones => "Time zones",
+    /// Usernames.
+    Usernames => "Usernames",
+    /// Website activity.
+    WebsiteActivity => "Website activity",
+    /// Websites.
+    Websites => "Websites",
+}
+
+impl From<String> for DataClass {
+    fn from(name: String) -> Self {
+        match DataClass::from(name.as_str()) {
+            DataClass::Other(_) => DataClass::Other(name),
+            class => class,
+        }
+    }
+}
+
+impl From<DataClass> for String {
+    fn from(class: DataClass) -> Self {
+        match class {
+            DataClass::Other(name) => name,
+            class => class.as_str().to_string(),
+        }
+    }
+}
+
+impl std::str::FromStr for DataClass {
+    type Err = std::convert::Infallible;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(DataClass::from(name))
+    }
+}
+
+impl fmt::Display for DataClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Breach {
+    /// Returns the data classes exposed in the breach as [`DataClass`] values.
+    pub fn typed_data_classes(&self) -> Vec<DataClass> {
+        self.data_classes
+            .iter()
+            .map(|name| DataClass::from(name.as_str()))
+            .collect()
+    }
+
+    /// Whether the breach exposed a class of data.
+    ///
+    /// # Arguments
+    ///
+    /// * `class` - The data class to look for
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{DataClass, HaveIBeenPwned};
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let breaches = hibp.get_breaches_for_account("test@example.com").await.unwrap();
+    /// for breach in breaches.iter().filter(|b| b.exposes(DataClass::Passwords)) {
+    ///     println!("Change the password you used on {}", breach.domain);
+    /// }
+    /// # }
+    /// ```
+    pub fn exposes(&self, class: DataClass) -> bool {
+        self.data_classes.iter().any(|name| name == class.as_str())
+    }
+}
@@ -0,0 +1,152 @@
+use crate::Breach;
+
+/// A link in the description of a breach.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DescriptionLink {
+    /// Target of the link, e.g. `https://example.com/disclosure`.
+    pub url: String,
+    /// Text of the link, without markup.
+    pub text: String,
+}
+
+impl Breach {
+    /// Returns the description without HTML markup, e.g. for terminals and plain-text reports.
+    ///
+    /// Tags are removed, line breaks and paragraph ends become newlines, and character
+    /// references like `&quot;` are decoded. Link targets are dropped; get them with
+    /// [`Breach::description_links`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let breach = hibp.get_breach_by_name("Adobe").await.unwrap();
+    /// println!("{}", breach.description_plain());
+    /// # }
+    /// ```
+    pub fn description_plain(&self) -> String {
+        parse(&self.description).0
+    }
+
+    /// Returns the links in the description, in order of appearance.
+    pub fn description_links(&self) -> Vec<DescriptionLink> {
+        parse(&self.description).1
+    }
+}
+
+/// Splits HTML into its plain text and links.
+fn parse(html: &str) -> (String, Vec<DescriptionLink>) {
+    let mut text = String::new();
+    let mut links = Vec::new();
+    // Link being read: its URL and where its text starts
+    let mut open_link: Option<(String, usize)> = None;
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..start]));
+        let Some(end) = rest[start..].find('>') else {
+            // Not a tag after all
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let closing = tag.starts_with('/');
+        match (name.as_str(), closing) {
+            ("a", false) => {
+                open_link = attribute(tag, "href").map(|url| (decode_entities(url), text.len()));
+            }
+            ("a", true) => {
+                if let Some((url, text_start)) = open_link.take() {
+                    links.push(DescriptionLink {
+                        url,
+                        text: text[text_start..].trim().to_string(),
+                    });
+                }
+            }
+            ("br", _) | ("p", true) | ("li", true) => text.push('\n'),
+            _ => {}
+        }
+    }
+    text.push_str(&decode_entities(rest));
+
+    (text.trim().to_string(), links)
+}
+
+/// Returns the value of an attribute of a tag, quoted or not.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let mut search = 0;
+    while let Some(found) = lower[search..].find(name) {
+        let at = search + found;
+        search = at + name.len();
+        let preceded_by_space = lower[..at].ends_with(char::is_whitespace);
+        let after = lower[search..].trim_start();
+        if !preceded_by_space || !after.starts_with('=') {
+            continue;
+        }
+        let value = tag[tag.len() - after.len() + 1..].trim_start();
+        return Some(match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next().unwrap_or_default(),
+            _ => value.split(char::is_whitespace).next().unwrap_or_default(),
+        });
+    }
+    None
+}
+
+/// Decodes named and numeric character references. Unknown references are kept as is.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "ndash" => Some('\u{2013}'),
+            "mdash" => Some('\u{2014}'),
+            "lsquo" => Some('\u{2018}'),
+            "rsquo" => Some('\u{2019}'),
+            "ldquo" => Some('\u{201C}'),
+            "rdquo" => Some('\u{201D}'),
+            "hellip" => Some('\u{2026}'),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        });
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
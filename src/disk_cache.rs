@@ -0,0 +1,290 @@
+use crate::cache::unix_now;
+use crate::hashing::to_hex_upper;
+use crate::schema;
+use crate::{
+    CacheSerializer, CacheStore, CachedResponse, HibpError, JsonCacheSerializer, Migrations,
+};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Extension of cache entry files.
+const ENTRY_EXTENSION: &str = "cache";
+
+/// File of a cache directory holding the schema version of its entries.
+const VERSION_FILE: &str = "schema-version";
+
+/// Number of temporary files created by this process, to name the next one.
+static TEMP_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// Keeps cached responses in a directory, so CLI runs and short-lived functions reuse
+/// them across process restarts.
+///
+/// Each response is a file named after the SHA-256 hash of its URL, holding its expiry,
+/// URL and the response encoded by a [`CacheSerializer`], JSON unless set with
+/// [`DiskCacheStore::with_serializer`]. Entries are written to a temporary file first and then renamed, so
+/// several processes can share the directory. Expired entries are removed when read.
+/// The store reads and writes files synchronously, so the client calls it on tokio's
+/// blocking pool, see [`CacheStore::is_blocking`].
+///
+/// Responses for account lookups reveal which breaches an account is in, so on Unix the
+/// directory is created readable by its owner only, and entry files are created with mode
+/// `0600`. Keep an existing directory private as well.
+///
+/// The directory is stamped with [`DiskCacheStore::SCHEMA_VERSION`]. Entries written by
+/// older versions of the crate are upgraded when the store is opened, see [`Migrations`].
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{DiskCacheStore, HaveIBeenPwned};
+/// use std::time::Duration;
+///
+/// # fn example() -> Result<(), hibp_rs::HibpError> {
+/// let store = DiskCacheStore::new(std::env::temp_dir().join("hibp-cache"))?;
+/// let hibp = HaveIBeenPwned::new("your_api_key").with_cache_store(store, Duration::from_secs(3600));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiskCacheStore {
+    dir: PathBuf,
+    serializer: Arc<dyn CacheSerializer>,
+}
+
+impl DiskCacheStore {
+    /// Schema version of entry files. Directories without a version are version 0.
+    pub const SCHEMA_VERSION: u32 = 3;
+
+    /// Returns the migrations the crate applies to entries of older schema versions.
+    ///
+    /// Add steps to them and open the store with [`DiskCacheStore::new_with`] to
+    /// customize upgrades.
+    pub fn migrations() -> Migrations<Vec<u8>> {
+        // Version 1 only added the version stamp. Version 2 records the URL of each entry
+        // so purges can find it, which older entries can't recover, so they are dropped.
+        // Version 3 encodes the status and body with a serializer, converting older
+        // entries to JSON.
+        Migrations::new()
+            .with_step(1, |_| {
+                Err(HibpError::InvalidInput(
+                    "Cache entries of schema version 1 don't record their URL".into(),
+                ))
+            })
+            .with_step(2, |entry: &mut Vec<u8>| {
+                let invalid = || HibpError::InvalidInput("Invalid cache entry".into());
+                let (header, rest) = split_line(entry).ok_or_else(invalid)?;
+                let (key, body) = split_line(rest).ok_or_else(invalid)?;
+                let (expires, status) = header.split_once(' ').ok_or_else(invalid)?;
+                let response = CachedResponse {
+                    status: status.parse().map_err(|_| invalid())?,
+                    body: body.to_vec(),
+                    fresh_until: None,
+                };
+                let mut contents = format!("{}\n{}\n", expires, key).into_bytes();
+                contents.extend(JsonCacheSerializer.serialize(&response)?);
+                *entry = contents;
+                Ok(())
+            })
+    }
+
+    /// Opens a cache directory, creating it if needed.
+    ///
+    /// Entries of older schema versions are upgraded with [`DiskCacheStore::migrations`],
+    /// and directories of newer versions are rejected with [`HibpError::InvalidInput`].
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory to keep responses in.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self, HibpError> {
+        Self::new_with(dir, &Self::migrations())
+    }
+
+    /// Opens a cache directory like [`DiskCacheStore::new`], upgrading entries of older
+    /// schema versions with custom migrations.
+    ///
+    /// Each entry file is migrated on its own. Entries a step fails for are removed, as
+    /// the responses can be fetched again.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory to keep responses in.
+    /// * `migrations` - Steps upgrading the contents of entry files of older versions.
+    pub fn new_with<P: AsRef<Path>>(
+        dir: P,
+        migrations: &Migrations<Vec<u8>>,
+    ) -> Result<Self, HibpError> {
+        let dir = dir.as_ref().to_path_buf();
+        let mut builder = std::fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(&dir)?;
+        let store = DiskCacheStore {
+            dir,
+            serializer: Arc::new(JsonCacheSerializer),
+        };
+        store.migrate(migrations)?;
+        Ok(store)
+    }
+
+    /// Upgrades the entries to [`DiskCacheStore::SCHEMA_VERSION`] and stamps the directory.
+    fn migrate(&self, migrations: &Migrations<Vec<u8>>) -> Result<(), HibpError> {
+        let version_path = self.dir.join(VERSION_FILE);
+        let version = match std::fs::read_to_string(&version_path) {
+            Ok(version) => version.trim().parse().map_err(|_| {
+                HibpError::InvalidInput(format!("Invalid cache schema version: {}", version))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+        if version == Self::SCHEMA_VERSION {
+            return Ok(());
+        }
+        schema::check_supported(version, Self::SCHEMA_VERSION)?;
+        for entry in std::fs::read_dir(&self.dir)?.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != ENTRY_EXTENSION) {
+                continue;
+            }
+            let Ok(mut contents) = std::fs::read(&path) else {
+                continue;
+            };
+            match migrations.migrate(&mut contents, version, Self::SCHEMA_VERSION) {
+                Ok(()) => self.replace(&path, &contents),
+                Err(_) => {
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+        }
+        std::fs::write(&version_path, Self::SCHEMA_VERSION.to_string())?;
+        Ok(())
+    }
+
+    /// Encodes responses with another serializer, e.g. to encrypt them.
+    ///
+    /// Entries written with another serializer can't be read and are fetched again.
+    ///
+    /// # Arguments
+    ///
+    /// * `serializer` - Encodes the responses in entry files.
+    #[must_use]
+    pub fn with_serializer<S: CacheSerializer + 'static>(mut self, serializer: S) -> Self {
+        self.serializer = Arc::new(serializer);
+        self
+    }
+
+    /// Returns the directory responses are kept in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes an entry file through a temporary file, ignoring failures.
+    fn replace(&self, path: &Path, contents: &[u8]) {
+        // Unique per write, so concurrent writers in any process never share a temporary file
+        let temp = path.with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        if write_private(&temp, contents).is_err() || std::fs::rename(&temp, path).is_err() {
+            let _ = std::fs::remove_file(&temp);
+        }
+    }
+
+    /// Removes the entry files whose contents `remove` returns `true` for. Entries that
+    /// can't be read are left for the next read to clean up.
+    fn remove_entries(&self, remove: impl Fn(&[u8]) -> bool) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != ENTRY_EXTENSION) {
+                continue;
+            }
+            if std::fs::read(&path).is_ok_and(|contents| remove(&contents)) {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let name = to_hex_upper(&Sha256::digest(key.as_bytes()));
+        self.dir.join(format!("{}.{}", name, ENTRY_EXTENSION))
+    }
+}
+
+/// Writes a new file that only its owner can read on Unix, failing if it already exists.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
+
+/// Splits the first line off an entry file.
+fn split_line(contents: &[u8]) -> Option<(&str, &[u8])> {
+    let newline = contents.iter().position(|&b| b == b'\n')?;
+    Some((
+        std::str::from_utf8(&contents[..newline]).ok()?,
+        &contents[newline + 1..],
+    ))
+}
+
+/// Reads an entry file: a line with its expiry in Unix milliseconds, a line with its key,
+/// then the serialized response.
+fn parse_entry(contents: &[u8]) -> Option<(u64, &str, &[u8])> {
+    let (expires, rest) = split_line(contents)?;
+    let (key, response) = split_line(rest)?;
+    Some((expires.parse().ok()?, key, response))
+}
+
+impl CacheStore for DiskCacheStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let path = self.entry_path(key);
+        let contents = std::fs::read(&path).ok()?;
+        let response = parse_entry(&contents)
+            .filter(|&(expires, stored, _)| stored == key && unix_now() < expires)
+            .and_then(|(_, _, response)| self.serializer.deserialize(response).ok());
+        if response.is_none() {
+            let _ = std::fs::remove_file(&path);
+        }
+        response
+    }
+
+    fn put(&self, key: &str, response: CachedResponse, ttl: Duration) {
+        let path = self.entry_path(key);
+        let expires = unix_now().saturating_add(ttl.as_millis() as u64);
+        let Ok(response) = self.serializer.serialize(&response) else {
+            return;
+        };
+        let mut contents = format!("{}\n{}\n", expires, key).into_bytes();
+        contents.extend(response);
+        self.replace(&path, &contents);
+    }
+
+    fn invalidate(&self, key: &str) {
+        let _ = std::fs::remove_file(self.entry_path(key));
+    }
+
+    fn invalidate_matching(&self, matches: &dyn Fn(&str) -> bool) {
+        self.remove_entries(|contents| {
+            split_line(contents)
+                .and_then(|(_, rest)| split_line(rest))
+                .is_none_or(|(key, _)| matches(key))
+        });
+    }
+
+    fn is_blocking(&self) -> bool {
+        true
+    }
+
+    fn clear(&self) {
+        self.remove_entries(|_| true);
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{DomainBreachEntry, HaveIBeenPwned, HibpError, ResultSink};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashSet};
+
+/// Breached email aliases of a domain, each mapped to the names of the breaches it
+/// appears in.
+///
+/// Aliases are the part of the address before the `@`, e.g. `john` for
+/// `john@example.com`.
+pub type DomainBreaches = BTreeMap<String, Vec<String>>;
+
+impl HaveIBeenPwned {
+    /// Gets all breached email addresses of a domain, with the breaches each appears in.
+    ///
+    /// The domain must be verified on the subscription of the API key, see
+    /// [`HaveIBeenPwned::get_all_subscribed_domains`]; other domains are rejected by the
+    /// API. Returns an empty map if no address of the domain has been breached. How many
+    /// addresses can be returned depends on the subscription, see
+    /// [`crate::SubscriptionStatus::domain_search_max_breached_accounts`].
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The verified domain to search, e.g. `example.com`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let accounts = hibp.get_breached_accounts_for_domain("example.com").await.unwrap();
+    /// for (alias, breaches) in &accounts {
+    ///     println!("{}@example.com: {}", alias, breaches.join(", "));
+    /// }
+    /// # }
+    /// ```
+    pub async fn get_breached_accounts_for_domain(
+        &self,
+        domain: &str,
+    ) -> Result<DomainBreaches, HibpError> {
+        let encoded_domain = urlencoding::encode(domain.trim());
+        let url = format!("{}/breacheddomain/{}", self.base_url, encoded_domain);
+        let resp = self.send_api_get("breacheddomain", &url).await?;
+
+        if resp.status.is_success() {
+            let accounts: DomainBreaches = self.decode_json(&resp)?;
+            Ok(accounts)
+        } else if resp.status.as_u16() == 404 {
+            Ok(DomainBreaches::new())
+        } else {
+            Err(resp.error())
+        }
+    }
+
+    /// Gets the breached email addresses of a domain, keeping only breaches added to HIBP
+    /// after the given time.
+    ///
+    /// Like [`HaveIBeenPwned::get_breached_accounts_for_domain`], but each alias only lists
+    /// the breaches added after `since`, and aliases without such breaches are left out, so
+    /// recurring domain audits only report new findings. Added dates are taken from the
+    /// breach snapshot of [`HaveIBeenPwned::get_breaches_added_since`]. With `since` set to
+    /// `None` all breaches are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The verified domain to search, e.g. `example.com`.
+    /// * `since` - Only return breaches added after this time, e.g. from
+    ///   [`crate::AuditState::start_run`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{AuditState, HaveIBeenPwned};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let mut state = AuditState::load("domain-audit.json")?;
+    /// let accounts = hibp
+    ///     .get_breached_accounts_for_domain_since("example.com", state.start_run())
+    ///     .await?;
+    /// for (alias, breaches) in &accounts {
+    ///     println!("{}@example.com is in new breaches: {}", alias, breaches.join(", "));
+    /// }
+    /// state.save("domain-audit.json")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_breached_accounts_for_domain_since(
+        &self,
+        domain: &str,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<DomainBreaches, HibpError> {
+        let mut accounts = self.get_breached_accounts_for_domain(domain).await?;
+        let Some(since) = since else {
+            return Ok(accounts);
+        };
+        if accounts.is_empty() {
+            return Ok(accounts);
+        }
+
+        let added: HashSet<String> = self
+            .get_breaches_added_since(since)
+            .await?
+            .into_iter()
+            .map(|breach| breach.name)
+            .collect();
+        accounts.retain(|_, breaches| {
+            breaches.retain(|name| added.contains(name));
+            !breaches.is_empty()
+        });
+        Ok(accounts)
+    }
+
+    /// Gets all breached email addresses of a domain, writing one entry per alias to a sink.
+    ///
+    /// Like [`HaveIBeenPwned::get_breached_accounts_for_domain`], but each alias goes to
+    /// the sink as a [`DomainBreachEntry`], in alias order, e.g. to write a CSV report.
+    /// The sink is flushed but not closed. Returns the number of breached aliases.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The verified domain to search, e.g. `example.com`.
+    /// * `sink` - Destination of the entries.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{CsvSink, HaveIBeenPwned, ResultSink};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let mut sink = CsvSink::new(std::fs::File::create("example.com.csv")?);
+    /// let breached = hibp
+    ///     .get_breached_accounts_for_domain_into("example.com", &mut sink)
+    ///     .await?;
+    /// sink.close().await?;
+    /// eprintln!("{} addresses were breached", breached);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_breached_accounts_for_domain_into<S: ResultSink<DomainBreachEntry>>(
+        &self,
+        domain: &str,
+        sink: &mut S,
+    ) -> Result<usize, HibpError> {
+        let accounts = self.get_breached_accounts_for_domain(domain).await?;
+        for (alias, breaches) in &accounts {
+            sink.write(DomainBreachEntry {
+                alias: alias.clone(),
+                breaches: breaches.clone(),
+            })
+            .await?;
+        }
+        sink.flush().await?;
+
+        Ok(accounts.len())
+    }
+}
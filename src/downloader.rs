@@ -0,0 +1,609 @@
+use crate::binary_corpus;
+use crate::hash;
+use crate::password::RangeMode;
+use crate::progress::{Progress, ProgressHook, ProgressTracker};
+use crate::runtime::{self, Instant};
+use crate::{HaveIBeenPwned, HibpError};
+use chrono::NaiveTime;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::task::JoinSet;
+
+/// Number of hash prefix ranges in the Pwned Passwords corpus (16^5).
+pub const RANGE_COUNT: u32 = 0x100000;
+
+/// Totals of a finished corpus download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DownloadSummary {
+    /// Number of ranges downloaded.
+    pub ranges: u32,
+    /// Number of hashes across all ranges.
+    pub hashes: u64,
+    /// Size of all range bodies, in bytes.
+    pub bytes: u64,
+}
+
+/// Downloads the Pwned Passwords corpus range by range, for offline auditing.
+///
+/// Like the official downloader, every one of the [`RANGE_COUNT`] ranges is fetched from
+/// the range API with many requests in flight at once. Ranges are requested without
+/// padding, and failed requests are retried according to the client's
+/// [`crate::RetryPolicy`]. Point the client at a mirror with
+/// [`HaveIBeenPwned::builder`] and `with_passwords_base_url` to download from elsewhere.
+///
+/// To run the sync on a production network, cap the requests in flight with
+/// [`CorpusDownloader::with_parallelism`], the average bandwidth with
+/// [`CorpusDownloader::with_max_bandwidth`], and restrict it to quiet hours with
+/// [`CorpusDownloader::with_nice_hours`].
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{CorpusDownloader, HaveIBeenPwned};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let downloader = CorpusDownloader::new(HaveIBeenPwned::new_free_tier()).with_parallelism(128);
+/// let summary = downloader.download_to_file("pwnedpasswords.txt").await?;
+/// println!("Downloaded {} hashes in {} ranges", summary.hashes, summary.ranges);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct CorpusDownloader {
+    client: HaveIBeenPwned,
+    parallelism: usize,
+    mode: RangeMode,
+    prefixes: Range<u32>,
+    max_bandwidth: Option<u64>,
+    nice_hours: Option<NiceHours>,
+    progress: Option<ProgressHook>,
+}
+
+/// Turns the outcome of a finished upload into a result.
+#[cfg(feature = "object-store")]
+fn join_upload<T>(
+    upload: Option<Result<object_store::Result<T>, tokio::task::JoinError>>,
+) -> Result<(), HibpError> {
+    match upload {
+        Some(Ok(Err(e))) => Err(HibpError::other(e)),
+        Some(Err(e)) => Err(HibpError::other(e)),
+        _ => Ok(()),
+    }
+}
+
+/// Body and `ETag` of a fetched range, or `None` if it was unchanged.
+type FetchedRange = Option<(Vec<u8>, Option<String>)>;
+
+/// Name of the file keeping the `ETag` of every range in a corpus directory.
+const ETAGS_FILE: &str = "etags.txt";
+
+/// Reads the `PREFIX ETAG` lines of an `ETag` file, if there is one.
+async fn read_etags(path: &Path) -> Result<BTreeMap<u32, String>, HibpError> {
+    let lines = match tokio::fs::read_to_string(path).await {
+        Ok(lines) => lines,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(lines
+        .lines()
+        .filter_map(|line| {
+            let (prefix, etag) = line.split_once(' ')?;
+            Some((u32::from_str_radix(prefix, 16).ok()?, etag.to_string()))
+        })
+        .collect())
+}
+
+/// Writes a file next to `path` and renames it into place, so readers never see a
+/// partly written file.
+async fn write_replacing(path: &Path, contents: &[u8]) -> Result<(), HibpError> {
+    let partial = path.with_extension("part");
+    tokio::fs::write(&partial, contents).await?;
+    tokio::fs::rename(&partial, path).await?;
+    Ok(())
+}
+
+/// Daily window, in UTC, in which a [`CorpusDownloader`] starts requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct NiceHours {
+    pub(crate) start: NaiveTime,
+    pub(crate) end: NaiveTime,
+}
+
+impl NiceHours {
+    /// Returns how long to wait at time of day `now` until the window opens, zero inside it.
+    pub(crate) fn wait_at(&self, now: NaiveTime) -> Duration {
+        let inside = if self.start <= self.end {
+            self.start == self.end || (self.start <= now && now < self.end)
+        } else {
+            // The window spans midnight, e.g. 22:00 to 06:00
+            self.start <= now || now < self.end
+        };
+        if inside {
+            return Duration::ZERO;
+        }
+        let millis = (self.start - now).num_milliseconds().rem_euclid(86_400_000);
+        Duration::from_millis(millis as u64)
+    }
+}
+
+/// Returns the current time of day in UTC.
+fn utc_time_of_day() -> NaiveTime {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    NaiveTime::from_num_seconds_from_midnight_opt((now.as_secs() % 86_400) as u32, 0)
+        .unwrap_or_default()
+}
+
+/// Delays new requests so downloads average at most a number of bytes per second.
+#[derive(Debug)]
+pub(crate) struct BandwidthPacer {
+    bytes_per_second: u64,
+    started: Instant,
+    bytes: u64,
+}
+
+impl BandwidthPacer {
+    pub(crate) fn new(bytes_per_second: u64) -> Self {
+        BandwidthPacer {
+            bytes_per_second,
+            started: Instant::now(),
+            bytes: 0,
+        }
+    }
+
+    /// Counts received bytes and returns how long to pause to get back to the average.
+    pub(crate) fn record(&mut self, bytes: u64) -> Duration {
+        self.bytes += bytes;
+        let due = Duration::from_secs_f64(self.bytes as f64 / self.bytes_per_second as f64);
+        due.saturating_sub(self.started.elapsed())
+    }
+}
+
+impl CorpusDownloader {
+    /// Creates a downloader fetching all SHA-1 ranges, 64 at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client ranges are requested with. No API key is needed.
+    pub fn new(client: HaveIBeenPwned) -> Self {
+        CorpusDownloader {
+            client,
+            parallelism: 64,
+            mode: RangeMode::Sha1,
+            prefixes: 0..RANGE_COUNT,
+            max_bandwidth: None,
+            nice_hours: None,
+            progress: None,
+        }
+    }
+
+    /// Sets the maximum number of ranges requested at once. Values below 1 are treated as 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `parallelism` - Maximum number of requests in flight.
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Caps the average download bandwidth. Unlimited by default. Values below 1 are
+    /// treated as 1.
+    ///
+    /// Once the download gets ahead of the cap, no new ranges are requested until it
+    /// falls back to the average. Requests already in flight still finish, so lower the
+    /// parallelism as well for a smoother rate.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes_per_second` - Maximum average number of body bytes received per second.
+    pub fn with_max_bandwidth(mut self, bytes_per_second: u64) -> Self {
+        self.max_bandwidth = Some(bytes_per_second.max(1));
+        self
+    }
+
+    /// Only requests ranges during a daily window, in UTC, e.g. at night.
+    ///
+    /// Outside the window, the download pauses after the requests in flight finished and
+    /// resumes when the window opens again. The window may span midnight; a window
+    /// starting and ending at the same time lasts all day.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - Time of day the window opens.
+    /// * `end` - Time of day the window closes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::NaiveTime;
+    /// use hibp_rs::{CorpusDownloader, HaveIBeenPwned};
+    ///
+    /// let downloader = CorpusDownloader::new(HaveIBeenPwned::new_free_tier())
+    ///     .with_max_bandwidth(10 * 1024 * 1024)
+    ///     .with_nice_hours(
+    ///         NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+    ///         NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+    ///     );
+    /// ```
+    pub fn with_nice_hours(mut self, start: NaiveTime, end: NaiveTime) -> Self {
+        self.nice_hours = Some(NiceHours { start, end });
+        self
+    }
+
+    /// Calls `hook` with the [`Progress`] of the download after every written range, with
+    /// the number of ranges as total and the size of their bodies as bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Called with the progress so far; should return quickly.
+    pub fn with_progress<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Progress) + Send + Sync + 'static,
+    {
+        self.progress = Some(ProgressHook::new(hook));
+        self
+    }
+
+    /// Downloads NTLM hashes instead of SHA-1 hashes, e.g. for Active Directory audits.
+    pub fn with_ntlm(mut self) -> Self {
+        self.mode = RangeMode::Ntlm;
+        self
+    }
+
+    /// Limits the download to some ranges, e.g. to split it across machines or to resume it.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefixes` - Hash prefixes to download, as numbers, e.g. `0x00000..0x80000`. The
+    ///   end is capped at [`RANGE_COUNT`].
+    pub fn with_prefix_range(mut self, prefixes: Range<u32>) -> Self {
+        self.prefixes = prefixes.start.min(RANGE_COUNT)..prefixes.end.min(RANGE_COUNT);
+        self
+    }
+
+    /// Downloads the ranges into a single file with one `HASH:COUNT` line per hash.
+    ///
+    /// Lines hold the full uppercase hash, ordered by hash prefix, and end with `\n`. An
+    /// existing file is overwritten.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write.
+    pub async fn download_to_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<DownloadSummary, HibpError> {
+        let file = tokio::fs::File::create(path).await?;
+        let mut writer = tokio::io::BufWriter::new(file);
+        let mut lines = Vec::new();
+        let summary = self
+            .for_each_range(&mut BTreeMap::new(), async |prefix, body| {
+                lines.clear();
+                for line in body.split(|&b| b == b'\n') {
+                    let line = line.trim_ascii();
+                    if !line.is_empty() {
+                        lines.extend_from_slice(prefix.as_bytes());
+                        lines.extend_from_slice(line);
+                        lines.push(b'\n');
+                    }
+                }
+                writer.write_all(&lines).await?;
+                Ok(())
+            })
+            .await?;
+        writer.flush().await?;
+        Ok(summary)
+    }
+
+    /// Downloads the ranges into a binary corpus for fast lookups with
+    /// [`crate::OfflinePasswordStore`].
+    ///
+    /// The file starts with an index of where each prefix's records begin, followed by
+    /// fixed-width records of the packed hash suffix and its count (capped at
+    /// `u32::MAX`), sorted by suffix within each prefix. Ranges outside the prefix range
+    /// are empty. The file is written next to `path` and renamed into place when
+    /// complete, so stores that have the old corpus mapped keep working.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write.
+    pub async fn download_to_binary<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<DownloadSummary, HibpError> {
+        let path = path.as_ref();
+        let suffix_len = match self.mode {
+            RangeMode::Sha1 => hash::SHA1_HEX_LEN,
+            RangeMode::Ntlm => hash::NTLM_HEX_LEN,
+        } - hash::PREFIX_LEN;
+        let partial = path.with_extension("part");
+        let mut writer = tokio::io::BufWriter::new(tokio::fs::File::create(&partial).await?);
+        writer.write_all(&binary_corpus::header(suffix_len)).await?;
+        writer.write_all(&vec![0; binary_corpus::INDEX_LEN]).await?;
+
+        let mut records = vec![0u64; binary_corpus::PREFIXES];
+        let summary = self
+            .for_each_range(&mut BTreeMap::new(), async |prefix, body| {
+                let (count, bytes) = binary_corpus::encode_range(suffix_len, &body);
+                let prefix = usize::from_str_radix(&prefix, 16).map_err(HibpError::other)?;
+                records[prefix] = count;
+                writer.write_all(&bytes).await?;
+                Ok(())
+            })
+            .await?;
+        writer.flush().await?;
+
+        // Turn the record counts into the number of records before each prefix
+        let mut index = Vec::with_capacity(binary_corpus::INDEX_LEN);
+        let mut total = 0u64;
+        index.extend_from_slice(&total.to_le_bytes());
+        for count in records {
+            total += count;
+            index.extend_from_slice(&total.to_le_bytes());
+        }
+        let mut file = writer.into_inner();
+        file.seek(std::io::SeekFrom::Start(binary_corpus::HEADER_LEN as u64))
+            .await?;
+        file.write_all(&index).await?;
+        file.sync_all().await?;
+        drop(file);
+        tokio::fs::rename(&partial, path).await?;
+        Ok(summary)
+    }
+
+    /// Downloads every range into its own `PREFIX.txt` file inside a directory.
+    ///
+    /// Files hold the range body as sent by the API, i.e. `SUFFIX:COUNT` lines. The
+    /// directory is created if needed, and existing range files are overwritten. The
+    /// `ETag` of every range is kept in an `etags.txt` file next to them, so
+    /// [`CorpusDownloader::update_dir`] can later fetch only the ranges that changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory to write range files to.
+    pub async fn download_to_dir<P: AsRef<Path>>(
+        &self,
+        dir: P,
+    ) -> Result<DownloadSummary, HibpError> {
+        self.sync_dir(dir.as_ref(), false).await
+    }
+
+    /// Brings a directory written by [`CorpusDownloader::download_to_dir`] up to date.
+    ///
+    /// Every range is revalidated with its kept `ETag`, so unchanged ranges cost a
+    /// `304 Not Modified` response without a body. Changed ranges are downloaded and
+    /// replaced atomically, so an [`crate::OfflinePasswordStore`] reading the directory
+    /// keeps working during the update. Ranges without a kept `ETag` are downloaded.
+    ///
+    /// The summary counts only the ranges that changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory of range files.
+    pub async fn update_dir<P: AsRef<Path>>(&self, dir: P) -> Result<DownloadSummary, HibpError> {
+        self.sync_dir(dir.as_ref(), true).await
+    }
+
+    /// Runs [`CorpusDownloader::update_dir`] every `interval`, keeping an offline store
+    /// fresh without full downloads.
+    ///
+    /// Every update pass is handed to `report`. Failed passes are retried at the next
+    /// interval, unless the error is permanent (see [`HibpError::is_permanent`]), which
+    /// stops the updates and is returned. Spawn it as a background task.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory of range files.
+    /// * `interval` - Time between the end of a pass and the start of the next.
+    /// * `report` - Receives the outcome of every pass.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hibp_rs::{CorpusDownloader, HaveIBeenPwned};
+    /// use std::time::Duration;
+    ///
+    /// # async fn example() {
+    /// let downloader = CorpusDownloader::new(HaveIBeenPwned::new_free_tier());
+    /// let daily = Duration::from_secs(24 * 60 * 60);
+    /// tokio::spawn(async move {
+    ///     let error = downloader
+    ///         .keep_dir_updated("pwnedpasswords", daily, |pass| match pass {
+    ///             Ok(summary) => println!("Updated {} ranges", summary.ranges),
+    ///             Err(e) => eprintln!("Corpus update failed: {}", e),
+    ///         })
+    ///         .await;
+    ///     eprintln!("Corpus updates stopped: {}", error);
+    /// });
+    /// # }
+    /// ```
+    pub async fn keep_dir_updated<P, F>(
+        &self,
+        dir: P,
+        interval: Duration,
+        mut report: F,
+    ) -> HibpError
+    where
+        P: AsRef<Path>,
+        F: FnMut(&Result<DownloadSummary, HibpError>),
+    {
+        loop {
+            let pass = self.update_dir(dir.as_ref()).await;
+            report(&pass);
+            if let Err(e) = pass
+                && e.is_permanent()
+            {
+                return e;
+            }
+            runtime::sleep(interval).await;
+        }
+    }
+
+    /// Downloads ranges into a directory, revalidating them with their kept `ETag`s if
+    /// `revalidate` is set.
+    async fn sync_dir(&self, dir: &Path, revalidate: bool) -> Result<DownloadSummary, HibpError> {
+        tokio::fs::create_dir_all(dir).await?;
+        let etags_path = dir.join(ETAGS_FILE);
+        let mut etags = read_etags(&etags_path).await?;
+        if !revalidate {
+            etags.retain(|prefix, _| !self.prefixes.contains(prefix));
+        }
+        // Owned by the callback, so the update future stays `Send` on a spawned task
+        let range_dir = dir.to_path_buf();
+        let summary = self
+            .for_each_range(&mut etags, async move |prefix, body| {
+                write_replacing(&range_dir.join(format!("{}.txt", prefix)), &body).await
+            })
+            .await;
+        // Keep the ETags of the ranges written before a failure
+        let mut lines = String::new();
+        for (prefix, etag) in &etags {
+            lines.push_str(&format!("{:05X} {}\n", prefix, etag));
+        }
+        write_replacing(&etags_path, lines.as_bytes()).await?;
+        summary
+    }
+
+    /// Uploads every range as its own `PREFIX.txt` object to a shared corpus.
+    ///
+    /// Objects hold the range body as sent by the API, like [`Self::download_to_dir`],
+    /// and existing objects are overwritten. Up to the parallelism of uploads are in
+    /// flight at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `remote` - The corpus to upload to.
+    #[cfg(feature = "object-store")]
+    pub async fn download_to_remote(
+        &self,
+        remote: &crate::RemotePasswordStore,
+    ) -> Result<DownloadSummary, HibpError> {
+        let mut uploads = JoinSet::new();
+        let summary = self
+            .for_each_range(&mut BTreeMap::new(), async |prefix, body| {
+                if uploads.len() >= self.parallelism {
+                    join_upload(uploads.join_next().await)?;
+                }
+                let store = remote.store().clone();
+                let path = remote.range_path(&prefix);
+                uploads.spawn(async move { store.put(&path, body.into()).await });
+                Ok(())
+            })
+            .await?;
+        while let Some(upload) = uploads.join_next().await {
+            join_upload(Some(upload))?;
+        }
+        Ok(summary)
+    }
+
+    /// Fetches all ranges concurrently and hands them to `write` in prefix order.
+    ///
+    /// Ranges with an entry in `etags` are revalidated and skipped if unchanged, and
+    /// `etags` is updated as ranges are written. At most `parallelism` ranges are in
+    /// flight or waiting to be written at once, so a slow range can't make finished ones
+    /// pile up in memory. New requests wait for the bandwidth cap and nice hours. The
+    /// first failure aborts all outstanding requests.
+    async fn for_each_range<F>(
+        &self,
+        etags: &mut BTreeMap<u32, String>,
+        mut write: F,
+    ) -> Result<DownloadSummary, HibpError>
+    where
+        F: AsyncFnMut(String, Vec<u8>) -> Result<(), HibpError>,
+    {
+        let mut summary = DownloadSummary::default();
+        let mut next_fetch = self.prefixes.start;
+        let mut next_write = self.prefixes.start;
+        let mut finished: BTreeMap<u32, FetchedRange> = BTreeMap::new();
+        let mut tasks = JoinSet::new();
+        let mut task_prefixes = HashMap::new();
+        let mut pacer = self.max_bandwidth.map(BandwidthPacer::new);
+        let mut tracker =
+            ProgressTracker::new(self.progress.clone(), Some(self.prefixes.len() as u64));
+
+        while next_write < self.prefixes.end {
+            while next_fetch < self.prefixes.end
+                && ((next_fetch - next_write) as usize) < self.parallelism
+            {
+                if let Some(nice_hours) = &self.nice_hours {
+                    let wait = nice_hours.wait_at(utc_time_of_day());
+                    // Let requests in flight finish before pausing
+                    if !wait.is_zero() && !tasks.is_empty() {
+                        break;
+                    }
+                    runtime::sleep(wait).await;
+                }
+                let client = self.client.clone();
+                let mode = self.mode;
+                let prefix = next_fetch;
+                let etag = etags.get(&prefix).cloned();
+                let handle = tasks.spawn(async move {
+                    let mut body = Vec::new();
+                    let changed = client
+                        .revalidate_range(
+                            &format!("{:05X}", prefix),
+                            mode,
+                            etag.as_deref(),
+                            &mut body,
+                        )
+                        .await?;
+                    Ok::<_, HibpError>(changed.map(|etag| (body, etag)))
+                });
+                task_prefixes.insert(handle.id(), prefix);
+                next_fetch += 1;
+            }
+
+            let Some(joined) = tasks.join_next_with_id().await else {
+                break;
+            };
+            let (prefix, range) = match joined {
+                Ok((id, Ok(range))) => (task_prefixes.remove(&id).unwrap_or_default(), range),
+                Ok((_, Err(e))) => {
+                    tasks.abort_all();
+                    return Err(e);
+                }
+                Err(e) => {
+                    tasks.abort_all();
+                    return Err(HibpError::other(e));
+                }
+            };
+            if let Some(pacer) = &mut pacer {
+                let bytes = range.as_ref().map_or(0, |(body, _)| body.len());
+                runtime::sleep(pacer.record(bytes as u64)).await;
+            }
+            finished.insert(prefix, range);
+
+            while let Some(range) = finished.remove(&next_write) {
+                let Some((body, etag)) = range else {
+                    tracker.complete(0);
+                    next_write += 1;
+                    continue;
+                };
+                let (bytes, hashes) = (
+                    body.len() as u64,
+                    body.split(|&b| b == b'\n')
+                        .filter(|line| !line.trim_ascii().is_empty())
+                        .count() as u64,
+                );
+                write(format!("{:05X}", next_write), body).await?;
+                match etag {
+                    Some(etag) => etags.insert(next_write, etag),
+                    None => etags.remove(&next_write),
+                };
+                summary.ranges += 1;
+                summary.bytes += bytes;
+                summary.hashes += hashes;
+                tracker.complete(bytes);
+                next_write += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+}
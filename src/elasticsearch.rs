@@ -0,0 +1,289 @@
+use crate::{ECS_VERSION, Event, HibpError, Notification, Notifier};
+use reqwest::Client;
+use reqwest::header::{AUTHORIZATION, HeaderValue};
+use serde_json::json;
+use tokio::sync::Mutex;
+
+/// Writes events to an Elasticsearch data stream through the bulk API.
+///
+/// Events are buffered and written once `batch_size` events are queued, or on
+/// [`ElasticsearchSink::flush`]. Documents use the ECS format from [`Event::to_ecs_json`],
+/// so Kibana's security and discover views work without custom field mappings.
+/// Call [`ElasticsearchSink::install_index_template`] once to create the data stream's
+/// index template before writing.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::ElasticsearchSink;
+///
+/// # async fn example(findings: Vec<hibp_rs::NtHashAuditEntry>) -> Result<(), Box<dyn std::error::Error>> {
+/// let sink = ElasticsearchSink::new("https://elastic.example.com:9200")
+///     .with_api_key("your-api-key");
+/// sink.install_index_template().await?;
+///
+/// for finding in findings.iter().filter(|f| f.is_breached()) {
+///     sink.send(finding.to_event()).await?;
+/// }
+/// sink.flush().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ElasticsearchSink {
+    url: String,
+    index: String,
+    credentials: Option<Credentials>,
+    batch_size: usize,
+    client: Client,
+    buffer: Mutex<Vec<Event>>,
+}
+
+/// Credentials sent with every request.
+enum Credentials {
+    /// The base64-encoded `id:api_key`.
+    ApiKey(String),
+    /// A username and password for HTTP basic authentication.
+    Basic { username: String, password: String },
+}
+
+impl std::fmt::Debug for ElasticsearchSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ElasticsearchSink")
+            .field("url", &self.url)
+            .field("index", &self.index)
+            .field(
+                "credentials",
+                &self.credentials.as_ref().map(|_| "<redacted>"),
+            )
+            .field("batch_size", &self.batch_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ElasticsearchSink {
+    /// Creates a sink writing 100 events per request to the `logs-hibp-default` data stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Base URL of the cluster, e.g. `https://elastic.example.com:9200`.
+    pub fn new<S: Into<String>>(url: S) -> Self {
+        ElasticsearchSink {
+            url: url.into().trim_end_matches('/').to_string(),
+            index: "logs-hibp-default".to_string(),
+            credentials: None,
+            batch_size: 100,
+            client: http_client(),
+            buffer: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Sets the data stream events are written to.
+    #[must_use]
+    pub fn with_index<S: Into<String>>(mut self, index: S) -> Self {
+        self.index = index.into();
+        self
+    }
+
+    /// Authenticates with an Elasticsearch API key (the base64-encoded `id:api_key`).
+    #[must_use]
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.credentials = Some(Credentials::ApiKey(api_key.to_string()));
+        self
+    }
+
+    /// Authenticates with a username and password.
+    #[must_use]
+    pub fn with_basic_auth(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some(Credentials::Basic {
+            username: username.to_string(),
+            password: password.to_string(),
+        });
+        self
+    }
+
+    /// Sets how many events are written per bulk request. Values below 1 are treated as 1.
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Returns the index template for the data stream, mapping the ECS fields of [`Event`].
+    pub fn index_template(&self) -> serde_json::Value {
+        json!({
+            "index_patterns": [self.index],
+            "data_stream": {},
+            "priority": 200,
+            "template": {
+                "mappings": {
+                    "_meta": { "ecs_version": ECS_VERSION },
+                    "properties": {
+                        "@timestamp": { "type": "date" },
+                        "message": { "type": "match_only_text" },
+                        "ecs": { "properties": { "version": { "type": "keyword" } } },
+                        "event": {
+                            "properties": {
+                                "kind": { "type": "keyword" },
+                                "category": { "type": "keyword" },
+                                "action": { "type": "keyword" },
+                                "severity": { "type": "long" },
+                                "provider": { "type": "keyword" }
+                            }
+                        },
+                        "user": {
+                            "properties": {
+                                "name": { "type": "keyword" },
+                                "email": { "type": "keyword" }
+                            }
+                        },
+                        "hibp": {
+                            "properties": {
+                                "severity": { "type": "keyword" },
+                                "breach": { "type": "keyword" },
+                                "pwned_count": { "type": "long" },
+                                "location": { "type": "keyword" }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Creates or updates the index template of the data stream.
+    pub async fn install_index_template(&self) -> Result<(), HibpError> {
+        let url = format!("{}/_index_template/{}", self.url, self.index);
+        let resp = self
+            .request(self.client.put(&url))
+            .json(&self.index_template())
+            .send()
+            .await?;
+
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(HibpError::other(format!(
+                "Elasticsearch request failed with status: {}",
+                resp.status()
+            )))
+        }
+    }
+
+    /// Queues an event, writing the batch once it is full.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to write.
+    pub async fn send(&self, event: Event) -> Result<(), HibpError> {
+        let full = {
+            let mut buffer = self.buffer.lock().await;
+            buffer.push(event);
+            buffer.len() >= self.batch_size
+        };
+        if full {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Writes all queued events with a single bulk request.
+    ///
+    /// Fails if the request fails or any document is rejected.
+    pub async fn flush(&self) -> Result<(), HibpError> {
+        let events = std::mem::take(&mut *self.buffer.lock().await);
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let url = format!("{}/_bulk", self.url);
+        let resp = self
+            .request(self.client.post(&url))
+            .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+            .body(self.bulk_body(&events)?)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(HibpError::other(format!(
+                "Elasticsearch request failed with status: {}",
+                resp.status()
+            )));
+        }
+        let result: serde_json::Value = resp.json().await?;
+        if result["errors"].as_bool().unwrap_or(false) {
+            let rejected = result["items"]
+                .as_array()
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter(|item| item["create"].get("error").is_some())
+                        .count()
+                })
+                .unwrap_or(0);
+            return Err(HibpError::other(format!(
+                "Elasticsearch rejected {} of {} events",
+                rejected,
+                events.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Builds the NDJSON body of a bulk request.
+    pub(crate) fn bulk_body(&self, events: &[Event]) -> Result<String, HibpError> {
+        let action = serde_json::to_string(&json!({ "create": { "_index": self.index } }))?;
+        let mut body = String::new();
+        for event in events {
+            body.push_str(&action);
+            body.push('\n');
+            body.push_str(&serde_json::to_string(&event.to_ecs_json())?);
+            body.push('\n');
+        }
+        Ok(body)
+    }
+
+    fn request(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.credentials {
+            Some(Credentials::ApiKey(api_key)) => {
+                match HeaderValue::try_from(format!("ApiKey {}", api_key)) {
+                    Ok(mut value) => {
+                        // Keeps the key out of the debug output of reqwest and hyper
+                        value.set_sensitive(true);
+                        request.header(AUTHORIZATION, value)
+                    }
+                    // Fails the request when it is sent
+                    Err(_) => request.header(AUTHORIZATION, api_key.as_str()),
+                }
+            }
+            Some(Credentials::Basic { username, password }) => {
+                request.basic_auth(username, Some(password))
+            }
+            None => request,
+        }
+    }
+}
+
+/// Builds an HTTP client with timeouts, so an unresponsive cluster can't stall a flush.
+fn http_client() -> Client {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let timeouts = crate::timeouts::Timeouts::default();
+        // Fails in the same cases `Client::new` panics in
+        Client::builder()
+            .connect_timeout(timeouts.connect)
+            .timeout(timeouts.request)
+            .build()
+            .expect("Client::new()")
+    }
+    #[cfg(target_arch = "wasm32")]
+    Client::new()
+}
+
+impl Notifier for ElasticsearchSink {
+    async fn notify(&self, notification: &Notification) -> Result<(), HibpError> {
+        for event in notification.events() {
+            self.send(event).await?;
+        }
+        self.flush().await
+    }
+}
@@ -0,0 +1,174 @@
+#[cfg(feature = "full")]
+use crate::BudgetExceeded;
+use crate::{RequestBlocked, ResponseTooLarge, SubscriptionRequired};
+use std::time::Duration;
+
+/// Error returned by every fallible operation of this crate.
+///
+/// Match on the variant to tell expected API outcomes like an unknown breach or an
+/// exhausted rate limit apart from network and decoding failures:
+///
+/// ```no_run
+/// # use hibp_rs::{HaveIBeenPwned, HibpError};
+/// # async fn example() {
+/// let hibp = HaveIBeenPwned::new("your_api_key");
+/// match hibp.get_breach_by_name("Adobe").await {
+///     Ok(breach) => println!("{} accounts", breach.pwn_count),
+///     Err(HibpError::NotFound) => println!("No such breach"),
+///     Err(HibpError::RateLimited { retry_after }) => println!("Retry in {:?}", retry_after),
+///     Err(HibpError::Unauthorized { .. }) => println!("Check the API key"),
+///     Err(e) => println!("Request failed: {}", e),
+/// }
+/// # }
+/// ```
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum HibpError {
+    /// The API key is missing or invalid (status 401).
+    #[error("The API key is missing or invalid{}", detail(.message))]
+    Unauthorized {
+        /// Explanation from the response body, e.g. `Access denied due to invalid
+        /// hibp-api-key.`
+        message: Option<String>,
+    },
+    /// The API key may not use the endpoint (status 403), e.g. because the subscription
+    /// doesn't include stealer logs. Blocked requests are [`HibpError::Blocked`] instead.
+    #[error("Access to the endpoint is forbidden{}", detail(.message))]
+    Forbidden {
+        /// Explanation from the response body.
+        message: Option<String>,
+    },
+    /// The rate limit of the API key was exceeded (status 429).
+    #[error("Rate limit exceeded")]
+    RateLimited {
+        /// How long to wait before retrying, from the `Retry-After` header.
+        retry_after: Option<Duration>,
+    },
+    /// The requested resource does not exist (status 404).
+    #[error("Resource not found")]
+    NotFound,
+    /// An argument or input file was rejected before anything was sent.
+    #[error("{0}")]
+    InvalidInput(String),
+    /// The API answered with another unsuccessful status.
+    #[error("API request failed with status: {0}")]
+    Status(reqwest::StatusCode),
+    /// The request could not be sent or the response could not be read.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    /// A response or stored document could not be deserialized.
+    #[error(transparent)]
+    Deserialize(#[from] serde_json::Error),
+    /// Reading or writing a local file or stream failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The response body exceeded the configured maximum size.
+    #[error(transparent)]
+    ResponseTooLarge(#[from] ResponseTooLarge),
+    /// A free-tier client called an endpoint that needs an API key.
+    #[error(transparent)]
+    SubscriptionRequired(#[from] SubscriptionRequired),
+    /// HIBP or Cloudflare blocked the request regardless of the API key.
+    #[error(transparent)]
+    Blocked(#[from] RequestBlocked),
+    /// The request budget set with `HaveIBeenPwned::with_request_budget` is used up.
+    #[cfg(feature = "full")]
+    #[error(transparent)]
+    BudgetExceeded(#[from] BudgetExceeded),
+    /// Any other failure, e.g. from a notifier, result sink or event output.
+    #[error(transparent)]
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl HibpError {
+    /// Wraps any error as [`HibpError::Other`].
+    ///
+    /// Use this in `ResultSink` and `Notifier` implementations to return their own errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error to wrap.
+    pub fn other<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> Self {
+        HibpError::Other(error.into())
+    }
+
+    /// Whether retrying can't help until the configuration changes: the API key is
+    /// invalid, may not use the endpoint, or is missing.
+    ///
+    /// Long-running watchers stop on these errors instead of polling forever.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HibpError;
+    /// assert!(HibpError::Unauthorized { message: None }.is_permanent());
+    /// assert!(!HibpError::RateLimited { retry_after: None }.is_permanent());
+    /// ```
+    pub fn is_permanent(&self) -> bool {
+        matches!(
+            self,
+            HibpError::Unauthorized { .. }
+                | HibpError::Forbidden { .. }
+                | HibpError::SubscriptionRequired(_)
+        )
+    }
+
+    /// Maps an unsuccessful response status to an error.
+    ///
+    /// `message` is the explanation from the response body, see [`api_message`].
+    pub(crate) fn from_status(
+        status: reqwest::StatusCode,
+        retry_after: Option<Duration>,
+        message: Option<String>,
+    ) -> Self {
+        match status.as_u16() {
+            401 => HibpError::Unauthorized { message },
+            403 => HibpError::Forbidden { message },
+            404 => HibpError::NotFound,
+            429 => HibpError::RateLimited { retry_after },
+            _ => HibpError::Status(status),
+        }
+    }
+}
+
+/// Formats the explanation of an error response as a suffix of the error message.
+fn detail(message: &Option<String>) -> String {
+    message
+        .as_ref()
+        .map_or_else(String::new, |message| format!(": {}", message))
+}
+
+/// Extracts the explanation from the JSON body of an error response, e.g.
+/// `{"statusCode": 401, "message": "Access denied due to invalid hibp-api-key."}`.
+pub(crate) fn api_message(body: &[u8]) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct ErrorBody {
+        message: Option<String>,
+    }
+
+    serde_json::from_slice::<ErrorBody>(body)
+        .ok()?
+        .message
+        .map(|message| message.trim().to_string())
+        .filter(|message| !message.is_empty())
+}
+
+// Errors have to cross `tokio::spawn` and thread boundaries, keep them `Send + Sync`
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync + 'static>() {}
+    assert_send_sync::<HibpError>();
+    assert_send_sync::<RequestBlocked>();
+    assert_send_sync::<ResponseTooLarge>();
+    assert_send_sync::<SubscriptionRequired>();
+    #[cfg(feature = "full")]
+    assert_send_sync::<BudgetExceeded>();
+};
+
+/// Parses the `Retry-After` header of a response, given in seconds by HIBP.
+pub(crate) fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .map(Duration::from_secs)
+}
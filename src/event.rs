@@ -0,0 +1,257 @@
+use crate::breach::{format_timestamp, now_utc};
+use crate::{
+    AdviceSeverity, AdviceThresholds, AlertSeverity, HibpError, Notification, Notifier,
+    PasswordAdvice,
+};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// A monitor event or audit finding, in a common shape for log and SIEM outputs.
+///
+/// Created with the `to_event` methods of [`crate::Alert`], [`crate::ScanFinding`],
+/// [`crate::ExportAuditEntry`] and [`crate::NtHashAuditEntry`], or with
+/// [`Notification::events`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Event {
+    /// When the event was created, in `YYYY-MM-DDTHH:MM:SSZ` format.
+    pub timestamp: String,
+    /// What happened: `breach-alert`, `secret-exposed`, `export-password-exposed` or
+    /// `nt-hash-exposed`.
+    pub action: String,
+    /// How urgent the event is.
+    pub severity: AlertSeverity,
+    /// Human-readable description.
+    pub message: String,
+    /// Affected account, if any.
+    pub account: Option<String>,
+    /// Name of the breach, for breach alerts.
+    pub breach: Option<String>,
+    /// Number of times the password appears in the Pwned Passwords corpus, for password findings.
+    pub count: Option<u64>,
+    /// File, site or other location of the finding, if any.
+    pub location: Option<String>,
+}
+
+impl Event {
+    /// Creates an event timestamped now.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - What happened.
+    /// * `severity` - How urgent the event is.
+    /// * `message` - Human-readable description.
+    pub fn new<A: Into<String>, M: Into<String>>(
+        action: A,
+        severity: AlertSeverity,
+        message: M,
+    ) -> Self {
+        Event {
+            timestamp: format_timestamp(&now_utc()),
+            action: action.into(),
+            severity,
+            message: message.into(),
+            account: None,
+            breach: None,
+            count: None,
+            location: None,
+        }
+    }
+
+    /// Sets the affected account.
+    #[must_use]
+    pub fn with_account<S: Into<String>>(mut self, account: S) -> Self {
+        self.account = Some(account.into());
+        self
+    }
+
+    /// Sets the breach name.
+    #[must_use]
+    pub fn with_breach<S: Into<String>>(mut self, breach: S) -> Self {
+        self.breach = Some(breach.into());
+        self
+    }
+
+    /// Sets the Pwned Passwords count.
+    #[must_use]
+    pub fn with_count(mut self, count: u64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Sets the location of the finding.
+    #[must_use]
+    pub fn with_location<S: Into<String>>(mut self, location: S) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+}
+
+/// Grades a password finding by how often the password appears in the corpus.
+pub(crate) fn password_severity(count: u64) -> AlertSeverity {
+    match PasswordAdvice::from_count(count, &AdviceThresholds::default()).severity {
+        AdviceSeverity::None => AlertSeverity::Info,
+        AdviceSeverity::Low => AlertSeverity::Low,
+        AdviceSeverity::Medium => AlertSeverity::Medium,
+        AdviceSeverity::High => AlertSeverity::High,
+    }
+}
+
+impl Notification {
+    /// Converts the notification into one event per alert.
+    pub fn events(&self) -> Vec<Event> {
+        self.alerts().iter().map(|a| a.to_event()).collect()
+    }
+}
+
+/// ECS version the JSON event format follows.
+pub const ECS_VERSION: &str = "8.11.0";
+
+impl Event {
+    /// Converts the event into an Elastic Common Schema (ECS) document.
+    ///
+    /// This is the stable format written by [`JsonEventWriter`]:
+    ///
+    /// | Field | Content |
+    /// |-------|---------|
+    /// | `@timestamp` | [`Event::timestamp`] |
+    /// | `ecs.version` | [`ECS_VERSION`] |
+    /// | `event.kind` | always `alert` |
+    /// | `event.category` | always `["iam"]` |
+    /// | `event.action` | [`Event::action`] |
+    /// | `event.severity` | 1 (info) to 5 (critical) |
+    /// | `event.provider` | always `hibp_rs` |
+    /// | `message` | [`Event::message`] |
+    /// | `user.name` | [`Event::account`], if set |
+    /// | `user.email` | [`Event::account`], if it is an email address |
+    /// | `hibp.severity` | `info`, `low`, `medium`, `high` or `critical` |
+    /// | `hibp.breach` | [`Event::breach`], if set |
+    /// | `hibp.pwned_count` | [`Event::count`], if set |
+    /// | `hibp.location` | [`Event::location`], if set |
+    ///
+    /// Fields are only ever added to this format, never renamed or removed.
+    pub fn to_ecs_json(&self) -> serde_json::Value {
+        let severity = match self.severity {
+            AlertSeverity::Info => 1,
+            AlertSeverity::Low => 2,
+            AlertSeverity::Medium => 3,
+            AlertSeverity::High => 4,
+            AlertSeverity::Critical => 5,
+        };
+
+        let mut document = serde_json::json!({
+            "@timestamp": self.timestamp,
+            "ecs": { "version": ECS_VERSION },
+            "event": {
+                "kind": "alert",
+                "category": ["iam"],
+                "action": self.action,
+                "severity": severity,
+                "provider": "hibp_rs",
+            },
+            "message": self.message,
+            "hibp": { "severity": self.severity },
+        });
+
+        if let Some(account) = &self.account {
+            let mut user = serde_json::json!({ "name": account });
+            if account.contains('@') {
+                user["email"] = account.clone().into();
+            }
+            document["user"] = user;
+        }
+        if let Some(breach) = &self.breach {
+            document["hibp"]["breach"] = breach.clone().into();
+        }
+        if let Some(count) = self.count {
+            document["hibp"]["pwned_count"] = count.into();
+        }
+        if let Some(location) = &self.location {
+            document["hibp"]["location"] = location.clone().into();
+        }
+        document
+    }
+}
+
+/// Writes events as newline-delimited ECS JSON for SIEM ingestion.
+///
+/// Each event becomes one line in the format documented on [`Event::to_ecs_json`], ready
+/// for Filebeat, Splunk's JSON sourcetypes or any other line-based shipper.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::JsonEventWriter;
+///
+/// # fn example(findings: Vec<hibp_rs::NtHashAuditEntry>) -> Result<(), Box<dyn std::error::Error>> {
+/// let writer = JsonEventWriter::file("/var/log/hibp/findings.ndjson")?;
+/// for finding in findings.iter().filter(|f| f.is_breached()) {
+///     writer.emit(&finding.to_event())?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct JsonEventWriter<W> {
+    writer: Mutex<W>,
+}
+
+impl JsonEventWriter<std::io::Stdout> {
+    /// Creates a writer printing events to stdout.
+    pub fn stdout() -> Self {
+        JsonEventWriter::new(std::io::stdout())
+    }
+}
+
+impl JsonEventWriter<std::fs::File> {
+    /// Creates a writer appending events to a file, creating it if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file.
+    pub fn file<P: AsRef<Path>>(path: P) -> Result<Self, HibpError> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        Ok(JsonEventWriter::new(file))
+    }
+}
+
+impl<W: Write> JsonEventWriter<W> {
+    /// Creates a writer emitting events to any [`Write`] implementation.
+    pub fn new(writer: W) -> Self {
+        JsonEventWriter {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Writes a single event as one JSON line.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to write.
+    pub fn emit(&self, event: &Event) -> Result<(), HibpError> {
+        let mut line = serde_json::to_vec(&event.to_ecs_json())?;
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writer.write_all(&line)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<W: Write> Notifier for JsonEventWriter<W> {
+    async fn notify(&self, notification: &Notification) -> Result<(), HibpError> {
+        for event in notification.events() {
+            self.emit(&event)?;
+        }
+        Ok(())
+    }
+}
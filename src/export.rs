@@ -0,0 +1,232 @@
+use crate::{
+    Breach, DomainBreaches, HibpError, Paste, StealerLogAlias, StealerLogDomain, StealerLogEmail,
+};
+use std::io::Write;
+
+/// Format written by [`export`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// CSV with a header row. Lists are joined with `; `.
+    Csv,
+    /// Newline-delimited JSON, one object per line.
+    Ndjson,
+    /// A pretty-printed JSON array.
+    PrettyJson,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = HibpError;
+
+    /// Parses `csv`, `ndjson` (or `jsonl`) and `json`, ignoring case.
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_ascii_lowercase().as_str() {
+            "csv" => Ok(OutputFormat::Csv),
+            "ndjson" | "jsonl" => Ok(OutputFormat::Ndjson),
+            "json" => Ok(OutputFormat::PrettyJson),
+            _ => Err(HibpError::InvalidInput(format!(
+                "Unknown output format: {}",
+                format
+            ))),
+        }
+    }
+}
+
+/// A result type [`export`] can write.
+///
+/// JSON output uses the field names of the HIBP API, so exported files read back into the
+/// same types. CSV output uses the same names for its columns but leaves out HTML and
+/// image fields, like [`Breach::description`] and [`Breach::logo_path`].
+pub trait Exportable: serde::Serialize {
+    /// Names of the CSV columns.
+    fn csv_headers() -> &'static [&'static str];
+
+    /// Values of the CSV columns, in the order of [`Exportable::csv_headers`].
+    fn csv_record(&self) -> Vec<String>;
+}
+
+/// Writes results as CSV, NDJSON or pretty JSON.
+///
+/// Wrap unbuffered writers like files in a [`std::io::BufWriter`].
+///
+/// # Arguments
+///
+/// * `items` - The results to write.
+/// * `format` - The output format.
+/// * `writer` - Where to write them.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{HaveIBeenPwned, OutputFormat, export};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let hibp = HaveIBeenPwned::new("your_api_key");
+/// let breaches = hibp.get_all_breaches().await?;
+/// let file = std::io::BufWriter::new(std::fs::File::create("breaches.csv")?);
+/// export(&breaches, OutputFormat::Csv, file)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn export<T: Exportable, W: Write>(
+    items: &[T],
+    format: OutputFormat,
+    mut writer: W,
+) -> Result<(), HibpError> {
+    match format {
+        OutputFormat::Csv => {
+            let mut csv = csv::Writer::from_writer(writer);
+            csv.write_record(T::csv_headers())
+                .map_err(HibpError::other)?;
+            for item in items {
+                csv.write_record(item.csv_record())
+                    .map_err(HibpError::other)?;
+            }
+            csv.flush()?;
+        }
+        OutputFormat::Ndjson => {
+            for item in items {
+                serde_json::to_writer(&mut writer, item)?;
+                writer.write_all(b"\n")?;
+            }
+            writer.flush()?;
+        }
+        OutputFormat::PrettyJson => {
+            serde_json::to_writer_pretty(&mut writer, items)?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// A breached alias of a domain and the breaches it appears in, one row of a
+/// [`DomainBreaches`] report.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct DomainBreachEntry {
+    /// The part of the address before the `@`.
+    #[serde(rename = "Alias")]
+    pub alias: String,
+    /// Names of the breaches the address appears in.
+    #[serde(rename = "Breaches")]
+    pub breaches: Vec<String>,
+}
+
+impl DomainBreachEntry {
+    /// Splits a domain report into one entry per alias, in alias order.
+    ///
+    /// # Arguments
+    ///
+    /// * `report` - The report from [`crate::HaveIBeenPwned::get_breached_accounts_for_domain`].
+    pub fn from_report(report: &DomainBreaches) -> Vec<DomainBreachEntry> {
+        report
+            .iter()
+            .map(|(alias, breaches)| DomainBreachEntry {
+                alias: alias.clone(),
+                breaches: breaches.clone(),
+            })
+            .collect()
+    }
+}
+
+impl Exportable for Breach {
+    fn csv_headers() -> &'static [&'static str] {
+        &[
+            "Name",
+            "Title",
+            "Domain",
+            "BreachDate",
+            "AddedDate",
+            "ModifiedDate",
+            "PwnCount",
+            "DataClasses",
+            "IsVerified",
+            "IsFabricated",
+            "IsSensitive",
+            "IsRetired",
+            "IsSpamList",
+            "IsMalware",
+            "IsStealerLog",
+            "IsSubscriptionFree",
+        ]
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.title.clone(),
+            self.domain.clone(),
+            self.breach_date.to_string(),
+            crate::breach::format_timestamp(&self.added_date),
+            crate::breach::format_timestamp(&self.modified_date),
+            self.pwn_count.to_string(),
+            self.data_classes.join("; "),
+            self.is_verified.to_string(),
+            self.is_fabricated.to_string(),
+            self.is_sensitive.to_string(),
+            self.is_retired.to_string(),
+            self.is_spam_list.to_string(),
+            self.is_malware.to_string(),
+            self.is_stealer_log.to_string(),
+            self.is_subscription_free.to_string(),
+        ]
+    }
+}
+
+impl Exportable for Paste {
+    fn csv_headers() -> &'static [&'static str] {
+        &["Source", "Id", "Title", "Date", "EmailCount"]
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        vec![
+            self.source.clone(),
+            self.id.clone(),
+            self.title.clone().unwrap_or_default(),
+            self.date
+                .as_ref()
+                .map(crate::breach::format_timestamp)
+                .unwrap_or_default(),
+            self.email_count.to_string(),
+        ]
+    }
+}
+
+impl Exportable for StealerLogEmail {
+    fn csv_headers() -> &'static [&'static str] {
+        &["email"]
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        vec![self.email.clone()]
+    }
+}
+
+impl Exportable for StealerLogAlias {
+    fn csv_headers() -> &'static [&'static str] {
+        &["alias"]
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        vec![self.alias.clone()]
+    }
+}
+
+impl Exportable for StealerLogDomain {
+    fn csv_headers() -> &'static [&'static str] {
+        &["domain"]
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        vec![self.domain.clone()]
+    }
+}
+
+impl Exportable for DomainBreachEntry {
+    fn csv_headers() -> &'static [&'static str] {
+        &["Alias", "Breaches"]
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        vec![self.alias.clone(), self.breaches.join("; ")]
+    }
+}
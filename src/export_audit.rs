@@ -0,0 +1,374 @@
+use crate::event::password_severity;
+use crate::hashing::{Secret, secret, to_hex_upper};
+use crate::password::{RangeMode, group_by_prefix, range_count};
+use crate::{
+    Event, HaveIBeenPwned, HibpError, MessageCatalog, ResultSink, SarifLevel, SarifResult,
+};
+use secrecy::{ExposeSecret, SecretString};
+use std::collections::HashMap;
+use std::io::Read;
+
+/// Candidate header names for a single column, in order of preference.
+type HeaderNames = &'static [&'static str];
+
+/// Layout of a password-manager CSV export.
+///
+/// Each format maps its own column names onto the same site/username/password triple,
+/// so all exports feed the same audit pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Any CSV with recognisable `site`/`url`, `username`/`login` and `password` headers.
+    Generic,
+    /// Google Chrome export (`name,url,username,password,note`).
+    Chrome,
+    /// Microsoft Edge export, which uses the Chrome layout.
+    Edge,
+    /// Mozilla Firefox export (`url,username,password,httpRealm,...`).
+    Firefox,
+    /// 1Password CSV export (`Title,Url,Username,Password,...`).
+    OnePassword,
+}
+
+impl ExportFormat {
+    /// Header names for the site, username and password columns, in order of preference.
+    fn columns(&self) -> (HeaderNames, HeaderNames, HeaderNames) {
+        match self {
+            ExportFormat::Generic => (
+                &["site", "url", "website", "name", "title"],
+                &["username", "login", "user", "email"],
+                &["password", "pass"],
+            ),
+            ExportFormat::Chrome | ExportFormat::Edge => {
+                (&["url", "name"], &["username"], &["password"])
+            }
+            ExportFormat::Firefox => (&["url"], &["username"], &["password"]),
+            ExportFormat::OnePassword => {
+                (&["title", "url", "website"], &["username"], &["password"])
+            }
+        }
+    }
+
+    /// Guesses the export format from a CSV header row, falling back to [`ExportFormat::Generic`].
+    ///
+    /// Edge exports are indistinguishable from Chrome ones and are reported as [`ExportFormat::Chrome`].
+    pub fn detect(headers: &[&str]) -> ExportFormat {
+        let headers: Vec<String> = headers.iter().map(|h| h.trim().to_lowercase()).collect();
+        let has = |name: &str| headers.iter().any(|h| h == name);
+
+        if has("httprealm") && has("formactionorigin") {
+            ExportFormat::Firefox
+        } else if has("otpauth") || (has("title") && has("url") && has("favorite")) {
+            ExportFormat::OnePassword
+        } else if headers.len() >= 4 && headers[..4] == ["name", "url", "username", "password"] {
+            ExportFormat::Chrome
+        } else {
+            ExportFormat::Generic
+        }
+    }
+}
+
+/// A single credential read from a password-manager export.
+#[derive(Clone)]
+pub struct ExportEntry {
+    /// Site or name of the entry.
+    pub site: String,
+    /// Username of the entry.
+    pub username: String,
+    /// Password of the entry. Only ever hashed, never transmitted, and wiped when dropped.
+    pub password: SecretString,
+}
+
+impl std::fmt::Debug for ExportEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExportEntry")
+            .field("site", &self.site)
+            .field("username", &self.username)
+            .field("password", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl ExportEntry {
+    /// Parses a generic password-manager CSV export with a header row.
+    ///
+    /// Columns are matched case-insensitively by header name: `site`/`url`/`name` for the site,
+    /// `username`/`login`/`email` for the username and `password` for the password.
+    /// Rows with an empty password are skipped. Use [`ExportEntry::parse_csv_as`] for
+    /// browser and 1Password exports.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source of the CSV data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::ExportEntry;
+    ///
+    /// let csv = "url,username,password\nhttps://example.com,alice,hunter22\n";
+    /// let entries = ExportEntry::parse_csv(csv.as_bytes()).unwrap();
+    /// assert_eq!(entries[0].username, "alice");
+    /// ```
+    pub fn parse_csv<R: Read>(reader: R) -> Result<Vec<ExportEntry>, HibpError> {
+        Self::parse_csv_as(reader, ExportFormat::Generic)
+    }
+
+    /// Parses a password-manager CSV export in the given format.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source of the CSV data.
+    /// * `format` - Layout of the export.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::{ExportEntry, ExportFormat};
+    ///
+    /// let csv = "name,url,username,password,note\nexample.com,https://example.com/,alice,hunter22,\n";
+    /// let entries = ExportEntry::parse_csv_as(csv.as_bytes(), ExportFormat::Chrome).unwrap();
+    /// assert_eq!(entries[0].site, "https://example.com/");
+    /// ```
+    pub fn parse_csv_as<R: Read>(
+        reader: R,
+        format: ExportFormat,
+    ) -> Result<Vec<ExportEntry>, HibpError> {
+        let (site_headers, username_headers, password_headers) = format.columns();
+        let mut csv_reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+        let headers = csv_reader.headers().map_err(HibpError::other)?.clone();
+
+        let find_column = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                headers
+                    .iter()
+                    .position(|header| header.trim().eq_ignore_ascii_case(name))
+            })
+        };
+
+        let password_column = find_column(password_headers)
+            .ok_or_else(|| HibpError::InvalidInput("CSV export has no password column".into()))?;
+        let site_column = find_column(site_headers);
+        let username_column = find_column(username_headers);
+
+        let mut entries = Vec::new();
+        for record in csv_reader.records() {
+            let record = record.map_err(HibpError::other)?;
+            let field = |column: Option<usize>| {
+                column
+                    .and_then(|c| record.get(c))
+                    .unwrap_or_default()
+                    .to_string()
+            };
+
+            let password = record.get(password_column).unwrap_or_default();
+            if password.is_empty() {
+                continue;
+            }
+            entries.push(ExportEntry {
+                site: field(site_column),
+                username: field(username_column),
+                password: SecretString::from(password),
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Exposure report for a single export entry.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExportAuditEntry {
+    /// Site or name of the entry.
+    pub site: String,
+    /// Username of the entry.
+    pub username: String,
+    /// Number of times the password appears in the Pwned Passwords corpus.
+    pub count: u64,
+    /// Number of other entries in the export using the same password.
+    pub reused_by: usize,
+}
+
+impl ExportAuditEntry {
+    /// Whether the password of the entry appears in the Pwned Passwords corpus.
+    pub fn is_exposed(&self) -> bool {
+        self.count > 0
+    }
+
+    /// Converts the entry into a SARIF result with an English message.
+    pub fn to_sarif_result(&self) -> SarifResult {
+        self.to_sarif_result_with(&MessageCatalog::english())
+    }
+
+    /// Converts the entry into a SARIF result, taking the message from the given catalog.
+    pub fn to_sarif_result_with(&self, catalog: &MessageCatalog) -> SarifResult {
+        SarifResult::new(
+            "hibp/pwned-password".to_string(),
+            SarifLevel::Error,
+            catalog.format(
+                "report.export.breached",
+                &[
+                    ("username", &self.username),
+                    ("site", &self.site),
+                    ("count", &self.count),
+                ],
+            ),
+        )
+        .with_location(self.site.clone(), None)
+    }
+
+    /// Converts the entry into an `export-password-exposed` event with an English message.
+    pub fn to_event(&self) -> Event {
+        Event::new(
+            "export-password-exposed",
+            password_severity(self.count),
+            MessageCatalog::english().format(
+                "report.export.breached",
+                &[
+                    ("username", &self.username),
+                    ("site", &self.site),
+                    ("count", &self.count),
+                ],
+            ),
+        )
+        .with_account(self.username.clone())
+        .with_count(self.count)
+        .with_location(self.site.clone())
+    }
+}
+
+/// Per-entry exposure report for a password-manager export.
+#[derive(Debug, Clone, Default)]
+pub struct ExportAuditReport {
+    /// One report per audited entry, in input order.
+    pub entries: Vec<ExportAuditEntry>,
+}
+
+impl ExportAuditReport {
+    /// Returns the entries whose password appears in the Pwned Passwords corpus.
+    pub fn exposed(&self) -> impl Iterator<Item = &ExportAuditEntry> {
+        self.entries.iter().filter(|e| e.is_exposed())
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Audits password-manager export entries against Pwned Passwords.
+    ///
+    /// Passwords are hashed locally and grouped by hash prefix, so each range is fetched once
+    /// and no plaintext ever leaves the machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - Entries parsed from an export, e.g. with [`ExportEntry::parse_csv`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{ExportEntry, HaveIBeenPwned};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let file = std::fs::File::open("passwords.csv")?;
+    /// let entries = ExportEntry::parse_csv(file)?;
+    /// let report = hibp.audit_export(&entries).await?;
+    /// for entry in report.exposed() {
+    ///     println!("{} ({}) is pwned {} times", entry.site, entry.username, entry.count);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn audit_export(
+        &self,
+        entries: &[ExportEntry],
+    ) -> Result<ExportAuditReport, HibpError> {
+        let passwords: Vec<&str> = entries.iter().map(|e| e.password.expose_secret()).collect();
+        let digests = secret(self.sha1_batch_async(&passwords).await);
+        let hashes: Secret<Vec<String>> =
+            secret(digests.iter().map(|digest| to_hex_upper(digest)).collect());
+        drop(digests);
+
+        let mut usage: HashMap<&str, usize> = HashMap::new();
+        for hash in hashes.iter() {
+            *usage.entry(hash.as_str()).or_default() += 1;
+        }
+
+        let counts = self.count_hashes(&hashes, RangeMode::Sha1).await?;
+
+        let entries = entries
+            .iter()
+            .zip(hashes.iter().zip(counts))
+            .map(|(entry, (hash, count))| ExportAuditEntry {
+                site: entry.site.clone(),
+                username: entry.username.clone(),
+                count,
+                reused_by: usage[hash.as_str()] - 1,
+            })
+            .collect();
+
+        Ok(ExportAuditReport { entries })
+    }
+
+    /// Audits password-manager export entries, writing each result to a sink as soon as its
+    /// range is fetched.
+    ///
+    /// Like [`HaveIBeenPwned::audit_export`], but results are not collected in memory.
+    /// Results arrive grouped by hash prefix rather than in input order. The sink is
+    /// flushed but not closed. Returns the number of exposed entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - Entries parsed from an export, e.g. with [`ExportEntry::parse_csv`].
+    /// * `sink` - Destination of the results.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{CsvSink, ExportEntry, HaveIBeenPwned, ResultSink};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let entries = ExportEntry::parse_csv(std::fs::File::open("passwords.csv")?)?;
+    /// let mut sink = CsvSink::new(std::fs::File::create("exposed.csv")?);
+    /// let exposed = hibp.audit_export_into(&entries, &mut sink).await?;
+    /// sink.close().await?;
+    /// eprintln!("{} entries are exposed", exposed);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn audit_export_into<S: ResultSink<ExportAuditEntry>>(
+        &self,
+        entries: &[ExportEntry],
+        sink: &mut S,
+    ) -> Result<usize, HibpError> {
+        let passwords: Vec<&str> = entries.iter().map(|e| e.password.expose_secret()).collect();
+        let digests = secret(self.sha1_batch_async(&passwords).await);
+        let hashes: Secret<Vec<String>> =
+            secret(digests.iter().map(|digest| to_hex_upper(digest)).collect());
+        drop(digests);
+
+        let mut usage: HashMap<&str, usize> = HashMap::new();
+        for hash in hashes.iter() {
+            *usage.entry(hash.as_str()).or_default() += 1;
+        }
+
+        let mut exposed = 0;
+        for (prefix, indices) in group_by_prefix(&hashes) {
+            let range = self
+                .fetch_compact_range(prefix, RangeMode::Sha1, true)
+                .await?;
+            for index in indices {
+                let (entry, hash) = (&entries[index], &hashes[index]);
+                let result = ExportAuditEntry {
+                    site: entry.site.clone(),
+                    username: entry.username.clone(),
+                    count: range_count(&range, &hash[5..]),
+                    reused_by: usage[hash.as_str()] - 1,
+                };
+                if result.is_exposed() {
+                    exposed += 1;
+                }
+                sink.write(result).await?;
+            }
+        }
+        sink.flush().await?;
+
+        Ok(exposed)
+    }
+}
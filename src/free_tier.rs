@@ -0,0 +1,79 @@
+use crate::HaveIBeenPwned;
+use std::fmt::Display;
+
+/// Endpoints that can be called without an API key.
+const FREE_ENDPOINTS: &[&str] = &["breaches", "breach", "latestbreach", "dataclasses", "range"];
+
+/// Error returned when a free-tier client calls an endpoint that needs a paid API key.
+///
+/// Returned by clients created with [`HaveIBeenPwned::new_free_tier`] before any request
+/// is sent, as [`crate::HibpError::SubscriptionRequired`]:
+///
+/// ```
+/// # use hibp_rs::{HaveIBeenPwned, HibpError};
+/// # async fn example() {
+/// let hibp = HaveIBeenPwned::new_free_tier();
+/// if let Err(e) = hibp.get_breaches_for_account("test@example.com").await {
+///     if let HibpError::SubscriptionRequired(required) = e {
+///         println!("Set HIBP_API_KEY to use {}", required.endpoint);
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionRequired {
+    /// Endpoint that needs a key, e.g. `breachedaccount`.
+    pub endpoint: String,
+}
+
+impl Display for SubscriptionRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The {} endpoint requires a paid HIBP API key, but the client is in free-tier mode",
+            self.endpoint
+        )
+    }
+}
+
+impl std::error::Error for SubscriptionRequired {}
+
+impl HaveIBeenPwned {
+    /// Creates a client restricted to data available without a paid API key.
+    ///
+    /// Free-tier clients can look up breach metadata ([`HaveIBeenPwned::get_all_breaches`],
+    /// [`HaveIBeenPwned::get_breach_by_name`], [`HaveIBeenPwned::get_latest_breach`]) and
+    /// Pwned Passwords. Every other call fails with [`SubscriptionRequired`] without
+    /// contacting the API, which suits open-source tools whose users have no key. Breaches
+    /// whose [`crate::Breach::is_subscription_free`] flag is set are the ones HIBP also
+    /// reports in free domain searches.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// let hibp = HaveIBeenPwned::new_free_tier();
+    /// assert!(hibp.is_free_tier());
+    /// ```
+    pub fn new_free_tier() -> Self {
+        HaveIBeenPwned {
+            free_tier: true,
+            ..Self::new("")
+        }
+    }
+
+    /// Returns whether the client was created with [`HaveIBeenPwned::new_free_tier`].
+    pub fn is_free_tier(&self) -> bool {
+        self.free_tier
+    }
+
+    /// Fails if the client is in free-tier mode and the endpoint needs an API key.
+    pub(crate) fn check_free_tier(&self, endpoint: &str) -> Result<(), SubscriptionRequired> {
+        if self.free_tier && !FREE_ENDPOINTS.contains(&endpoint) {
+            return Err(SubscriptionRequired {
+                endpoint: endpoint.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
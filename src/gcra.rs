@@ -0,0 +1,31 @@
+use governor::{DefaultDirectRateLimiter, Jitter, Quota};
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+/// GCRA bucket behind a [`crate::RateLimiter`], with the `governor` feature.
+#[derive(Debug)]
+pub(crate) struct Bucket {
+    limiter: DefaultDirectRateLimiter,
+}
+
+impl Bucket {
+    /// Creates a bucket that allows a full burst right away.
+    pub(crate) fn new(rpm: i32, burst: u32) -> Self {
+        let rpm = NonZeroU32::new(rpm.max(1) as u32).unwrap_or(NonZeroU32::MIN);
+        let burst = NonZeroU32::new(burst).unwrap_or(NonZeroU32::MIN);
+        Bucket {
+            limiter: DefaultDirectRateLimiter::direct(Quota::per_minute(rpm).allow_burst(burst)),
+        }
+    }
+
+    /// Waits until a request conforms to the quota, plus a random delay of up to `jitter`.
+    pub(crate) async fn acquire(&self, jitter: Duration) {
+        if jitter.is_zero() {
+            self.limiter.until_ready().await;
+        } else {
+            self.limiter
+                .until_ready_with_jitter(Jitter::up_to(jitter))
+                .await;
+        }
+    }
+}
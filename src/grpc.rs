@@ -0,0 +1,473 @@
+use crate::hashing::secret;
+use crate::{Breach, Event, HaveIBeenPwned, HibpError, Notification, Notifier};
+use prost::Message;
+use std::net::SocketAddr;
+use tokio::sync::broadcast;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::BroadcastStream;
+use tonic::codegen::{Arc, Body, BoxFuture, Context, Poll, Service, StdError, http};
+use tonic::{Request, Response, Status};
+
+/// Protobuf messages of the `hibp.v1` package, matching `proto/hibp.proto`.
+pub mod proto {
+    /// Request of `CheckAccount`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CheckAccountRequest {
+        /// The account to check.
+        #[prost(string, tag = "1")]
+        pub account: String,
+    }
+
+    /// A breach, as returned by `CheckAccount`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Breach {
+        /// Name of the breach.
+        #[prost(string, tag = "1")]
+        pub name: String,
+        /// Title of the breach.
+        #[prost(string, tag = "2")]
+        pub title: String,
+        /// Domain associated with the breach.
+        #[prost(string, tag = "3")]
+        pub domain: String,
+        /// Date the breach occurred.
+        #[prost(string, tag = "4")]
+        pub breach_date: String,
+        /// Date the breach was added to HIBP.
+        #[prost(string, tag = "5")]
+        pub added_date: String,
+        /// Number of accounts affected.
+        #[prost(uint64, tag = "6")]
+        pub pwn_count: u64,
+        /// Data classes compromised in the breach.
+        #[prost(string, repeated, tag = "7")]
+        pub data_classes: Vec<String>,
+        /// Whether the breach is verified.
+        #[prost(bool, tag = "8")]
+        pub is_verified: bool,
+        /// Whether the breach is sensitive.
+        #[prost(bool, tag = "9")]
+        pub is_sensitive: bool,
+    }
+
+    /// Response of `CheckAccount`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CheckAccountResponse {
+        /// Breaches of the account.
+        #[prost(message, repeated, tag = "1")]
+        pub breaches: Vec<Breach>,
+    }
+
+    /// Request of `CheckPassword`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CheckPasswordRequest {
+        /// Hex SHA-1 hash of the password.
+        #[prost(string, tag = "1")]
+        pub sha1_hash: String,
+        /// Plaintext password, used only if `sha1_hash` is empty.
+        #[prost(string, tag = "2")]
+        pub password: String,
+    }
+
+    /// Response of `CheckPassword`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct CheckPasswordResponse {
+        /// Number of times the password appears in Pwned Passwords.
+        #[prost(uint64, tag = "1")]
+        pub count: u64,
+    }
+
+    /// Request of `SubscribeEvents`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct SubscribeEventsRequest {
+        /// Only stream events with these actions; all events if empty.
+        #[prost(string, repeated, tag = "1")]
+        pub actions: Vec<String>,
+    }
+
+    /// An event streamed by `SubscribeEvents`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Event {
+        /// When the event was created.
+        #[prost(string, tag = "1")]
+        pub timestamp: String,
+        /// What happened.
+        #[prost(string, tag = "2")]
+        pub action: String,
+        /// `info`, `low`, `medium`, `high` or `critical`.
+        #[prost(string, tag = "3")]
+        pub severity: String,
+        /// Human-readable description.
+        #[prost(string, tag = "4")]
+        pub message: String,
+        /// Affected account, if any.
+        #[prost(string, optional, tag = "5")]
+        pub account: Option<String>,
+        /// Name of the breach, for breach alerts.
+        #[prost(string, optional, tag = "6")]
+        pub breach: Option<String>,
+        /// Pwned Passwords count, for password findings.
+        #[prost(uint64, optional, tag = "7")]
+        pub count: Option<u64>,
+        /// Location of the finding, if any.
+        #[prost(string, optional, tag = "8")]
+        pub location: Option<String>,
+    }
+
+    /// `google.rpc.Status`, carrying the error details of a failed call.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct RpcStatus {
+        #[prost(int32, tag = "1")]
+        pub code: i32,
+        #[prost(string, tag = "2")]
+        pub message: String,
+        #[prost(message, repeated, tag = "3")]
+        pub details: Vec<Any>,
+    }
+
+    /// `google.protobuf.Any`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct Any {
+        #[prost(string, tag = "1")]
+        pub type_url: String,
+        #[prost(bytes = "vec", tag = "2")]
+        pub value: Vec<u8>,
+    }
+
+    /// `google.rpc.RetryInfo`, telling clients how long to wait before retrying.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct RetryInfo {
+        #[prost(message, optional, tag = "1")]
+        pub retry_delay: Option<Duration>,
+    }
+
+    /// `google.protobuf.Duration`.
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub(crate) struct Duration {
+        #[prost(int64, tag = "1")]
+        pub seconds: i64,
+        #[prost(int32, tag = "2")]
+        pub nanos: i32,
+    }
+
+    /// Type URL of [`RetryInfo`] in an [`Any`].
+    pub(crate) const RETRY_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.RetryInfo";
+}
+
+impl From<Breach> for proto::Breach {
+    fn from(breach: Breach) -> Self {
+        proto::Breach {
+            name: breach.name,
+            title: breach.title,
+            domain: breach.domain,
+            breach_date: breach.breach_date.to_string(),
+            added_date: crate::breach::format_timestamp(&breach.added_date),
+            pwn_count: breach.pwn_count,
+            data_classes: breach.data_classes,
+            is_verified: breach.is_verified,
+            is_sensitive: breach.is_sensitive,
+        }
+    }
+}
+
+impl From<Event> for proto::Event {
+    fn from(event: Event) -> Self {
+        proto::Event {
+            timestamp: event.timestamp,
+            action: event.action,
+            severity: format!("{:?}", event.severity).to_lowercase(),
+            message: event.message,
+            account: event.account,
+            breach: event.breach,
+            count: event.count,
+            location: event.location,
+        }
+    }
+}
+
+/// Shared state of a [`GrpcGateway`] and its clones.
+#[derive(Debug)]
+struct GatewayInner {
+    client: HaveIBeenPwned,
+    events: broadcast::Sender<Event>,
+}
+
+/// Exposes a client as the `hibp.v1.Hibp` gRPC service described in `proto/hibp.proto`.
+///
+/// All requests go through the one client, so every consumer shares its rate limiter and
+/// API key. Events published with [`GrpcGateway::publish`] (or by using the gateway as a
+/// [`Notifier`]) are streamed to `SubscribeEvents` callers.
+///
+/// Client errors are returned with the matching gRPC status, e.g. `INVALID_ARGUMENT` for
+/// malformed hashes, `UNAUTHENTICATED` for a rejected API key and `RESOURCE_EXHAUSTED`
+/// with a `google.rpc.RetryInfo` detail when rate limited.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{GrpcGateway, HaveIBeenPwned};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let hibp = HaveIBeenPwned::new_with_auto_rate_limit("your_api_key").await?;
+/// let gateway = GrpcGateway::new(hibp);
+/// gateway.serve("0.0.0.0:50051".parse()?).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GrpcGateway {
+    inner: Arc<GatewayInner>,
+}
+
+impl GrpcGateway {
+    /// Full gRPC name of the service.
+    pub const SERVICE_NAME: &'static str = "hibp.v1.Hibp";
+
+    /// Creates a gateway forwarding requests to the given client.
+    ///
+    /// Subscribers that fall more than 1024 events behind miss the oldest ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client all requests are made with.
+    pub fn new(client: HaveIBeenPwned) -> Self {
+        let (events, _) = broadcast::channel(1024);
+        GrpcGateway {
+            inner: Arc::new(GatewayInner { client, events }),
+        }
+    }
+
+    /// Streams an event to all current `SubscribeEvents` callers.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to stream.
+    pub fn publish(&self, event: Event) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.inner.events.send(event);
+    }
+
+    /// Serves the gateway on the given address until the process exits.
+    ///
+    /// Use the gateway as a service of a [`tonic::transport::Server`] to combine it with
+    /// other services, TLS or interceptors.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Address to listen on.
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), HibpError> {
+        tonic::transport::Server::builder()
+            .add_service(self)
+            .serve(addr)
+            .await
+            .map_err(HibpError::other)?;
+        Ok(())
+    }
+
+    async fn check_account(
+        &self,
+        request: proto::CheckAccountRequest,
+    ) -> Result<proto::CheckAccountResponse, Status> {
+        if request.account.trim().is_empty() {
+            return Err(Status::invalid_argument("account must not be empty"));
+        }
+        let breaches = self
+            .inner
+            .client
+            .get_breaches_for_account(&request.account)
+            .await
+            .map_err(error_status)?;
+        Ok(proto::CheckAccountResponse {
+            breaches: breaches.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    async fn check_password(
+        &self,
+        request: proto::CheckPasswordRequest,
+    ) -> Result<proto::CheckPasswordResponse, Status> {
+        let proto::CheckPasswordRequest {
+            sha1_hash,
+            password,
+        } = request;
+        let (sha1_hash, password) = (secret(sha1_hash), secret(password));
+
+        let count = if sha1_hash.is_empty() {
+            self.inner.client.check_password_padded(&password).await
+        } else {
+            self.inner
+                .client
+                .check_password_hash_padded(sha1_hash.as_str())
+                .await
+        }
+        .map_err(error_status)?;
+        Ok(proto::CheckPasswordResponse { count })
+    }
+
+    // The stream item type is dictated by tonic
+    #[allow(clippy::result_large_err)]
+    fn subscribe_events(
+        &self,
+        request: proto::SubscribeEventsRequest,
+    ) -> impl tokio_stream::Stream<Item = Result<proto::Event, Status>> + Send + 'static {
+        let actions = request.actions;
+        BroadcastStream::new(self.inner.events.subscribe()).filter_map(move |event| {
+            // Lagging subscribers skip the events they missed
+            let event = event.ok()?;
+            (actions.is_empty() || actions.contains(&event.action)).then(|| Ok(event.into()))
+        })
+    }
+}
+
+/// Maps a client error to the gRPC status of the failed call.
+///
+/// Rate limit and budget errors carry a `google.rpc.RetryInfo` detail when the wait is
+/// known, so gRPC clients can back off like direct API callers.
+pub(crate) fn error_status(error: HibpError) -> Status {
+    let message = error.to_string();
+    match error {
+        HibpError::InvalidInput(_) => Status::invalid_argument(message),
+        HibpError::Unauthorized { .. } => Status::unauthenticated(message),
+        HibpError::Forbidden { .. }
+        | HibpError::SubscriptionRequired(_)
+        | HibpError::Blocked(_) => Status::permission_denied(message),
+        HibpError::NotFound => Status::not_found(message),
+        HibpError::RateLimited { retry_after } => resource_exhausted(message, retry_after),
+        HibpError::BudgetExceeded(budget) => resource_exhausted(message, Some(budget.resets_in)),
+        HibpError::Deserialize(_) | HibpError::ResponseTooLarge(_) => Status::internal(message),
+        _ => Status::unavailable(message),
+    }
+}
+
+/// Builds a `RESOURCE_EXHAUSTED` status, with a `google.rpc.RetryInfo` detail if the wait
+/// is known.
+fn resource_exhausted(message: String, retry_after: Option<std::time::Duration>) -> Status {
+    let Some(retry_after) = retry_after else {
+        return Status::resource_exhausted(message);
+    };
+    let retry_info = proto::RetryInfo {
+        retry_delay: Some(proto::Duration {
+            seconds: i64::try_from(retry_after.as_secs()).unwrap_or(i64::MAX),
+            nanos: retry_after.subsec_nanos() as i32,
+        }),
+    };
+    let details = proto::RpcStatus {
+        code: tonic::Code::ResourceExhausted as i32,
+        message: message.clone(),
+        details: vec![proto::Any {
+            type_url: proto::RETRY_INFO_TYPE_URL.to_string(),
+            value: retry_info.encode_to_vec(),
+        }],
+    };
+    Status::with_details(
+        tonic::Code::ResourceExhausted,
+        message,
+        details.encode_to_vec().into(),
+    )
+}
+
+impl Notifier for GrpcGateway {
+    async fn notify(&self, notification: &Notification) -> Result<(), HibpError> {
+        for event in notification.events() {
+            self.publish(event);
+        }
+        Ok(())
+    }
+}
+
+/// Tonic's unary service interface for `CheckAccount`.
+struct CheckAccount(GrpcGateway);
+
+impl tonic::server::UnaryService<proto::CheckAccountRequest> for CheckAccount {
+    type Response = proto::CheckAccountResponse;
+    type Future = BoxFuture<Response<Self::Response>, Status>;
+
+    fn call(&mut self, request: Request<proto::CheckAccountRequest>) -> Self::Future {
+        let gateway = self.0.clone();
+        Box::pin(async move {
+            let response = gateway.check_account(request.into_inner()).await?;
+            Ok(Response::new(response))
+        })
+    }
+}
+
+/// Tonic's unary service interface for `CheckPassword`.
+struct CheckPassword(GrpcGateway);
+
+impl tonic::server::UnaryService<proto::CheckPasswordRequest> for CheckPassword {
+    type Response = proto::CheckPasswordResponse;
+    type Future = BoxFuture<Response<Self::Response>, Status>;
+
+    fn call(&mut self, request: Request<proto::CheckPasswordRequest>) -> Self::Future {
+        let gateway = self.0.clone();
+        Box::pin(async move {
+            let response = gateway.check_password(request.into_inner()).await?;
+            Ok(Response::new(response))
+        })
+    }
+}
+
+/// Tonic's server-streaming interface for `SubscribeEvents`.
+struct SubscribeEvents(GrpcGateway);
+
+impl tonic::server::ServerStreamingService<proto::SubscribeEventsRequest> for SubscribeEvents {
+    type Response = proto::Event;
+    type ResponseStream =
+        std::pin::Pin<Box<dyn tokio_stream::Stream<Item = Result<proto::Event, Status>> + Send>>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<proto::SubscribeEventsRequest>) -> Self::Future {
+        let stream = self.0.subscribe_events(request.into_inner());
+        Box::pin(async move { Ok(Response::new(Box::pin(stream) as Self::ResponseStream)) })
+    }
+}
+
+impl<B> Service<http::Request<B>> for GrpcGateway
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<tonic::body::Body>;
+    type Error = std::convert::Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let gateway = self.clone();
+
+        match req.uri().path() {
+            "/hibp.v1.Hibp/CheckAccount" => Box::pin(async move {
+                let mut grpc = tonic::server::Grpc::new(tonic::codec::ProstCodec::default());
+                Ok(grpc.unary(CheckAccount(gateway), req).await)
+            }),
+            "/hibp.v1.Hibp/CheckPassword" => Box::pin(async move {
+                let mut grpc = tonic::server::Grpc::new(tonic::codec::ProstCodec::default());
+                Ok(grpc.unary(CheckPassword(gateway), req).await)
+            }),
+            "/hibp.v1.Hibp/SubscribeEvents" => Box::pin(async move {
+                let mut grpc = tonic::server::Grpc::new(tonic::codec::ProstCodec::default());
+                Ok(grpc.server_streaming(SubscribeEvents(gateway), req).await)
+            }),
+            _ => Box::pin(async move {
+                let mut response = http::Response::new(tonic::body::Body::default());
+                let headers = response.headers_mut();
+                headers.insert(
+                    tonic::Status::GRPC_STATUS,
+                    (tonic::Code::Unimplemented as i32).into(),
+                );
+                headers.insert(
+                    http::header::CONTENT_TYPE,
+                    tonic::metadata::GRPC_CONTENT_TYPE,
+                );
+                Ok(response)
+            }),
+        }
+    }
+}
+
+impl tonic::server::NamedService for GrpcGateway {
+    const NAME: &'static str = GrpcGateway::SERVICE_NAME;
+}
@@ -0,0 +1,141 @@
+//! Hashing helpers matching the rules of the Pwned Passwords range API.
+//!
+//! The range API expects uppercase hex digests, queried by their first
+//! [`PREFIX_LEN`] characters. These helpers hash with [`crate::RustCryptoBackend`];
+//! use [`crate::HaveIBeenPwned::with_hash_backend`] to hash client lookups differently.
+//!
+//! # Example
+//!
+//! ```
+//! use hibp_rs::hash;
+//!
+//! let digest = hash::sha1_hex("password");
+//! let (prefix, suffix) = hash::split_hash(&digest).unwrap();
+//! assert_eq!(prefix, "5BAA6");
+//! assert_eq!(suffix, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+//! ```
+
+use crate::hashing::{HashBackend, RustCryptoBackend, to_hex_upper};
+
+/// Number of hash characters sent to the range API.
+pub const PREFIX_LEN: usize = 5;
+
+/// Length of a hex encoded SHA-1 digest.
+pub const SHA1_HEX_LEN: usize = 40;
+
+/// Length of a hex encoded NTLM digest.
+pub const NTLM_HEX_LEN: usize = 32;
+
+/// Returns the uppercase hex SHA-1 digest of a password, as used by the range API.
+///
+/// # Arguments
+///
+/// * `password` - The password to hash
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::hash;
+/// assert_eq!(hash::sha1_hex("password"), "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8");
+/// ```
+pub fn sha1_hex(password: &str) -> String {
+    to_hex_upper(&RustCryptoBackend.sha1(password.as_bytes()))
+}
+
+/// Returns the uppercase hex NTLM digest (MD4 of the UTF-16LE encoding) of a password,
+/// as used by the range API in NTLM mode.
+///
+/// # Arguments
+///
+/// * `password` - The password to hash
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::hash;
+/// assert_eq!(hash::ntlm_hex("password"), "8846F7EAEE8FB117AD06BDD830B7586C");
+/// ```
+pub fn ntlm_hex(password: &str) -> String {
+    let digest = RustCryptoBackend
+        .ntlm(password)
+        .expect("RustCryptoBackend implements NTLM");
+    to_hex_upper(&digest)
+}
+
+/// Splits a hex SHA-1 or NTLM digest into the uppercase range prefix and suffix.
+///
+/// Returns `None` unless the hash consists of exactly [`SHA1_HEX_LEN`] or
+/// [`NTLM_HEX_LEN`] hex characters. Lowercase input is accepted.
+///
+/// # Arguments
+///
+/// * `hash` - The hex encoded digest
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::hash;
+/// let (prefix, suffix) = hash::split_hash("8846f7eaee8fb117ad06bdd830b7586c").unwrap();
+/// assert_eq!(prefix, "8846F");
+/// assert_eq!(suffix, "7EAEE8FB117AD06BDD830B7586C");
+/// assert!(hash::split_hash("not a hash").is_none());
+/// ```
+pub fn split_hash(hash: &str) -> Option<(String, String)> {
+    if !matches!(hash.len(), SHA1_HEX_LEN | NTLM_HEX_LEN)
+        || !hash.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return None;
+    }
+    let hash = hash.to_ascii_uppercase();
+    let (prefix, suffix) = hash.split_at(PREFIX_LEN);
+    Some((prefix.to_string(), suffix.to_string()))
+}
+
+/// A SHA-1 digest given as hex text or as the raw 20 bytes.
+///
+/// Accepted by [`crate::HaveIBeenPwned::check_password_hash`] so stored digests can be
+/// checked without the plaintext password.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::hash::Sha1Digest;
+/// let digest = [0x5B, 0xAA, 0x61, 0xE4, 0xC9, 0xB9, 0x3F, 0x3F, 0x06, 0x82,
+///               0x25, 0x0B, 0x6C, 0xF8, 0x33, 0x1B, 0x7E, 0xE6, 0x8F, 0xD8];
+/// assert_eq!(digest.to_sha1_hex().unwrap(), "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8");
+/// assert_eq!(
+///     "5baa61e4c9b93f3f0682250b6cf8331b7ee68fd8".to_sha1_hex().unwrap(),
+///     "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8"
+/// );
+/// assert!("5BAA6".to_sha1_hex().is_none());
+/// ```
+pub trait Sha1Digest {
+    /// Returns the uppercase hex digest, or `None` if this is not a valid SHA-1 digest.
+    fn to_sha1_hex(&self) -> Option<String>;
+}
+
+impl Sha1Digest for str {
+    fn to_sha1_hex(&self) -> Option<String> {
+        let hash = self.trim();
+        (hash.len() == SHA1_HEX_LEN && hash.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| hash.to_ascii_uppercase())
+    }
+}
+
+impl Sha1Digest for String {
+    fn to_sha1_hex(&self) -> Option<String> {
+        self.as_str().to_sha1_hex()
+    }
+}
+
+impl Sha1Digest for [u8; 20] {
+    fn to_sha1_hex(&self) -> Option<String> {
+        Some(to_hex_upper(self))
+    }
+}
+
+impl<T: Sha1Digest + ?Sized> Sha1Digest for &T {
+    fn to_sha1_hex(&self) -> Option<String> {
+        (**self).to_sha1_hex()
+    }
+}
@@ -0,0 +1,270 @@
+use crate::HaveIBeenPwned;
+use std::sync::Arc;
+
+/// Computes the password hashes looked up in Pwned Passwords.
+///
+/// The client hashes passwords with [`RustCryptoBackend`] unless another backend is set
+/// with [`HaveIBeenPwned::with_hash_backend`], e.g. to use a validated implementation in
+/// FIPS-constrained environments.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{HashBackend, HaveIBeenPwned};
+///
+/// #[derive(Debug)]
+/// struct AuditedSha1;
+///
+/// impl HashBackend for AuditedSha1 {
+///     fn sha1(&self, data: &[u8]) -> [u8; 20] {
+///         // Call the approved implementation here
+///         # hibp_rs::RustCryptoBackend.sha1(data)
+///     }
+/// }
+///
+/// let hibp = HaveIBeenPwned::new("your_api_key").with_hash_backend(AuditedSha1);
+/// ```
+pub trait HashBackend: std::fmt::Debug + Send + Sync {
+    /// Returns the SHA-1 digest of the data.
+    fn sha1(&self, data: &[u8]) -> [u8; 20];
+
+    /// Returns the SHA-1 digests of many inputs, in input order.
+    ///
+    /// Override this if the implementation can hash several inputs at once, e.g. with
+    /// multi-buffer SIMD.
+    fn sha1_batch(&self, inputs: &[&[u8]]) -> Vec<[u8; 20]> {
+        inputs.iter().map(|data| self.sha1(data)).collect()
+    }
+
+    /// Returns the NTLM hash (MD4 of the UTF-16LE encoding) of a password.
+    ///
+    /// Returns `None` if the backend does not implement MD4, which approved FIPS modules
+    /// don't.
+    fn ntlm(&self, password: &str) -> Option<[u8; 16]> {
+        let _ = password;
+        None
+    }
+}
+
+/// Pure-Rust hashing with the `sha1` and `md4` crates. The default backend.
+///
+/// SHA-1 uses the SHA extensions of x86 and ARM CPUs when they are detected at runtime.
+/// The `asm` feature adds assembly implementations for CPUs without them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RustCryptoBackend;
+
+impl HashBackend for RustCryptoBackend {
+    fn sha1(&self, data: &[u8]) -> [u8; 20] {
+        use sha1::Digest;
+        sha1::Sha1::digest(data).into()
+    }
+
+    fn ntlm(&self, password: &str) -> Option<[u8; 16]> {
+        use md4::Digest;
+        let mut utf16 = Vec::with_capacity(password.len() * 2);
+        utf16.extend(password.encode_utf16().flat_map(u16::to_le_bytes));
+        let utf16 = secret(utf16);
+        Some(md4::Md4::digest(utf16.as_slice()).into())
+    }
+}
+
+/// Hashing with ring's SHA-1 implementation (`ring` feature).
+///
+/// ring does not implement MD4, so NTLM hashing is unavailable with this backend.
+#[cfg(feature = "ring")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RingBackend;
+
+#[cfg(feature = "ring")]
+impl HashBackend for RingBackend {
+    fn sha1(&self, data: &[u8]) -> [u8; 20] {
+        let digest = ring::digest::digest(&ring::digest::SHA1_FOR_LEGACY_USE_ONLY, data);
+        let mut hash = [0u8; 20];
+        hash.copy_from_slice(digest.as_ref());
+        hash
+    }
+}
+
+/// Hashing with AWS-LC's SHA-1 implementation (`aws-lc` feature, native targets only).
+///
+/// AWS-LC has a FIPS 140-3 validated module. This crate doesn't expose a feature for it, as
+/// building it requires CMake and Go; enable it in the application instead, and cargo
+/// builds the backend on top of it:
+///
+/// ```toml
+/// aws-lc-rs = { version = "1", features = ["fips"] }
+/// ```
+///
+/// Check [`AwsLcBackend::is_fips`] at startup to make sure the validated module is in use.
+/// AWS-LC's FIPS module does not approve MD4, so NTLM hashing is unavailable with this
+/// backend.
+#[cfg(all(feature = "aws-lc", not(target_arch = "wasm32")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AwsLcBackend;
+
+#[cfg(all(feature = "aws-lc", not(target_arch = "wasm32")))]
+impl AwsLcBackend {
+    /// Whether AWS-LC runs as its FIPS validated module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::{AwsLcBackend, HaveIBeenPwned};
+    /// if !AwsLcBackend::is_fips() {
+    ///     eprintln!("AWS-LC is not running in FIPS mode");
+    /// }
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_hash_backend(AwsLcBackend);
+    /// ```
+    pub fn is_fips() -> bool {
+        aws_lc_rs::try_fips_mode().is_ok()
+    }
+}
+
+#[cfg(all(feature = "aws-lc", not(target_arch = "wasm32")))]
+impl HashBackend for AwsLcBackend {
+    fn sha1(&self, data: &[u8]) -> [u8; 20] {
+        let digest = aws_lc_rs::digest::digest(&aws_lc_rs::digest::SHA1_FOR_LEGACY_USE_ONLY, data);
+        let mut hash = [0u8; 20];
+        hash.copy_from_slice(digest.as_ref());
+        hash
+    }
+}
+
+/// Batches at least this large are hashed on several threads.
+#[cfg(not(target_arch = "wasm32"))]
+const PARALLEL_BATCH_THRESHOLD: usize = 4096;
+
+/// Hashes many inputs with a backend, on several threads for large batches.
+fn sha1_batch_with(backend: &dyn HashBackend, inputs: &[&[u8]]) -> Vec<[u8; 20]> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        if threads > 1 && inputs.len() >= PARALLEL_BATCH_THRESHOLD {
+            let chunk_size = inputs.len().div_ceil(threads);
+            return std::thread::scope(|scope| {
+                let handles: Vec<_> = inputs
+                    .chunks(chunk_size)
+                    .map(|chunk| scope.spawn(move || backend.sha1_batch(chunk)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().expect("hashing thread panicked"))
+                    .collect()
+            });
+        }
+    }
+
+    backend.sha1_batch(inputs)
+}
+
+/// Formats bytes as uppercase hex, as used by the range API.
+///
+/// The string is allocated once at its final size, so no partial copies of a password
+/// hash are left behind in reallocated buffers.
+pub(crate) fn to_hex_upper(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(DIGITS[usize::from(byte >> 4)] as char);
+        hex.push(DIGITS[usize::from(byte & 0x0F)] as char);
+    }
+    hex
+}
+
+/// Password material, wiped from memory when dropped with the `zeroize` feature.
+#[cfg(feature = "zeroize")]
+pub(crate) type Secret<T> = zeroize::Zeroizing<T>;
+
+/// Password material, wiped from memory when dropped with the `zeroize` feature.
+#[cfg(not(feature = "zeroize"))]
+pub(crate) type Secret<T> = T;
+
+/// Marks a plaintext, digest or hex hash of a password to be wiped when dropped.
+#[cfg(feature = "zeroize")]
+pub(crate) fn secret<T: zeroize::Zeroize>(value: T) -> Secret<T> {
+    zeroize::Zeroizing::new(value)
+}
+
+/// Marks a plaintext, digest or hex hash of a password to be wiped when dropped.
+#[cfg(not(feature = "zeroize"))]
+pub(crate) fn secret<T>(value: T) -> Secret<T> {
+    value
+}
+
+impl HaveIBeenPwned {
+    /// Sets the backend passwords are hashed with before they are looked up.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The hashing implementation to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::{HaveIBeenPwned, RustCryptoBackend};
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_hash_backend(RustCryptoBackend);
+    /// ```
+    #[must_use]
+    pub fn with_hash_backend<B: HashBackend + 'static>(mut self, backend: B) -> Self {
+        self.hash_backend = Arc::new(backend);
+        self
+    }
+
+    /// Hashes many passwords with SHA-1, spreading the work over all CPU cores.
+    ///
+    /// Intended for offline audits of millions of passwords, where hashing one at a time
+    /// dominates the run time. Digests are returned in input order. This blocks the calling
+    /// thread until all passwords are hashed, so don't call it on an async runtime with
+    /// large batches; [`HaveIBeenPwned::check_passwords`] hashes them on tokio's blocking
+    /// pool instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `passwords` - The passwords to hash.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let digests = hibp.sha1_batch(&["password", "hunter2"]);
+    /// assert_eq!(digests[0][0], 0x5B);
+    /// ```
+    pub fn sha1_batch(&self, passwords: &[&str]) -> Vec<[u8; 20]> {
+        let inputs: Vec<&[u8]> = passwords.iter().map(|p| p.as_bytes()).collect();
+        sha1_batch_with(self.hash_backend.as_ref(), &inputs)
+    }
+
+    /// Like [`HaveIBeenPwned::sha1_batch`] without blocking the async runtime.
+    ///
+    /// Small batches are hashed inline. Larger ones are copied and hashed on tokio's
+    /// blocking pool on native targets, so the worker thread keeps driving other tasks.
+    pub(crate) async fn sha1_batch_async(&self, passwords: &[&str]) -> Vec<[u8; 20]> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if passwords.len() >= PARALLEL_BATCH_THRESHOLD {
+            let backend = Arc::clone(&self.hash_backend);
+            // The blocking task needs owned passwords, wiped like the originals
+            let owned: Secret<Vec<String>> =
+                secret(passwords.iter().map(|p| p.to_string()).collect());
+            return tokio::task::spawn_blocking(move || {
+                let inputs: Vec<&[u8]> = owned.iter().map(|p| p.as_bytes()).collect();
+                sha1_batch_with(backend.as_ref(), &inputs)
+            })
+            .await
+            .expect("hashing task panicked");
+        }
+
+        self.sha1_batch(passwords)
+    }
+
+    /// Computes the uppercase hex SHA-1 digest of a password with the configured backend.
+    pub(crate) fn sha1_hex(&self, password: &str) -> Secret<String> {
+        self.sha1_hex_bytes(password.as_bytes())
+    }
+
+    /// Computes the uppercase hex SHA-1 digest of password bytes with the configured backend.
+    pub(crate) fn sha1_hex_bytes(&self, password: &[u8]) -> Secret<String> {
+        let digest = secret(self.hash_backend.sha1(password));
+        secret(to_hex_upper(digest.as_slice()))
+    }
+}
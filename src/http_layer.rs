@@ -0,0 +1,75 @@
+use crate::{HaveIBeenPwned, HibpError};
+use tower::util::BoxCloneSyncService;
+use tower::{BoxError, Layer, Service, ServiceExt};
+
+/// The HTTP stack requests are sent through, as a boxed tower service.
+pub type HttpService = BoxCloneSyncService<reqwest::Request, reqwest::Response, BoxError>;
+
+impl HaveIBeenPwned {
+    /// Sends requests through a tower layer, e.g. timeouts, load shedding, concurrency
+    /// limits or tracing from the tower ecosystem.
+    ///
+    /// Layers wrap the HTTP client, below the client's own retries, rate limiting and
+    /// caching, so they see every attempt. Each call adds a layer around the previous
+    /// ones. Set the HTTP client and timeouts first: layers wrap the client set at the
+    /// time they are added. Errors of layers are returned as [`HibpError::Other`].
+    ///
+    /// Requires the `tower` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `layer` - The layer to wrap the HTTP stack in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// use tower::util::MapRequestLayer;
+    ///
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_http_layer(MapRequestLayer::new(
+    ///     |mut request: reqwest::Request| {
+    ///         request
+    ///             .headers_mut()
+    ///             .insert("x-request-source", "billing".parse().unwrap());
+    ///         request
+    ///     },
+    /// ));
+    /// ```
+    #[must_use]
+    pub fn with_http_layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<HttpService>,
+        L::Service:
+            Service<reqwest::Request, Response = reqwest::Response> + Clone + Send + Sync + 'static,
+        <L::Service as Service<reqwest::Request>>::Error: Into<BoxError>,
+        <L::Service as Service<reqwest::Request>>::Future: Send + 'static,
+    {
+        let inner = self.http_service();
+        let service = layer.layer(inner).map_err(Into::into);
+        self.http_service = Some(BoxCloneSyncService::new(service));
+        self
+    }
+
+    /// Returns the HTTP stack as a tower service: the HTTP client wrapped in the layers
+    /// set with [`HaveIBeenPwned::with_http_layer`].
+    ///
+    /// Requires the `tower` feature.
+    pub fn http_service(&self) -> HttpService {
+        self.http_service.clone().unwrap_or_else(|| {
+            BoxCloneSyncService::new(self.client.clone().map_err(BoxError::from))
+        })
+    }
+}
+
+/// Sends a request through a tower service, turning errors back into [`HibpError`].
+pub(crate) async fn call(
+    service: &HttpService,
+    request: reqwest::Request,
+) -> Result<reqwest::Response, HibpError> {
+    service.clone().oneshot(request).await.map_err(|error| {
+        match error.downcast::<reqwest::Error>() {
+            Ok(error) => HibpError::from(*error),
+            Err(error) => HibpError::other(error),
+        }
+    })
+}
@@ -0,0 +1,239 @@
+use crate::{AlertSeverity, HibpError, Notification, Notifier};
+use reqwest::Client;
+use serde_json::json;
+
+/// Returns the key incident managers use to merge repeated events into one incident.
+///
+/// Single alerts are keyed by account and breach; digests by the breaches they cover.
+fn dedup_key(notification: &Notification) -> String {
+    match notification {
+        Notification::Alert(alert) => format!("hibp:{}:{}", alert.account, alert.breach),
+        Notification::Digest { alerts } => {
+            let mut breaches: Vec<&str> = alerts.iter().map(|a| a.breach.as_str()).collect();
+            breaches.sort_unstable();
+            breaches.dedup();
+            format!("hibp:digest:{}", breaches.join(","))
+        }
+    }
+}
+
+/// Opens PagerDuty incidents through the Events API v2.
+///
+/// Only notifications at or above the minimum severity (default [`AlertSeverity::High`])
+/// trigger an incident. Repeated events for the same account and breach are merged into
+/// the open incident by their dedup key.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{AlertSeverity, DedupNotifier, PagerDutyNotifier};
+///
+/// let notifier = DedupNotifier::new(
+///     PagerDutyNotifier::new("your_routing_key").with_min_severity(AlertSeverity::Medium),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct PagerDutyNotifier {
+    routing_key: String,
+    min_severity: AlertSeverity,
+    url: String,
+    client: Client,
+}
+
+impl std::fmt::Debug for PagerDutyNotifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PagerDutyNotifier")
+            .field("routing_key", &"[REDACTED]")
+            .field("min_severity", &self.min_severity)
+            .field("url", &self.url)
+            .finish()
+    }
+}
+
+impl PagerDutyNotifier {
+    /// Creates a notifier for the given Events API v2 integration.
+    ///
+    /// # Arguments
+    ///
+    /// * `routing_key` - Integration key of the PagerDuty service.
+    pub fn new<S: Into<String>>(routing_key: S) -> Self {
+        PagerDutyNotifier {
+            routing_key: routing_key.into(),
+            min_severity: AlertSeverity::High,
+            url: "https://events.pagerduty.com/v2/enqueue".to_string(),
+            client: Client::new(),
+        }
+    }
+
+    /// Sets the lowest severity that opens an incident.
+    #[must_use]
+    pub fn with_min_severity(mut self, min_severity: AlertSeverity) -> Self {
+        self.min_severity = min_severity;
+        self
+    }
+
+    /// Sets the Events API URL, e.g. for the EU service region.
+    #[must_use]
+    pub fn with_url<S: Into<String>>(mut self, url: S) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Builds the event for a notification, or `None` if it is below the minimum severity.
+    pub(crate) fn event(&self, notification: &Notification) -> Option<serde_json::Value> {
+        let severity = notification.severity();
+        if severity < self.min_severity {
+            return None;
+        }
+
+        let severity = match severity {
+            AlertSeverity::Critical => "critical",
+            AlertSeverity::High => "error",
+            AlertSeverity::Medium => "warning",
+            AlertSeverity::Low | AlertSeverity::Info => "info",
+        };
+        Some(json!({
+            "routing_key": self.routing_key,
+            "event_action": "trigger",
+            "dedup_key": dedup_key(notification),
+            "payload": {
+                "summary": notification.summary(),
+                "source": "hibp_rs",
+                "severity": severity,
+                "custom_details": notification,
+            },
+        }))
+    }
+}
+
+impl Notifier for PagerDutyNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), HibpError> {
+        let Some(event) = self.event(notification) else {
+            return Ok(());
+        };
+
+        let resp = self.client.post(&self.url).json(&event).send().await?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(HibpError::other(format!(
+                "PagerDuty request failed with status: {}",
+                resp.status()
+            )))
+        }
+    }
+}
+
+/// Creates Opsgenie alerts through the Alert API.
+///
+/// Only notifications at or above the minimum severity (default [`AlertSeverity::High`])
+/// create an alert. Severities map to priorities from P1 (critical) to P5 (info), and
+/// repeated events for the same account and breach are merged by their alias.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{DedupNotifier, OpsgenieNotifier};
+///
+/// let notifier = DedupNotifier::new(OpsgenieNotifier::new("your_api_key"));
+/// ```
+#[derive(Clone)]
+pub struct OpsgenieNotifier {
+    api_key: String,
+    min_severity: AlertSeverity,
+    url: String,
+    client: Client,
+}
+
+impl std::fmt::Debug for OpsgenieNotifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpsgenieNotifier")
+            .field("api_key", &"[REDACTED]")
+            .field("min_severity", &self.min_severity)
+            .field("url", &self.url)
+            .finish()
+    }
+}
+
+impl OpsgenieNotifier {
+    /// Creates a notifier using the given Opsgenie API integration key.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Key of an Opsgenie API integration.
+    pub fn new<S: Into<String>>(api_key: S) -> Self {
+        OpsgenieNotifier {
+            api_key: api_key.into(),
+            min_severity: AlertSeverity::High,
+            url: "https://api.opsgenie.com/v2/alerts".to_string(),
+            client: Client::new(),
+        }
+    }
+
+    /// Sets the lowest severity that creates an alert.
+    #[must_use]
+    pub fn with_min_severity(mut self, min_severity: AlertSeverity) -> Self {
+        self.min_severity = min_severity;
+        self
+    }
+
+    /// Sets the Alert API URL, e.g. `https://api.eu.opsgenie.com/v2/alerts` for EU accounts.
+    #[must_use]
+    pub fn with_url<S: Into<String>>(mut self, url: S) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Builds the alert for a notification, or `None` if it is below the minimum severity.
+    pub(crate) fn alert(&self, notification: &Notification) -> Option<serde_json::Value> {
+        let severity = notification.severity();
+        if severity < self.min_severity {
+            return None;
+        }
+
+        let priority = match severity {
+            AlertSeverity::Critical => "P1",
+            AlertSeverity::High => "P2",
+            AlertSeverity::Medium => "P3",
+            AlertSeverity::Low => "P4",
+            AlertSeverity::Info => "P5",
+        };
+        // Opsgenie truncates messages at 130 characters
+        let message: String = notification.summary().chars().take(130).collect();
+        Some(json!({
+            "message": message,
+            "alias": dedup_key(notification),
+            "description": serde_json::to_string_pretty(notification).unwrap_or_default(),
+            "priority": priority,
+            "source": "hibp_rs",
+            "tags": ["hibp"],
+        }))
+    }
+}
+
+impl Notifier for OpsgenieNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), HibpError> {
+        let Some(alert) = self.alert(notification) else {
+            return Ok(());
+        };
+
+        let resp = self
+            .client
+            .post(&self.url)
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("GenieKey {}", self.api_key),
+            )
+            .json(&alert)
+            .send()
+            .await?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(HibpError::other(format!(
+                "Opsgenie request failed with status: {}",
+                resp.status()
+            )))
+        }
+    }
+}
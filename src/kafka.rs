@@ -0,0 +1,100 @@
+use crate::{Event, HibpError, Notification, Notifier};
+use rdkafka::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::util::Timeout;
+
+/// Publishes events to a Kafka topic.
+///
+/// Each event is one message whose value is the ECS document from [`Event::to_ecs_json`]
+/// and whose key is the affected account (or the event action when there is none), so all
+/// events of an account land on the same partition in order.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::KafkaSink;
+///
+/// # async fn example(findings: Vec<hibp_rs::ScanFinding>) -> Result<(), Box<dyn std::error::Error>> {
+/// let sink = KafkaSink::new("kafka-1:9092,kafka-2:9092", "security.hibp-findings")?;
+/// for finding in &findings {
+///     sink.send(&finding.to_event()).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct KafkaSink {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl std::fmt::Debug for KafkaSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaSink")
+            .field("topic", &self.topic)
+            .finish()
+    }
+}
+
+impl KafkaSink {
+    /// Creates a sink connecting to the given brokers.
+    ///
+    /// # Arguments
+    ///
+    /// * `brokers` - Comma-separated list of `host:port` bootstrap servers.
+    /// * `topic` - Topic events are published to.
+    pub fn new(brokers: &str, topic: &str) -> Result<Self, HibpError> {
+        let mut config = ClientConfig::new();
+        config
+            .set("bootstrap.servers", brokers)
+            .set("message.timeout.ms", "30000");
+        Self::with_config(&config, topic)
+    }
+
+    /// Creates a sink from a full librdkafka configuration, e.g. with SASL or TLS settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Producer configuration.
+    /// * `topic` - Topic events are published to.
+    pub fn with_config(config: &ClientConfig, topic: &str) -> Result<Self, HibpError> {
+        Ok(KafkaSink {
+            producer: config.create().map_err(HibpError::other)?,
+            topic: topic.to_string(),
+        })
+    }
+
+    /// Publishes a single event and waits for the broker to acknowledge it.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to publish.
+    pub async fn send(&self, event: &Event) -> Result<(), HibpError> {
+        let (key, payload) = record_parts(event)?;
+        self.producer
+            .send(
+                FutureRecord::to(&self.topic).key(&key).payload(&payload),
+                Timeout::Never,
+            )
+            .await
+            .map_err(|(e, _)| HibpError::other(format!("Kafka delivery failed: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Returns the message key and value of an event.
+pub(crate) fn record_parts(event: &Event) -> Result<(String, String), HibpError> {
+    let key = event
+        .account
+        .clone()
+        .unwrap_or_else(|| event.action.clone());
+    Ok((key, serde_json::to_string(&event.to_ecs_json())?))
+}
+
+impl Notifier for KafkaSink {
+    async fn notify(&self, notification: &Notification) -> Result<(), HibpError> {
+        for event in notification.events() {
+            self.send(&event).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use crate::hash::{NTLM_HEX_LEN, SHA1_HEX_LEN};
+use crate::hashing::secret;
+use crate::password::{RangeMode, range_count};
+use crate::{HaveIBeenPwned, HibpError, ResultSink};
+use std::collections::BTreeMap;
+
+/// Hash algorithm of a stored legacy password hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LegacyHashKind {
+    /// Unsalted SHA-1 of the password.
+    Sha1,
+    /// NTLM hash (MD4 of the UTF-16LE password).
+    Ntlm,
+}
+
+impl LegacyHashKind {
+    /// Returns the range API mode for hashes of this kind.
+    fn range_mode(self) -> RangeMode {
+        match self {
+            LegacyHashKind::Sha1 => RangeMode::Sha1,
+            LegacyHashKind::Ntlm => RangeMode::Ntlm,
+        }
+    }
+}
+
+/// A record of a legacy credential database and its stored password hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyHashRecord {
+    /// Identifier of the record, e.g. a user ID or login name.
+    pub id: String,
+    /// Uppercase hex password hash.
+    pub hash: String,
+    /// Algorithm the hash was computed with.
+    pub kind: LegacyHashKind,
+}
+
+impl LegacyHashRecord {
+    /// Parses an export of stored hashes, one `id,hash` or `id:hash` record per line.
+    ///
+    /// The hash kind is derived from its length: 40 hex characters for SHA-1 and 32 for
+    /// NTLM. Blank lines and lines starting with `#` are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `export` - Contents of the export.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::{LegacyHashKind, LegacyHashRecord};
+    ///
+    /// let export = "1001,5baa61e4c9b93f3f0682250b6cf8331b7ee68fd8\n1002:8846F7EAEE8FB117AD06BDD830B7586C\n";
+    /// let records = LegacyHashRecord::parse_export(export).unwrap();
+    /// assert_eq!(records[0].kind, LegacyHashKind::Sha1);
+    /// assert_eq!(records[1].id, "1002");
+    /// assert_eq!(records[1].kind, LegacyHashKind::Ntlm);
+    /// ```
+    pub fn parse_export(export: &str) -> Result<Vec<LegacyHashRecord>, HibpError> {
+        let mut records = Vec::new();
+
+        for (index, line) in export.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (id, hash) = line.rsplit_once([',', ':']).ok_or_else(|| {
+                HibpError::InvalidInput(format!("Expected id,hash on line {}", index + 1))
+            })?;
+            let hash = hash.trim();
+            if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(HibpError::InvalidInput(format!(
+                    "Invalid password hash on line {}",
+                    index + 1
+                )));
+            }
+            let kind = match hash.len() {
+                SHA1_HEX_LEN => LegacyHashKind::Sha1,
+                NTLM_HEX_LEN => LegacyHashKind::Ntlm,
+                _ => {
+                    return Err(HibpError::InvalidInput(format!(
+                        "Invalid password hash on line {}",
+                        index + 1
+                    )));
+                }
+            };
+
+            records.push(LegacyHashRecord {
+                id: id.trim().to_string(),
+                hash: hash.to_ascii_uppercase(),
+                kind,
+            });
+        }
+
+        Ok(records)
+    }
+}
+
+/// Audit result for a single legacy record.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LegacyAuditEntry {
+    /// Identifier of the record.
+    pub id: String,
+    /// Number of times the record's password appears in the Pwned Passwords corpus.
+    pub count: u64,
+}
+
+impl LegacyAuditEntry {
+    /// Whether the record's password is breached and must be reset before migration.
+    pub fn must_reset(&self) -> bool {
+        self.count > 0
+    }
+}
+
+/// Report of a legacy credential database audit.
+#[derive(Debug, Clone, Default)]
+pub struct LegacyAuditReport {
+    /// One result per audited record, in input order.
+    pub entries: Vec<LegacyAuditEntry>,
+}
+
+impl LegacyAuditReport {
+    /// Returns the records whose password must be force-reset.
+    pub fn must_reset(&self) -> impl Iterator<Item = &LegacyAuditEntry> {
+        self.entries.iter().filter(|e| e.must_reset())
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Audits stored SHA-1 or NTLM hashes of a legacy system without needing plaintext.
+    ///
+    /// Intended for migrations off legacy credential stores: the report lists the records
+    /// whose password must be force-reset. Hashes are grouped by prefix so each range is
+    /// fetched only once per hash kind, and ranges are requested with padding.
+    ///
+    /// # Arguments
+    ///
+    /// * `records` - Records and their hashes, e.g. from [`LegacyHashRecord::parse_export`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{HaveIBeenPwned, LegacyHashRecord};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let export = std::fs::read_to_string("legacy-hashes.csv")?;
+    /// let records = LegacyHashRecord::parse_export(&export)?;
+    /// let report = hibp.audit_legacy_hashes(&records).await?;
+    /// for entry in report.must_reset() {
+    ///     println!("Force a password reset for {}", entry.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn audit_legacy_hashes(
+        &self,
+        records: &[LegacyHashRecord],
+    ) -> Result<LegacyAuditReport, HibpError> {
+        let mut counts = vec![0; records.len()];
+        for kind in [LegacyHashKind::Sha1, LegacyHashKind::Ntlm] {
+            let (indices, hashes): (Vec<usize>, Vec<String>) = records
+                .iter()
+                .enumerate()
+                .filter(|(_, record)| record.kind == kind)
+                .map(|(index, record)| (index, record.hash.clone()))
+                .unzip();
+            let hashes = secret(hashes);
+            let kind_counts = self.count_hashes(&hashes, kind.range_mode()).await?;
+            for (index, count) in indices.into_iter().zip(kind_counts) {
+                counts[index] = count;
+            }
+        }
+
+        let entries = records
+            .iter()
+            .zip(counts)
+            .map(|(record, count)| LegacyAuditEntry {
+                id: record.id.clone(),
+                count,
+            })
+            .collect();
+
+        Ok(LegacyAuditReport { entries })
+    }
+
+    /// Audits stored legacy hashes, writing each result to a sink as soon as its range is fetched.
+    ///
+    /// Unlike [`HaveIBeenPwned::audit_legacy_hashes`], results are not collected in memory,
+    /// so large databases can be audited straight into a CSV file or another
+    /// [`ResultSink`]. Results arrive grouped by hash prefix rather than in input order.
+    /// The sink is flushed but not closed. Returns the number of records that must be
+    /// force-reset.
+    ///
+    /// # Arguments
+    ///
+    /// * `records` - Records and their hashes, e.g. from [`LegacyHashRecord::parse_export`].
+    /// * `sink` - Destination of the results.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{HaveIBeenPwned, LegacyHashRecord, NdjsonSink, ResultSink};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let export = std::fs::read_to_string("legacy-hashes.csv")?;
+    /// let records = LegacyHashRecord::parse_export(&export)?;
+    /// let mut sink = NdjsonSink::new(std::io::stdout());
+    /// let must_reset = hibp.audit_legacy_hashes_into(&records, &mut sink).await?;
+    /// sink.close().await?;
+    /// eprintln!("{} records must be reset", must_reset);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn audit_legacy_hashes_into<S: ResultSink<LegacyAuditEntry>>(
+        &self,
+        records: &[LegacyHashRecord],
+        sink: &mut S,
+    ) -> Result<usize, HibpError> {
+        let mut groups: BTreeMap<(LegacyHashKind, &str), Vec<&LegacyHashRecord>> = BTreeMap::new();
+        for record in records {
+            groups
+                .entry((record.kind, &record.hash[..5]))
+                .or_default()
+                .push(record);
+        }
+
+        let mut must_reset = 0;
+        for ((kind, prefix), records) in groups {
+            let range = self
+                .fetch_compact_range(prefix, kind.range_mode(), true)
+                .await?;
+            for record in records {
+                let entry = LegacyAuditEntry {
+                    id: record.id.clone(),
+                    count: range_count(&range, &record.hash[5..]),
+                };
+                if entry.must_reset() {
+                    must_reset += 1;
+                }
+                sink.write(entry).await?;
+            }
+        }
+        sink.flush().await?;
+
+        Ok(must_reset)
+    }
+}
@@ -0,0 +1,182 @@
+use crate::HaveIBeenPwned;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Error returned when a response body exceeds the configured maximum size.
+///
+/// Set limits with [`HaveIBeenPwned::with_max_response_size`] and
+/// [`HaveIBeenPwned::with_endpoint_max_response_size`]. Returned as
+/// [`crate::HibpError::ResponseTooLarge`]:
+///
+/// ```no_run
+/// # use hibp_rs::{HaveIBeenPwned, HibpError};
+/// # async fn example() {
+/// let hibp = HaveIBeenPwned::new("your_api_key").with_max_response_size(1024 * 1024);
+/// if let Err(e) = hibp.get_all_breaches().await {
+///     if let HibpError::ResponseTooLarge(too_large) = e {
+///         println!("{} sent more than {} bytes", too_large.endpoint, too_large.limit);
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseTooLarge {
+    /// Endpoint the response came from, e.g. `breaches`.
+    pub endpoint: String,
+    /// The exceeded limit, in bytes.
+    pub limit: usize,
+}
+
+impl Display for ResponseTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Response from {} exceeds the limit of {} bytes",
+            self.endpoint, self.limit
+        )
+    }
+}
+
+impl std::error::Error for ResponseTooLarge {}
+
+/// Maximum response sizes of a client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ResponseLimits {
+    default: Option<usize>,
+    per_endpoint: HashMap<String, usize>,
+}
+
+impl ResponseLimits {
+    /// Returns the maximum response size of an endpoint, if any.
+    pub(crate) fn limit_for(&self, endpoint: &str) -> Option<usize> {
+        self.per_endpoint.get(endpoint).copied().or(self.default)
+    }
+}
+
+/// Why a response body could not be read.
+pub(crate) enum BodyError {
+    /// The body exceeds the limit.
+    TooLarge,
+    /// The connection failed while reading.
+    Network(reqwest::Error),
+}
+
+/// Receives the body of a successful response chunk by chunk as it arrives.
+pub(crate) trait BodySink {
+    /// Consumes the next chunk of the body. Chunks can end anywhere, even inside a line.
+    fn write(&mut self, chunk: &[u8]);
+
+    /// Discards everything written so far, before the request is sent again.
+    fn reset(&mut self);
+}
+
+impl BodySink for Vec<u8> {
+    fn write(&mut self, chunk: &[u8]) {
+        self.extend_from_slice(chunk);
+    }
+
+    fn reset(&mut self) {
+        self.clear();
+    }
+}
+
+/// Reads a response body into a sink, stopping as soon as it exceeds the limit.
+///
+/// Returns the number of bytes read.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn read_chunks<S: BodySink + ?Sized>(
+    mut resp: reqwest::Response,
+    limit: Option<usize>,
+    sink: &mut S,
+) -> Result<usize, BodyError> {
+    if let Some(limit) = limit
+        && resp.content_length().is_some_and(|len| len > limit as u64)
+    {
+        return Err(BodyError::TooLarge);
+    }
+
+    let mut read = 0;
+    while let Some(chunk) = resp.chunk().await.map_err(BodyError::Network)? {
+        read += chunk.len();
+        if limit.is_some_and(|limit| read > limit) {
+            return Err(BodyError::TooLarge);
+        }
+        sink.write(&chunk);
+    }
+    Ok(read)
+}
+
+/// Reads a response body into a sink, rejecting it if it exceeds the limit.
+///
+/// The browser fetch API buffers the whole body, so only the declared length can be
+/// checked before reading, and the sink receives the body as a single chunk.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn read_chunks<S: BodySink + ?Sized>(
+    resp: reqwest::Response,
+    limit: Option<usize>,
+    sink: &mut S,
+) -> Result<usize, BodyError> {
+    if let Some(limit) = limit
+        && resp.content_length().is_some_and(|len| len > limit as u64)
+    {
+        return Err(BodyError::TooLarge);
+    }
+    let body = resp.bytes().await.map_err(BodyError::Network)?;
+    if limit.is_some_and(|limit| body.len() > limit) {
+        return Err(BodyError::TooLarge);
+    }
+    sink.write(&body);
+    Ok(body.len())
+}
+
+impl HaveIBeenPwned {
+    /// Sets the maximum size of any response body.
+    ///
+    /// Larger responses fail with [`ResponseTooLarge`] without being read completely,
+    /// protecting memory-constrained services from unexpectedly huge payloads.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Maximum body size in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_max_response_size(1024 * 1024);
+    /// ```
+    #[must_use]
+    pub fn with_max_response_size(mut self, bytes: usize) -> Self {
+        self.response_limits.default = Some(bytes);
+        self
+    }
+
+    /// Sets the maximum size of response bodies of one endpoint, overriding
+    /// [`HaveIBeenPwned::with_max_response_size`].
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - Endpoint name as used in [`crate::ClientStats::requests_by_endpoint`],
+    ///   e.g. `breaches`, `breachedaccount` or `range`.
+    /// * `bytes` - Maximum body size in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// let hibp = HaveIBeenPwned::new("your_api_key")
+    ///     .with_max_response_size(64 * 1024)
+    ///     .with_endpoint_max_response_size("breaches", 8 * 1024 * 1024);
+    /// ```
+    #[must_use]
+    pub fn with_endpoint_max_response_size<S: Into<String>>(
+        mut self,
+        endpoint: S,
+        bytes: usize,
+    ) -> Self {
+        self.response_limits
+            .per_endpoint
+            .insert(endpoint.into(), bytes);
+        self
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Write};
+use std::sync::Arc;
+
+/// Built-in English messages, keyed by identifier.
+///
+/// Placeholders in braces (e.g. `{count}`) are substituted by [`MessageCatalog::format`].
+const ENGLISH: &[(&str, &str)] = &[
+    (
+        "password.not_breached",
+        "This password has not appeared in any known data breach.",
+    ),
+    (
+        "password.breached.low",
+        "This password has appeared in a data breach {count} times. Consider choosing a different one.",
+    ),
+    (
+        "password.breached.medium",
+        "This password has appeared in data breaches {count} times. Please choose a different one.",
+    ),
+    (
+        "password.breached.high",
+        "This password is one of the most commonly breached passwords ({count} times). Please choose a different one.",
+    ),
+    (
+        "report.scan.breached",
+        "Secret matched by '{pattern}' appears {count} times in the Pwned Passwords corpus",
+    ),
+    (
+        "report.export.breached",
+        "Password for '{username}' on '{site}' appears {count} times in the Pwned Passwords corpus",
+    ),
+    (
+        "report.nt_hash.breached",
+        "Password of account '{account}' appears {count} times in the Pwned Passwords corpus",
+    ),
+];
+
+/// A source of translated messages.
+///
+/// Implement this to plug in translations from gettext, Fluent, a database, etc.
+/// Keys without a translation fall back to the built-in English message.
+pub trait Translations: Send + Sync {
+    /// Returns the translated message template for the key, if any.
+    fn get(&self, key: &str) -> Option<&str>;
+}
+
+impl Translations for HashMap<String, String> {
+    fn get(&self, key: &str) -> Option<&str> {
+        HashMap::get(self, key).map(String::as_str)
+    }
+}
+
+impl Translations for HashMap<&'static str, &'static str> {
+    fn get(&self, key: &str) -> Option<&str> {
+        HashMap::get(self, key).copied()
+    }
+}
+
+/// Catalog of user-facing messages used by the advice and report modules.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::MessageCatalog;
+/// use std::collections::HashMap;
+///
+/// let mut german = HashMap::new();
+/// german.insert("password.breached.low", "Dieses Passwort wurde {count} Mal in Datenlecks gefunden.");
+///
+/// let catalog = MessageCatalog::with_translations(german);
+/// assert_eq!(
+///     catalog.format("password.breached.low", &[("count", &3)]),
+///     "Dieses Passwort wurde 3 Mal in Datenlecks gefunden."
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct MessageCatalog {
+    translations: Option<Arc<dyn Translations>>,
+}
+
+impl std::fmt::Debug for MessageCatalog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MessageCatalog")
+            .field("translated", &self.translations.is_some())
+            .finish()
+    }
+}
+
+impl MessageCatalog {
+    /// Creates a catalog with only the built-in English messages.
+    pub fn english() -> Self {
+        MessageCatalog { translations: None }
+    }
+
+    /// Creates a catalog that prefers the given translations over the English messages.
+    pub fn with_translations<T: Translations + 'static>(translations: T) -> Self {
+        MessageCatalog {
+            translations: Some(Arc::new(translations)),
+        }
+    }
+
+    /// Returns the message template for a key, falling back to English and then to the key itself.
+    pub fn template<'a>(&'a self, key: &'a str) -> &'a str {
+        self.translations
+            .as_ref()
+            .and_then(|t| t.get(key))
+            .or_else(|| {
+                ENGLISH
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, message)| *message)
+            })
+            .unwrap_or(key)
+    }
+
+    /// Renders the message for a key, substituting `{name}` placeholders with the given arguments.
+    ///
+    /// The template is scanned once, so placeholders inside substituted values are kept
+    /// as they are. Placeholders without an argument are left in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Identifier of the message.
+    /// * `args` - Placeholder names and their values.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut rest = self.template(key);
+        let mut message = String::with_capacity(rest.len());
+        while let Some(open) = rest.find('{') {
+            message.push_str(&rest[..open]);
+            let placeholder = &rest[open..];
+            let value = placeholder.find('}').and_then(|close| {
+                let name = &placeholder[1..close];
+                let (_, value) = args.iter().find(|(arg, _)| *arg == name)?;
+                Some((close, value))
+            });
+            match value {
+                Some((close, value)) => {
+                    let _ = write!(message, "{}", value);
+                    rest = &placeholder[close + 1..];
+                }
+                None => {
+                    message.push('{');
+                    rest = &placeholder[1..];
+                }
+            }
+        }
+        message.push_str(rest);
+        message
+    }
+}
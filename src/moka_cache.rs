@@ -0,0 +1,102 @@
+use crate::{CacheStore, CachedResponse};
+use moka::sync::Cache;
+use std::time::{Duration, Instant};
+
+/// A cached response with the TTL it was stored with.
+#[derive(Debug, Clone)]
+struct Entry {
+    response: CachedResponse,
+    ttl: Duration,
+}
+
+/// Expires each entry after the TTL it was stored with.
+struct EntryExpiry;
+
+impl moka::Expiry<String, Entry> for EntryExpiry {
+    fn expire_after_create(&self, _key: &String, entry: &Entry, _at: Instant) -> Option<Duration> {
+        Some(entry.ttl)
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        entry: &Entry,
+        _at: Instant,
+        _remaining: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(entry.ttl)
+    }
+}
+
+/// Keeps cached responses in a bounded, concurrent in-process cache built on
+/// [moka](https://docs.rs/moka), for high-QPS password-check services that can tolerate
+/// a cache per process.
+///
+/// Unlike [`crate::MemoryCacheStore`], which grows until entries expire and locks one map
+/// for every call, the store is sharded and evicts the least useful responses once their
+/// bodies reach a size limit. Expired responses are evicted in the background by moka's
+/// housekeeping rather than on the request path.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{HaveIBeenPwned, MokaCacheStore};
+/// use std::time::Duration;
+///
+/// // Up to 64 MB of range and lookup responses
+/// let store = MokaCacheStore::new(64 * 1024 * 1024);
+/// let hibp = HaveIBeenPwned::new("your_api_key").with_cache_store(store, Duration::from_secs(300));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MokaCacheStore {
+    cache: Cache<String, Entry>,
+}
+
+impl MokaCacheStore {
+    /// Creates a store holding up to `max_bytes` of keys and response bodies.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes` - The size limit of the store, above which responses are evicted.
+    pub fn new(max_bytes: u64) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(max_bytes)
+            .weigher(|key: &String, entry: &Entry| {
+                u32::try_from(key.len() + entry.response.body.len()).unwrap_or(u32::MAX)
+            })
+            .expire_after(EntryExpiry)
+            .build();
+        MokaCacheStore { cache }
+    }
+
+    /// Returns the number of cached responses, which may lag behind recent changes.
+    pub fn entry_count(&self) -> u64 {
+        self.cache.entry_count()
+    }
+}
+
+impl CacheStore for MokaCacheStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.cache.get(key).map(|entry| entry.response)
+    }
+
+    fn put(&self, key: &str, response: CachedResponse, ttl: Duration) {
+        self.cache.insert(key.to_string(), Entry { response, ttl });
+    }
+
+    fn invalidate(&self, key: &str) {
+        self.cache.invalidate(key);
+    }
+
+    fn invalidate_matching(&self, matches: &dyn Fn(&str) -> bool) {
+        for (key, _) in &self.cache {
+            if matches(&key) {
+                self.cache.invalidate(key.as_str());
+            }
+        }
+    }
+
+    fn clear(&self) {
+        self.cache.invalidate_all();
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{Breach, HaveIBeenPwned, HibpError, ResultSink};
+use futures_core::Stream;
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+/// Watches the breach catalogue and yields breaches as HIBP adds them.
+///
+/// Each poll downloads the breach list, which is revalidated with its ETag so unchanged
+/// lists cost next to nothing, and yields the breaches not seen before. The first poll
+/// only records the current catalogue, unless known breaches were set with
+/// [`BreachMonitor::with_known_breaches`], e.g. from a previous run.
+///
+/// Failed polls are retried with exponential backoff; the client's statistics still count
+/// them. Errors that retrying can't fix, see [`HibpError::is_permanent`], are yielded and
+/// end the stream.
+///
+/// Requires the `monitor` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures_util::StreamExt;
+/// use hibp_rs::{BreachMonitor, HaveIBeenPwned};
+/// use std::time::Duration;
+///
+/// # async fn example() {
+/// let hibp = HaveIBeenPwned::new("your_api_key");
+/// let monitor = BreachMonitor::new(hibp).with_poll_interval(Duration::from_secs(15 * 60));
+/// let mut breaches = std::pin::pin!(monitor.into_stream());
+/// while let Some(breach) = breaches.next().await {
+///     match breach {
+///         Ok(breach) => println!("New breach: {} ({} accounts)", breach.title, breach.pwn_count),
+///         Err(e) => eprintln!("Stopped monitoring: {}", e),
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BreachMonitor {
+    client: HaveIBeenPwned,
+    poll_interval: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    /// Names of the breaches seen so far, `None` before the first poll
+    known: Option<HashSet<String>>,
+}
+
+impl BreachMonitor {
+    /// Default time between polls.
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+    /// Creates a monitor polling every hour, backing off from 30 seconds up to an hour
+    /// after failed polls.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to poll with. Its rate limiter, retry policy and caches apply.
+    pub fn new(client: HaveIBeenPwned) -> Self {
+        BreachMonitor {
+            client,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            initial_backoff: Duration::from_secs(30),
+            max_backoff: Duration::from_secs(60 * 60),
+            known: None,
+        }
+    }
+
+    /// Sets the time between polls.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - How long to wait after a successful poll.
+    #[must_use]
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Sets how long to wait after failed polls.
+    ///
+    /// The wait doubles with each consecutive failure, up to `max`, and resets after a
+    /// successful poll.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - Wait after the first failure.
+    /// * `max` - Longest wait.
+    #[must_use]
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Treats the given breaches as already seen, so the first poll yields every other
+    /// breach in the catalogue.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - Names of the breaches seen before, as in [`Breach::name`].
+    #[must_use]
+    pub fn with_known_breaches<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.known = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Downloads the breach list once and returns the breaches not seen before, oldest
+    /// first.
+    ///
+    /// The first poll returns nothing unless known breaches were set.
+    pub async fn poll(&mut self) -> Result<Vec<Breach>, HibpError> {
+        let mut breaches = self.client.get_all_breaches().await?;
+        let Some(known) = &mut self.known else {
+            self.known = Some(breaches.into_iter().map(|b| b.name).collect());
+            return Ok(Vec::new());
+        };
+        breaches.retain(|b| !known.contains(&b.name));
+        breaches.sort_by_key(|b| b.added_date);
+        known.extend(breaches.iter().map(|b| b.name.clone()));
+        Ok(breaches)
+    }
+
+    /// Downloads the breach list once and writes the breaches not seen before to a sink,
+    /// oldest first.
+    ///
+    /// Like [`BreachMonitor::poll`]. The sink is flushed but not closed. Returns the number
+    /// of new breaches.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - Destination of the new breaches.
+    pub async fn poll_into<S: ResultSink<Breach>>(
+        &mut self,
+        sink: &mut S,
+    ) -> Result<usize, HibpError> {
+        let breaches = self.poll().await?;
+        let count = breaches.len();
+        for breach in breaches {
+            sink.write(breach).await?;
+        }
+        sink.flush().await?;
+        Ok(count)
+    }
+
+    /// Polls until a permanent error, yielding each new breach once.
+    ///
+    /// The first poll happens right away, then every poll interval. Failed polls are
+    /// retried with backoff, unless the error is permanent, see
+    /// [`HibpError::is_permanent`]: then it is yielded and the stream ends.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Breach, HibpError>> {
+        let state = (Some(self), VecDeque::new(), true);
+        futures_util::stream::unfold(state, |(monitor, mut pending, mut first)| async move {
+            let mut monitor = monitor?;
+            let mut failures = 0;
+            while pending.is_empty() {
+                if !first {
+                    let wait = match failures {
+                        0 => monitor.poll_interval,
+                        n => monitor.backoff(n),
+                    };
+                    crate::runtime::sleep(wait).await;
+                }
+                first = false;
+                match monitor.poll().await {
+                    Ok(breaches) => {
+                        failures = 0;
+                        pending.extend(breaches);
+                    }
+                    Err(e) if e.is_permanent() => return Some((Err(e), (None, pending, first))),
+                    Err(_) => failures += 1,
+                }
+            }
+            let breach = pending.pop_front()?;
+            Some((Ok(breach), (Some(monitor), pending, first)))
+        })
+    }
+
+    /// Wait after `failures` consecutive failed polls.
+    fn backoff(&self, failures: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{Event, HibpError, Notification, Notifier};
+use rumqttc::{AsyncClient, MqttOptions, Outgoing, QoS};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Publishes events to an MQTT broker.
+///
+/// Events are published with QoS 1 to `{topic_prefix}/{action}`, e.g.
+/// `hibp/breach-alert`, with the ECS document from [`Event::to_ecs_json`] as payload, so
+/// existing MQTT automation can react to "new breach affects watched account" events.
+///
+/// The connection is driven by a background tokio task that reconnects after failures,
+/// so the sink must be created inside a tokio runtime. Dropping the last clone of the sink
+/// sends the queued events, disconnects and ends the task.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{DedupNotifier, MqttSink};
+///
+/// # async fn example() {
+/// let notifier = DedupNotifier::new(MqttSink::new("mqtt.home.lan", 1883, "hibp-monitor", "hibp"));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MqttSink {
+    client: AsyncClient,
+    topic_prefix: String,
+    /// Kept only to end the event loop task when the last clone is dropped.
+    _event_loop: Arc<EventLoopTask>,
+}
+
+/// Ends the event loop task once the last clone of a sink is dropped.
+#[derive(Debug)]
+struct EventLoopTask {
+    client: AsyncClient,
+    closed: Arc<AtomicBool>,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for EventLoopTask {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+        // Queued behind the pending publishes, so they're sent before the task ends
+        if self.client.try_disconnect().is_err() {
+            self.handle.abort();
+        }
+    }
+}
+
+impl MqttSink {
+    /// Creates a sink connecting to the given broker without authentication.
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - Host name of the broker.
+    /// * `port` - Port of the broker, usually 1883.
+    /// * `client_id` - MQTT client identifier.
+    /// * `topic_prefix` - Prefix of the topics events are published to.
+    pub fn new(host: &str, port: u16, client_id: &str, topic_prefix: &str) -> Self {
+        let mut options = MqttOptions::new(client_id, host, port);
+        options.set_keep_alive(Duration::from_secs(30));
+        Self::with_options(options, topic_prefix)
+    }
+
+    /// Creates a sink from full connection options, e.g. with credentials.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Connection options.
+    /// * `topic_prefix` - Prefix of the topics events are published to.
+    pub fn with_options(options: MqttOptions, topic_prefix: &str) -> Self {
+        let (client, mut event_loop) = AsyncClient::new(options, 64);
+        let closed = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&closed);
+        let handle = tokio::spawn(async move {
+            loop {
+                match event_loop.poll().await {
+                    Ok(rumqttc::Event::Outgoing(Outgoing::Disconnect)) => break,
+                    Ok(_) => {}
+                    // The disconnect can't be sent without a connection
+                    Err(_) if stopped.load(Ordering::Relaxed) => break,
+                    Err(_) => {
+                        // Polling again reconnects; back off so an unreachable broker isn't hammered
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    }
+                }
+            }
+        });
+
+        MqttSink {
+            client: client.clone(),
+            topic_prefix: topic_prefix.trim_end_matches('/').to_string(),
+            _event_loop: Arc::new(EventLoopTask {
+                client,
+                closed,
+                handle,
+            }),
+        }
+    }
+
+    /// Queues an event for publishing.
+    ///
+    /// Waits only if the outgoing queue is full, not for the broker to acknowledge.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to publish.
+    pub async fn send(&self, event: &Event) -> Result<(), HibpError> {
+        let payload = serde_json::to_vec(&event.to_ecs_json())?;
+        self.client
+            .publish(self.topic(event), QoS::AtLeastOnce, false, payload)
+            .await
+            .map_err(HibpError::other)?;
+        Ok(())
+    }
+
+    /// Returns the topic an event is published to.
+    pub(crate) fn topic(&self, event: &Event) -> String {
+        format!("{}/{}", self.topic_prefix, event.action)
+    }
+}
+
+impl Notifier for MqttSink {
+    async fn notify(&self, notification: &Notification) -> Result<(), HibpError> {
+        for event in notification.events() {
+            self.send(&event).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+use crate::runtime::Instant;
+use crate::{Breach, DataClass, Event, HibpError};
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How urgent an alert is.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    /// For information only.
+    Info,
+    /// The breach exposed no credentials.
+    Low,
+    /// The breach exposed passwords.
+    Medium,
+    /// Credentials were captured by an info-stealer.
+    High,
+    /// Needs immediate action, e.g. a stealer-log hit for a privileged account.
+    Critical,
+}
+
+/// A breach found for a watched account.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Alert {
+    /// The affected account.
+    pub account: String,
+    /// Name of the breach, e.g. `Adobe`.
+    pub breach: String,
+    /// Title of the breach.
+    pub title: String,
+    /// Date the breach was added to HIBP.
+    pub added_date: String,
+    /// Number of accounts affected by the breach.
+    pub pwn_count: u64,
+    /// Data classes compromised in the breach.
+    pub data_classes: Vec<String>,
+    /// How urgent the alert is.
+    pub severity: AlertSeverity,
+}
+
+impl Alert {
+    /// Creates an alert for a breach of an account.
+    ///
+    /// The severity is [`AlertSeverity::High`] for stealer logs, [`AlertSeverity::Medium`] for
+    /// breaches exposing passwords and [`AlertSeverity::Low`] otherwise. Use
+    /// [`Alert::with_severity`] to escalate, e.g. for privileged accounts.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The affected account.
+    /// * `breach` - The breach the account appears in.
+    pub fn from_breach(account: &str, breach: &Breach) -> Self {
+        Alert {
+            account: account.to_string(),
+            breach: breach.name.clone(),
+            title: breach.title.clone(),
+            added_date: crate::breach::format_timestamp(&breach.added_date),
+            pwn_count: breach.pwn_count,
+            data_classes: breach.data_classes.clone(),
+            severity: if breach.is_stealer_log {
+                AlertSeverity::High
+            } else if breach.exposes(DataClass::Passwords) {
+                AlertSeverity::Medium
+            } else {
+                AlertSeverity::Low
+            },
+        }
+    }
+
+    /// Sets the severity of the alert.
+    #[must_use]
+    pub fn with_severity(mut self, severity: AlertSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Converts the alert into a `breach-alert` event.
+    pub fn to_event(&self) -> Event {
+        Event::new(
+            "breach-alert",
+            self.severity,
+            format!("{} appears in the {} breach", self.account, self.title),
+        )
+        .with_account(self.account.clone())
+        .with_breach(self.breach.clone())
+    }
+}
+
+/// A message delivered to a [`Notifier`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Notification {
+    /// A single alert.
+    Alert(Alert),
+    /// Alerts rolled up into one message because too many arrived at once.
+    Digest {
+        /// The rolled-up alerts.
+        alerts: Vec<Alert>,
+    },
+}
+
+impl Notification {
+    /// Returns the alerts of the notification.
+    pub fn alerts(&self) -> &[Alert] {
+        match self {
+            Notification::Alert(alert) => std::slice::from_ref(alert),
+            Notification::Digest { alerts } => alerts,
+        }
+    }
+
+    /// Returns the highest severity of the notification's alerts.
+    pub fn severity(&self) -> AlertSeverity {
+        self.alerts()
+            .iter()
+            .map(|a| a.severity)
+            .max()
+            .unwrap_or(AlertSeverity::Info)
+    }
+
+    /// Returns a one-line summary suitable as a message title.
+    pub fn summary(&self) -> String {
+        match self {
+            Notification::Alert(alert) => {
+                format!("{} appears in the {} breach", alert.account, alert.title)
+            }
+            Notification::Digest { alerts } => {
+                format!("{} watched accounts appear in breaches", alerts.len())
+            }
+        }
+    }
+}
+
+/// A destination for breach notifications, e.g. chat, email or an incident manager.
+pub trait Notifier {
+    /// Delivers a notification.
+    fn notify(&self, notification: &Notification) -> impl Future<Output = Result<(), HibpError>>;
+}
+
+/// Window and counter of the alert budget.
+#[derive(Debug)]
+struct ThrottleWindow {
+    started: Instant,
+    sent: usize,
+}
+
+/// Wraps a [`Notifier`], suppressing duplicate alerts and rolling floods into digests.
+///
+/// Every (account, breach) pair is alerted at most once. At most `max_alerts` individual
+/// alerts are sent per window; anything beyond that is delivered as a single
+/// [`Notification::Digest`], so a mega-breach matching thousands of watched accounts
+/// produces one message instead of thousands.
+///
+/// # Example
+///
+/// ```no_run
+/// # use hibp_rs::{Alert, DedupNotifier, HaveIBeenPwned, HibpError, Notification, Notifier};
+/// # struct Stdout;
+/// # impl Notifier for Stdout {
+/// #     async fn notify(&self, n: &Notification) -> Result<(), HibpError> {
+/// #         println!("{:?}", n);
+/// #         Ok(())
+/// #     }
+/// # }
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let hibp = HaveIBeenPwned::new("your_api_key");
+/// let notifier = DedupNotifier::new(Stdout)
+///     .with_max_alerts(20, std::time::Duration::from_secs(3600));
+///
+/// let breaches = hibp.get_breaches_for_account("test@example.com").await?;
+/// let alerts = breaches
+///     .iter()
+///     .map(|b| Alert::from_breach("test@example.com", b))
+///     .collect();
+/// notifier.notify_all(alerts).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DedupNotifier<N> {
+    inner: N,
+    max_alerts: usize,
+    window: Duration,
+    seen: Mutex<HashSet<(String, String)>>,
+    throttle: Mutex<ThrottleWindow>,
+}
+
+impl<N: Notifier> DedupNotifier<N> {
+    /// Wraps a notifier, allowing 10 individual alerts per minute.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The notifier to deliver to.
+    pub fn new(inner: N) -> Self {
+        DedupNotifier {
+            inner,
+            max_alerts: 10,
+            window: Duration::from_secs(60),
+            seen: Mutex::new(HashSet::new()),
+            throttle: Mutex::new(ThrottleWindow {
+                started: Instant::now(),
+                sent: 0,
+            }),
+        }
+    }
+
+    /// Sets how many individual alerts may be sent per window before alerts are rolled into digests.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_alerts` - Individual alerts allowed per window.
+    /// * `window` - Length of the window.
+    #[must_use]
+    pub fn with_max_alerts(mut self, max_alerts: usize, window: Duration) -> Self {
+        self.max_alerts = max_alerts;
+        self.window = window;
+        self
+    }
+
+    /// Marks (account, breach) pairs as already alerted, e.g. from a previous run.
+    ///
+    /// # Arguments
+    ///
+    /// * `pairs` - Account and breach name pairs to suppress.
+    #[must_use]
+    pub fn with_seen<I: IntoIterator<Item = (String, String)>>(self, pairs: I) -> Self {
+        self.seen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(pairs);
+        self
+    }
+
+    /// Returns the wrapped notifier.
+    pub fn inner(&self) -> &N {
+        &self.inner
+    }
+
+    /// Delivers alerts, dropping duplicates and rolling any over the budget into one digest.
+    ///
+    /// # Arguments
+    ///
+    /// * `alerts` - Alerts to deliver.
+    pub async fn notify_all(&self, alerts: Vec<Alert>) -> Result<(), HibpError> {
+        let (individual, digest) = self.plan(alerts);
+
+        for alert in individual {
+            self.inner.notify(&Notification::Alert(alert)).await?;
+        }
+        if !digest.is_empty() {
+            self.inner
+                .notify(&Notification::Digest { alerts: digest })
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Splits new alerts into those sent individually and those rolled into a digest.
+    fn plan(&self, alerts: Vec<Alert>) -> (Vec<Alert>, Vec<Alert>) {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        let mut throttle = self.throttle.lock().unwrap_or_else(|e| e.into_inner());
+        if throttle.started.elapsed() >= self.window {
+            throttle.started = Instant::now();
+            throttle.sent = 0;
+        }
+
+        let mut individual = Vec::new();
+        let mut digest = Vec::new();
+        for alert in alerts {
+            if !seen.insert((alert.account.clone(), alert.breach.clone())) {
+                continue;
+            }
+            if throttle.sent < self.max_alerts {
+                throttle.sent += 1;
+                individual.push(alert);
+            } else {
+                digest.push(alert);
+            }
+        }
+        (individual, digest)
+    }
+}
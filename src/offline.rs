@@ -0,0 +1,234 @@
+use crate::binary_corpus;
+#[cfg(feature = "mmap")]
+use crate::binary_corpus::BinaryCorpus;
+use crate::limits::BodySink;
+use crate::password::RangeParser;
+use crate::{HibpError, PwnedPassword, hash};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "mmap")]
+use std::sync::Arc;
+
+/// How a downloaded corpus is laid out on disk.
+#[derive(Debug, Clone)]
+enum Layout {
+    /// One file of `HASH:COUNT` lines sorted by hash.
+    File(PathBuf),
+    /// A directory of `PREFIX.txt` range files with `SUFFIX:COUNT` lines.
+    Dir(PathBuf),
+    /// A memory-mapped file of fixed-width records, indexed by prefix.
+    #[cfg(feature = "mmap")]
+    Binary(Arc<BinaryCorpus>),
+}
+
+/// Answers Pwned Passwords lookups from a downloaded corpus, without network requests.
+///
+/// Opens any layout written by [`crate::CorpusDownloader`]: a single file of sorted
+/// `HASH:COUNT` lines, which is binary searched on disk, a directory of `PREFIX.txt`
+/// range files, or a binary corpus, which is memory-mapped and binary searched by
+/// prefix (with the `mmap` feature, part of `full`). Files from the official downloader work as well. Lookups read only the
+/// needed part of the corpus, so even the full corpus is never loaded into memory.
+///
+/// The binary corpus is the fastest and about half the size of the text file, so
+/// prefer it for high-throughput lookups.
+///
+/// A corpus holds either SHA-1 or NTLM hashes, depending on how it was downloaded; look
+/// it up with the matching methods. Hashes missing from the corpus have a count of 0.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::OfflinePasswordStore;
+///
+/// # fn example() -> Result<(), hibp_rs::HibpError> {
+/// let store = OfflinePasswordStore::open("pwnedpasswords.txt")?;
+/// let count = store.check_password("password123")?;
+/// println!("This password was found {} times in data breaches", count);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OfflinePasswordStore {
+    layout: Layout,
+}
+
+impl OfflinePasswordStore {
+    /// Opens a corpus file, binary corpus or directory of range files.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The corpus file or directory.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, HibpError> {
+        let path = path.as_ref();
+        let layout = if std::fs::metadata(path)?.is_dir() {
+            Layout::Dir(path.to_path_buf())
+        } else if binary_corpus::detect(path)? {
+            open_binary(path)?
+        } else {
+            Layout::File(path.to_path_buf())
+        };
+        Ok(OfflinePasswordStore { layout })
+    }
+
+    /// Returns how often a password appears in a SHA-1 corpus.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to check
+    pub fn check_password(&self, password: &str) -> Result<u64, HibpError> {
+        self.check_hash(&hash::sha1_hex(password))
+    }
+
+    /// Returns how often a password appears in an NTLM corpus.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to check
+    pub fn check_password_ntlm(&self, password: &str) -> Result<u64, HibpError> {
+        self.check_hash(&hash::ntlm_hex(password))
+    }
+
+    /// Returns how often a hex SHA-1 or NTLM hash appears in the corpus.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The full hex digest, in any case
+    pub fn check_hash(&self, hash: &str) -> Result<u64, HibpError> {
+        let (prefix, suffix) = hash::split_hash(hash).ok_or_else(|| {
+            HibpError::InvalidInput("Hash must be a hex SHA-1 or NTLM digest".into())
+        })?;
+        #[cfg(feature = "mmap")]
+        if let Layout::Binary(corpus) = &self.layout {
+            return corpus.count(&prefix, &suffix);
+        }
+        Ok(self
+            .range(&prefix, Some(&suffix))?
+            .into_iter()
+            .find(|p| p.hash_suffix == suffix)
+            .map_or(0, |p| p.count))
+    }
+
+    /// Returns all hashes of a range, like [`crate::HaveIBeenPwned::search_password_range`].
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_prefix` - First 5 characters of a password hash
+    pub fn search_range(&self, hash_prefix: &str) -> Result<Vec<PwnedPassword>, HibpError> {
+        if hash_prefix.len() != hash::PREFIX_LEN
+            || !hash_prefix.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(HibpError::InvalidInput(
+                "Hash prefix must be exactly 5 hex characters".into(),
+            ));
+        }
+        self.range(&hash_prefix.to_ascii_uppercase(), None)
+    }
+
+    /// Reads the entries of an uppercase range prefix, stopping after `until` in a file.
+    fn range(&self, prefix: &str, until: Option<&str>) -> Result<Vec<PwnedPassword>, HibpError> {
+        match &self.layout {
+            #[cfg(feature = "mmap")]
+            Layout::Binary(corpus) => corpus.range(prefix),
+            Layout::Dir(dir) => {
+                let body = std::fs::read(dir.join(format!("{}.txt", prefix)))?;
+                let mut parser = RangeParser::new(parse_line);
+                parser.write(&body);
+                Ok(parser.finish())
+            }
+            Layout::File(path) => {
+                let mut reader = BufReader::new(File::open(path)?);
+                seek_to_first_at_least(&mut reader, prefix.as_bytes())?;
+                let mut entries = Vec::new();
+                let mut line = Vec::new();
+                while read_line(&mut reader, &mut line)? {
+                    let Some(suffix_line) = line.strip_prefix(prefix.as_bytes()) else {
+                        break;
+                    };
+                    let Some(entry) = std::str::from_utf8(suffix_line).ok().and_then(parse_line)
+                    else {
+                        continue;
+                    };
+                    let done = until.is_some_and(|until| entry.hash_suffix.as_str() >= until);
+                    entries.push(entry);
+                    if done {
+                        break;
+                    }
+                }
+                Ok(entries)
+            }
+        }
+    }
+}
+
+/// Maps a binary corpus.
+#[cfg(feature = "mmap")]
+fn open_binary(path: &Path) -> Result<Layout, HibpError> {
+    Ok(Layout::Binary(Arc::new(BinaryCorpus::open(path)?)))
+}
+
+/// Rejects a binary corpus, which can't be read without memory-mapping it.
+#[cfg(not(feature = "mmap"))]
+fn open_binary(path: &Path) -> Result<Layout, HibpError> {
+    Err(HibpError::InvalidInput(format!(
+        "Binary corpora need the `mmap` feature: {}",
+        path.display()
+    )))
+}
+
+/// Parses a `SUFFIX:COUNT` line.
+pub(crate) fn parse_line(line: &str) -> Option<PwnedPassword> {
+    let (suffix, count) = line.trim().split_once(':')?;
+    Some(PwnedPassword {
+        hash_suffix: suffix.to_ascii_uppercase(),
+        count: count.parse().ok()?,
+    })
+}
+
+/// Reads the next line without its line ending, returning `false` at the end of the file.
+fn read_line<R: BufRead>(reader: &mut R, line: &mut Vec<u8>) -> std::io::Result<bool> {
+    line.clear();
+    if reader.read_until(b'\n', line)? == 0 {
+        return Ok(false);
+    }
+    line.truncate(line.trim_ascii_end().len());
+    Ok(true)
+}
+
+/// Returns the start of the first line at or after `pos`, and that line.
+fn line_at<R: BufRead + Seek>(
+    reader: &mut R,
+    pos: u64,
+    line: &mut Vec<u8>,
+) -> std::io::Result<Option<u64>> {
+    let start = if pos == 0 {
+        reader.seek(SeekFrom::Start(0))?;
+        0
+    } else {
+        // The line containing `pos - 1` ends at or after `pos`; skip it
+        reader.seek(SeekFrom::Start(pos - 1))?;
+        pos - 1 + reader.skip_until(b'\n')? as u64
+    };
+    Ok(read_line(reader, line)?.then_some(start))
+}
+
+/// Positions the reader at the first line of a sorted file that is not less than `target`.
+///
+/// Lines are compared byte-wise, so hashes must be uppercase like in downloaded corpora.
+fn seek_to_first_at_least<R: BufRead + Seek>(reader: &mut R, target: &[u8]) -> std::io::Result<()> {
+    let mut lo = 0;
+    let mut hi = reader.seek(SeekFrom::End(0))?;
+    let mut line = Vec::new();
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        match line_at(reader, mid, &mut line)? {
+            Some(start) if line.as_slice() < target => lo = start + 1,
+            _ => hi = mid,
+        }
+    }
+    match line_at(reader, lo, &mut line)? {
+        Some(start) => reader.seek(SeekFrom::Start(start))?,
+        None => reader.seek(SeekFrom::End(0))?,
+    };
+    Ok(())
+}
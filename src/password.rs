@@ -1,5 +1,12 @@
-use crate::HaveIBeenPwned;
-use sha1::{Digest, Sha1};
+#[cfg(feature = "full")]
+use crate::ResultSink;
+use crate::hash::Sha1Digest;
+use crate::hashing::{Secret, secret, to_hex_upper};
+use crate::limits::BodySink;
+use crate::{HaveIBeenPwned, HibpError};
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, ReadBuf};
 
 /// Represents a password hash and its occurrence count in the HIBP database.
 #[derive(Debug, Clone)]
@@ -10,6 +17,162 @@ pub struct PwnedPassword {
     pub count: u64,
 }
 
+/// Outcome of a password check, with the context the decision was made in.
+///
+/// Serializes to JSON, so the full decision can be written to audit logs.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::PasswordVerdict;
+/// let verdict = PasswordVerdict::from_count(42, true, Some(100));
+/// assert!(verdict.compromised);
+/// assert_eq!(verdict.threshold_exceeded, Some(false));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct PasswordVerdict {
+    /// Whether the password appears in the Pwned Passwords corpus at all.
+    pub compromised: bool,
+    /// Number of times the password appears in the Pwned Passwords corpus.
+    pub count: u64,
+    /// Whether the range request was padded.
+    pub checked_with_padding: bool,
+    /// Whether the count is above the caller's threshold, or `None` without a threshold.
+    pub threshold_exceeded: Option<bool>,
+}
+
+impl PasswordVerdict {
+    /// Builds a verdict from a breach count.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Number of times the password appears in the Pwned Passwords corpus.
+    /// * `checked_with_padding` - Whether the range request was padded.
+    /// * `threshold` - Count the password may reach before it is considered too common.
+    pub fn from_count(count: u64, checked_with_padding: bool, threshold: Option<u64>) -> Self {
+        PasswordVerdict {
+            compromised: count > 0,
+            count,
+            checked_with_padding,
+            threshold_exceeded: threshold.map(|threshold| count > threshold),
+        }
+    }
+}
+
+/// Longest input [`HaveIBeenPwned::check_password_reader`] accepts, in bytes, including a
+/// trailing line ending.
+pub const MAX_READER_PASSWORD_LEN: usize = 4096;
+
+/// Result for one password of [`HaveIBeenPwned::check_passwords_into`].
+///
+/// Identifies the password by its position in the input rather than by the password
+/// itself, so results can be written anywhere without leaking plaintext.
+#[cfg(feature = "full")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct PasswordCheck {
+    /// Index of the password in the checked slice.
+    pub index: usize,
+    /// Number of times the password appears in the Pwned Passwords corpus.
+    pub count: u64,
+}
+
+/// A range entry stored without heap allocations, for processing millions of entries.
+///
+/// The hash suffix is packed into 18 bytes of nibbles and the count is capped at
+/// `u32::MAX`, so an entry takes 24 bytes instead of the 60+ bytes and one allocation of
+/// a [`PwnedPassword`]. Both SHA-1 (35 character) and NTLM (27 character) suffixes fit.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::CompactPwnedPassword;
+/// let entry = CompactPwnedPassword::parse("0018A45C4D1DEF81644B54AB7F969B88D65:10").unwrap();
+/// assert!(entry.matches("0018a45c4d1def81644b54ab7f969b88d65"));
+/// assert_eq!(entry.count(), 10);
+/// assert_eq!(std::mem::size_of::<CompactPwnedPassword>(), 24);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CompactPwnedPassword {
+    suffix: [u8; 18],
+    len: u8,
+    count: u32,
+}
+
+impl CompactPwnedPassword {
+    /// Parses a `SUFFIX:COUNT` line of a range response.
+    ///
+    /// Returns `None` if the line is malformed or the suffix is longer than 36 characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A line of a range response.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (suffix, count) = line.trim().split_once(':')?;
+        Some(CompactPwnedPassword {
+            suffix: pack_hex(suffix)?,
+            len: suffix.len() as u8,
+            count: count.parse::<u64>().ok()?.min(u32::MAX as u64) as u32,
+        })
+    }
+
+    /// Whether the entry has the given hash suffix, ignoring case.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_suffix` - The hash without its 5-character prefix.
+    pub fn matches(&self, hash_suffix: &str) -> bool {
+        hash_suffix.len() == self.len as usize && pack_hex(hash_suffix) == Some(self.suffix)
+    }
+
+    /// Returns the uppercase hex suffix of the hash.
+    pub fn hash_suffix(&self) -> String {
+        (0..self.len as usize)
+            .map(|i| {
+                let nibble = (self.suffix[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0xF;
+                char::from_digit(nibble as u32, 16)
+                    .unwrap_or('0')
+                    .to_ascii_uppercase()
+            })
+            .collect()
+    }
+
+    /// Returns how often the password appears in breaches, capped at `u32::MAX`.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+impl From<CompactPwnedPassword> for PwnedPassword {
+    fn from(entry: CompactPwnedPassword) -> Self {
+        PwnedPassword {
+            hash_suffix: entry.hash_suffix(),
+            count: entry.count as u64,
+        }
+    }
+}
+
+/// Packs up to 36 hex characters into nibbles, left-aligned.
+pub(crate) fn pack_hex(hex: &str) -> Option<[u8; 18]> {
+    if hex.len() > 36 {
+        return None;
+    }
+    let mut packed = [0u8; 18];
+    for (i, c) in hex.chars().enumerate() {
+        let nibble = c.to_digit(16)? as u8;
+        packed[i / 2] |= if i % 2 == 0 { nibble << 4 } else { nibble };
+    }
+    Some(packed)
+}
+
+/// Hash algorithm of a Pwned Passwords range query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RangeMode {
+    /// SHA-1 hashes (the default mode of the API).
+    Sha1,
+    /// NTLM hashes, as stored by Active Directory.
+    Ntlm,
+}
+
 impl HaveIBeenPwned {
     /// Searches for a password hash by its first 5 characters (prefix).
     ///
@@ -32,31 +195,9 @@ impl HaveIBeenPwned {
     pub async fn search_password_range(
         &self,
         hash_prefix: &str,
-    ) -> Result<Vec<PwnedPassword>, Box<dyn std::error::Error>> {
-        if hash_prefix.len() != 5 {
-            return Err("Hash prefix must be exactly 5 characters".into());
-        }
-
-        let url = format!("https://api.pwnedpasswords.com/range/{}", hash_prefix);
-        let headers = self.create_headers()?;
-        let resp = self.client.get(&url).headers(headers).send().await?;
-
-        if resp.status().is_success() {
-            let text = resp.text().await?;
-            let passwords: Vec<PwnedPassword> = text
-                .lines()
-                .map(|line| {
-                    let parts: Vec<&str> = line.split(':').collect();
-                    PwnedPassword {
-                        hash_suffix: parts[0].to_string(),
-                        count: parts[1].parse().unwrap_or(0),
-                    }
-                })
-                .collect();
-            Ok(passwords)
-        } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
-        }
+    ) -> Result<Vec<PwnedPassword>, HibpError> {
+        self.fetch_password_range(hash_prefix, RangeMode::Sha1, false)
+            .await
     }
 
     /// Searches for a password hash with padding for enhanced privacy.
@@ -82,33 +223,271 @@ impl HaveIBeenPwned {
     pub async fn search_password_range_padded(
         &self,
         hash_prefix: &str,
-    ) -> Result<Vec<PwnedPassword>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<PwnedPassword>, HibpError> {
+        self.fetch_password_range(hash_prefix, RangeMode::Sha1, true)
+            .await
+    }
+
+    /// Searches for an NTLM password hash by its first 5 characters (prefix).
+    ///
+    /// Returned suffixes are the remaining 27 characters of the NTLM hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_prefix` - First 5 characters of an NTLM password hash
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let results = hibp.search_password_range_ntlm("8846F").await.unwrap();
+    /// for pwd in results {
+    ///     println!("Hash suffix: {}, Count: {}", pwd.hash_suffix, pwd.count);
+    /// }
+    /// # }
+    /// ```
+    pub async fn search_password_range_ntlm(
+        &self,
+        hash_prefix: &str,
+    ) -> Result<Vec<PwnedPassword>, HibpError> {
+        self.fetch_password_range(hash_prefix, RangeMode::Ntlm, false)
+            .await
+    }
+
+    /// Searches for a password hash with padding, returning compact entries.
+    ///
+    /// Use this instead of [`HaveIBeenPwned::search_password_range_padded`] when processing
+    /// many ranges, e.g. in bulk scans, to avoid one heap allocation per entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_prefix` - First 5 characters of a SHA-1 password hash
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let results = hibp.search_password_range_compact("CBF2D").await.unwrap();
+    /// let count = results
+    ///     .iter()
+    ///     .find(|p| p.matches("A6D9B4C6D6E1E7E4F0F5F3A1B2C3D4E5F60"))
+    ///     .map_or(0, |p| p.count());
+    /// println!("Count: {}", count);
+    /// # }
+    /// ```
+    pub async fn search_password_range_compact(
+        &self,
+        hash_prefix: &str,
+    ) -> Result<Vec<CompactPwnedPassword>, HibpError> {
+        self.fetch_compact_range(hash_prefix, RangeMode::Sha1, true)
+            .await
+    }
+
+    /// Fetches and parses a range from the Pwned Passwords API.
+    async fn fetch_password_range(
+        &self,
+        hash_prefix: &str,
+        mode: RangeMode,
+        padded: bool,
+    ) -> Result<Vec<PwnedPassword>, HibpError> {
+        let key = format!("{:?}:{}:{}", mode, padded, hash_prefix);
+        self.in_flight
+            .ranges
+            .run(key, || async {
+                let mut parser = RangeParser::new(|line: &str| {
+                    let (suffix, count) = line.trim().split_once(':')?;
+                    Some(PwnedPassword {
+                        hash_suffix: suffix.to_string(),
+                        count: count.parse().unwrap_or(0),
+                    })
+                });
+                self.fetch_range(hash_prefix, mode, padded, &mut parser)
+                    .await?;
+                Ok(parser.finish())
+            })
+            .await
+    }
+
+    /// Fetches a range from the Pwned Passwords API, parsing it into compact entries.
+    pub(crate) async fn fetch_compact_range(
+        &self,
+        hash_prefix: &str,
+        mode: RangeMode,
+        padded: bool,
+    ) -> Result<Vec<CompactPwnedPassword>, HibpError> {
+        let key = format!("{:?}:{}:{}", mode, padded, hash_prefix);
+        self.in_flight
+            .compact_ranges
+            .run(key, || async {
+                let mut parser = RangeParser::new(CompactPwnedPassword::parse);
+                self.fetch_range(hash_prefix, mode, padded, &mut parser)
+                    .await?;
+                Ok(parser.finish())
+            })
+            .await
+    }
+
+    /// Fetches a range from the Pwned Passwords API, feeding its body to a sink as it
+    /// arrives instead of buffering it first.
+    pub(crate) async fn fetch_range<S: BodySink>(
+        &self,
+        hash_prefix: &str,
+        mode: RangeMode,
+        padded: bool,
+        sink: &mut S,
+    ) -> Result<(), HibpError> {
+        let (url, headers) = self.range_request(hash_prefix, mode, padded)?;
+        #[cfg(feature = "full")]
+        if let Some(body) = self.cached_range(&url, &headers, padded).await {
+            sink.reset();
+            sink.write(&body);
+            return Ok(());
+        }
+        self.request_range(&url, headers, padded, sink).await
+    }
+
+    /// Returns the body of a cached range, refreshing it in the background if it is stale.
+    #[cfg(feature = "full")]
+    pub(crate) async fn cached_range(
+        &self,
+        url: &str,
+        headers: &reqwest::header::HeaderMap,
+        padded: bool,
+    ) -> Option<Vec<u8>> {
+        let key = crate::cache::range_key(url, padded);
+        let hit = self.response_cache.as_ref()?.get_range(&key).await?;
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(claim) = hit.refresh {
+            let (client, url, headers) = (self.clone(), url.to_string(), headers.clone());
+            claim.spawn(async move {
+                let _ = client
+                    .fetch_cached_range(&key, &url, headers, &mut Vec::new())
+                    .await;
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = headers;
+        Some(hit.value)
+    }
+
+    /// Fetches a range from the API without looking at the response cache, storing it
+    /// there if the client has one.
+    pub(crate) async fn request_range<S: BodySink>(
+        &self,
+        url: &str,
+        headers: reqwest::header::HeaderMap,
+        padded: bool,
+        sink: &mut S,
+    ) -> Result<(), HibpError> {
+        #[cfg(feature = "full")]
+        if self.response_cache.is_some() {
+            let key = crate::cache::range_key(url, padded);
+            return self.fetch_cached_range(&key, url, headers, sink).await;
+        }
+        #[cfg(not(feature = "full"))]
+        let _ = padded;
+
+        #[cfg(feature = "full")]
+        self.wait_for_passwords_rate_limit("range").await?;
+        let resp = self.send_get_into("range", url, headers, sink).await?;
+
+        if resp.status.is_success() {
+            Ok(())
+        } else {
+            Err(resp.error())
+        }
+    }
+
+    /// Fetches a range like [`HaveIBeenPwned::fetch_range`] and stores it in the response
+    /// cache under `key`.
+    #[cfg(feature = "full")]
+    async fn fetch_cached_range<S: BodySink>(
+        &self,
+        key: &str,
+        url: &str,
+        headers: reqwest::header::HeaderMap,
+        sink: &mut S,
+    ) -> Result<(), HibpError> {
+        self.wait_for_passwords_rate_limit("range").await?;
+        let mut copying = crate::cache::CopyingSink {
+            sink,
+            copy: Vec::new(),
+        };
+        let resp = self
+            .send_get_into("range", url, headers, &mut copying)
+            .await?;
+        if !resp.status.is_success() {
+            return Err(resp.error());
+        }
+        if let Some(cache) = &self.response_cache {
+            cache.insert_range(key, copying.copy).await;
+        }
+        Ok(())
+    }
+
+    /// Fetches a range unless it still has the given `ETag`, for corpus updates.
+    ///
+    /// Returns `None` if the API answered `304 Not Modified`, and otherwise the new
+    /// `ETag`, if any, with the body written to `sink`.
+    #[cfg(all(feature = "full", not(target_arch = "wasm32")))]
+    pub(crate) async fn revalidate_range<S: BodySink>(
+        &self,
+        hash_prefix: &str,
+        mode: RangeMode,
+        etag: Option<&str>,
+        sink: &mut S,
+    ) -> Result<Option<Option<String>>, HibpError> {
+        let (url, mut headers) = self.range_request(hash_prefix, mode, false)?;
+        if let Some(etag) = etag
+            && let Ok(value) = reqwest::header::HeaderValue::from_str(etag)
+        {
+            headers.insert(reqwest::header::IF_NONE_MATCH, value);
+        }
+
+        self.wait_for_passwords_rate_limit("range").await?;
+        let resp = self.send_get_into("range", &url, headers, sink).await?;
+
+        if resp.status == reqwest::StatusCode::NOT_MODIFIED {
+            Ok(None)
+        } else if resp.status.is_success() {
+            Ok(Some(resp.etag))
+        } else {
+            Err(resp.error())
+        }
+    }
+
+    /// Returns the URL and headers of a range request.
+    pub(crate) fn range_request(
+        &self,
+        hash_prefix: &str,
+        mode: RangeMode,
+        padded: bool,
+    ) -> Result<(String, reqwest::header::HeaderMap), HibpError> {
         if hash_prefix.len() != 5 {
-            return Err("Hash prefix must be exactly 5 characters".into());
+            return Err(HibpError::InvalidInput(
+                "Hash prefix must be exactly 5 characters".into(),
+            ));
         }
 
-        let url = format!("https://api.pwnedpasswords.com/range/{}", hash_prefix);
+        let url = match mode {
+            RangeMode::Sha1 => format!("{}/range/{}", self.passwords_base_url, hash_prefix),
+            RangeMode::Ntlm => format!(
+                "{}/range/{}?mode=ntlm",
+                self.passwords_base_url, hash_prefix
+            ),
+        };
         let mut headers = self.create_headers()?;
-        headers.insert("Add-Padding", "true".parse()?);
-
-        let resp = self.client.get(&url).headers(headers).send().await?;
-
-        if resp.status().is_success() {
-            let text = resp.text().await?;
-            let passwords: Vec<PwnedPassword> = text
-                .lines()
-                .map(|line| {
-                    let parts: Vec<&str> = line.split(':').collect();
-                    PwnedPassword {
-                        hash_suffix: parts[0].to_string(),
-                        count: parts[1].parse().unwrap_or(0),
-                    }
-                })
-                .collect();
-            Ok(passwords)
-        } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+        if padded {
+            headers.insert(
+                "Add-Padding",
+                reqwest::header::HeaderValue::from_static("true"),
+            );
         }
+        Ok((url, headers))
     }
 
     /// Check if a password has been exposed in data breaches.
@@ -127,11 +506,8 @@ impl HaveIBeenPwned {
     /// println!("This password was found {} times in data breaches", count);
     /// # }
     /// ```
-    pub async fn check_password(&self, password: &str) -> Result<u64, Box<dyn std::error::Error>> {
-        let mut hasher = Sha1::new();
-        hasher.update(password.as_bytes());
-        let hash = hasher.finalize();
-        let hash_str = format!("{:X}", hash);
+    pub async fn check_password(&self, password: &str) -> Result<u64, HibpError> {
+        let hash_str = self.sha1_hex(password);
 
         let prefix = &hash_str[..5];
         let suffix = &hash_str[5..];
@@ -161,14 +537,8 @@ impl HaveIBeenPwned {
     /// println!("This password was found {} times in data breaches", count);
     /// # }
     /// ```
-    pub async fn check_password_padded(
-        &self,
-        password: &str,
-    ) -> Result<u64, Box<dyn std::error::Error>> {
-        let mut hasher = Sha1::new();
-        hasher.update(password.as_bytes());
-        let hash = hasher.finalize();
-        let hash_str = format!("{:X}", hash);
+    pub async fn check_password_padded(&self, password: &str) -> Result<u64, HibpError> {
+        let hash_str = self.sha1_hex(password);
 
         let prefix = &hash_str[..5];
         let suffix = &hash_str[5..];
@@ -181,4 +551,460 @@ impl HaveIBeenPwned {
             .map(|p| p.count)
             .unwrap_or(0))
     }
+
+    /// Check a password and return a [`PasswordVerdict`] instead of a bare count, using
+    /// padding for privacy.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to check
+    /// * `threshold` - Count the password may reach before `threshold_exceeded` is set, or
+    ///   `None` to only report whether it is compromised
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let verdict = hibp.check_password_verdict("password123", Some(10)).await.unwrap();
+    /// if verdict.threshold_exceeded == Some(true) {
+    ///     println!("Rejected: found {} times in data breaches", verdict.count);
+    /// }
+    /// # }
+    /// ```
+    pub async fn check_password_verdict(
+        &self,
+        password: &str,
+        threshold: Option<u64>,
+    ) -> Result<PasswordVerdict, HibpError> {
+        let hash_str = self.sha1_hex(password);
+        let count = self.count_sha1_hash(&hash_str).await?;
+        Ok(PasswordVerdict::from_count(count, true, threshold))
+    }
+
+    /// Check if a password has been exposed in data breaches by its SHA-1 digest.
+    ///
+    /// Use this when only hashes of the passwords are stored, so the plaintext never has to
+    /// be handed to this crate. Fails with [`HibpError::InvalidInput`] unless the digest
+    /// is 40 hex characters or 20 raw bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The SHA-1 digest, as hex text (any case) or as a `[u8; 20]`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let count = hibp
+    ///     .check_password_hash("5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8")
+    ///     .await
+    ///     .unwrap();
+    /// println!("This password was found {} times in data breaches", count);
+    /// # }
+    /// ```
+    pub async fn check_password_hash<H: Sha1Digest + ?Sized>(
+        &self,
+        hash: &H,
+    ) -> Result<u64, HibpError> {
+        let hash_str = secret(valid_sha1_hex(hash)?);
+        self.count_hash(&hash_str, RangeMode::Sha1, false).await
+    }
+
+    /// Check if a password has been exposed in data breaches by its SHA-1 digest, using
+    /// padding for privacy.
+    ///
+    /// See [`HaveIBeenPwned::check_password_hash`].
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The SHA-1 digest, as hex text (any case) or as a `[u8; 20]`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let digest: [u8; 20] = [0x5B; 20];
+    /// let count = hibp.check_password_hash_padded(&digest).await.unwrap();
+    /// println!("This password was found {} times in data breaches", count);
+    /// # }
+    /// ```
+    pub async fn check_password_hash_padded<H: Sha1Digest + ?Sized>(
+        &self,
+        hash: &H,
+    ) -> Result<u64, HibpError> {
+        let hash_str = secret(valid_sha1_hex(hash)?);
+        self.count_sha1_hash(&hash_str).await
+    }
+
+    /// Check many passwords at once, using padding for privacy.
+    ///
+    /// Passwords are hashed in parallel and grouped by their 5-character hash prefix, so
+    /// every range is fetched only once and passwords sharing a prefix cost a single
+    /// request. Auditing thousands of passwords takes far fewer requests than calling
+    /// [`HaveIBeenPwned::check_password`] in a loop. Counts are returned in input order.
+    ///
+    /// # Arguments
+    ///
+    /// * `passwords` - The passwords to check
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let passwords = ["password123", "hunter2", "correct horse battery staple"];
+    /// let counts = hibp.check_passwords(&passwords).await.unwrap();
+    /// for (password, count) in passwords.iter().zip(counts) {
+    ///     println!("{} was found {} times in data breaches", password, count);
+    /// }
+    /// # }
+    /// ```
+    pub async fn check_passwords(&self, passwords: &[&str]) -> Result<Vec<u64>, HibpError> {
+        let digests = secret(self.sha1_batch_async(passwords).await);
+        let hashes: Secret<Vec<String>> =
+            secret(digests.iter().map(|digest| to_hex_upper(digest)).collect());
+        drop(digests);
+        self.count_hashes(&hashes, RangeMode::Sha1).await
+    }
+
+    /// Check many passwords at once, writing each result to a sink as soon as its range is
+    /// fetched.
+    ///
+    /// Like [`HaveIBeenPwned::check_passwords`], but results are not collected in memory.
+    /// Each result carries the index of its password instead of the password. Results
+    /// arrive grouped by hash prefix rather than in input order. The sink is flushed but
+    /// not closed. Returns the number of passwords found in the corpus.
+    ///
+    /// # Arguments
+    ///
+    /// * `passwords` - The passwords to check
+    /// * `sink` - Destination of the results
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{HaveIBeenPwned, NdjsonSink, ResultSink};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let passwords = ["password123", "hunter2", "correct horse battery staple"];
+    /// let mut sink = NdjsonSink::new(std::io::stdout());
+    /// let pwned = hibp.check_passwords_into(&passwords, &mut sink).await?;
+    /// sink.close().await?;
+    /// eprintln!("{} passwords were found in data breaches", pwned);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "full")]
+    pub async fn check_passwords_into<S: ResultSink<PasswordCheck>>(
+        &self,
+        passwords: &[&str],
+        sink: &mut S,
+    ) -> Result<usize, HibpError> {
+        let digests = secret(self.sha1_batch_async(passwords).await);
+        let hashes: Secret<Vec<String>> =
+            secret(digests.iter().map(|digest| to_hex_upper(digest)).collect());
+        drop(digests);
+
+        let mut pwned = 0;
+        for (prefix, indices) in group_by_prefix(&hashes) {
+            let range = self
+                .fetch_compact_range(prefix, RangeMode::Sha1, true)
+                .await?;
+            for index in indices {
+                let count = range_count(&range, &hashes[index][5..]);
+                if count > 0 {
+                    pwned += 1;
+                }
+                sink.write(PasswordCheck { index, count }).await?;
+            }
+        }
+        sink.flush().await?;
+
+        Ok(pwned)
+    }
+
+    /// Check many SHA-1 digests at once, using padding for privacy.
+    ///
+    /// Like [`HaveIBeenPwned::check_passwords`] for stored digests, see
+    /// [`HaveIBeenPwned::check_password_hash`]. Fails with [`HibpError::InvalidInput`]
+    /// before sending anything if one of the digests is invalid.
+    ///
+    /// # Arguments
+    ///
+    /// * `hashes` - The SHA-1 digests, as hex text (any case) or as `[u8; 20]`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let hashes = [
+    ///     "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8",
+    ///     "F3BBBD66A63D4BF1747940578EC3D0103530E21D",
+    /// ];
+    /// let counts = hibp.check_password_hashes(&hashes).await.unwrap();
+    /// println!("Counts: {:?}", counts);
+    /// # }
+    /// ```
+    pub async fn check_password_hashes<H: Sha1Digest>(
+        &self,
+        hashes: &[H],
+    ) -> Result<Vec<u64>, HibpError> {
+        let hashes = secret(
+            hashes
+                .iter()
+                .map(valid_sha1_hex)
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+        self.count_hashes(&hashes, RangeMode::Sha1).await
+    }
+
+    /// Check if a password has been exposed in data breaches by its NTLM hash, using
+    /// padding for privacy.
+    ///
+    /// The NTLM hash (MD4 of the UTF-16LE encoding) is computed locally with the configured
+    /// [`HashBackend`](crate::HashBackend) and looked up in NTLM mode, so Active Directory
+    /// audits get the same counts as tools that query NTLM hashes directly. Fails with
+    /// [`HibpError::InvalidInput`] if the backend does not implement NTLM.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to check
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let count = hibp.check_password_ntlm("password123").await.unwrap();
+    /// println!("This password was found {} times in data breaches", count);
+    /// # }
+    /// ```
+    pub async fn check_password_ntlm(&self, password: &str) -> Result<u64, HibpError> {
+        let hash_str = {
+            let digest = secret(self.hash_backend.ntlm(password).ok_or_else(|| {
+                HibpError::InvalidInput("The hash backend does not implement NTLM".into())
+            })?);
+            secret(to_hex_upper(digest.as_slice()))
+        };
+        self.count_hash(&hash_str, RangeMode::Ntlm, true).await
+    }
+
+    /// Check if a password given as raw bytes has been exposed in data breaches, using
+    /// padding for privacy.
+    ///
+    /// Useful for secrets that never were valid UTF-8 strings, or that shouldn't be copied
+    /// into a `String` first, e.g. bytes received from a pipe or an agent socket.
+    /// With the `zeroize` feature the digest and hex hash are wiped once the lookup is done;
+    /// wiping `password` itself is up to the caller.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password bytes to check
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let count = hibp.check_password_bytes(b"password123").await.unwrap();
+    /// println!("This password was found {} times in data breaches", count);
+    /// # }
+    /// ```
+    pub async fn check_password_bytes(&self, password: &[u8]) -> Result<u64, HibpError> {
+        let hash_str = self.sha1_hex_bytes(password);
+        self.count_sha1_hash(&hash_str).await
+    }
+
+    /// Reads a password from a source and checks if it has been exposed in data breaches,
+    /// using padding for privacy.
+    ///
+    /// The whole source is read without blocking the executor before the request is sent.
+    /// A single trailing line ending is not considered part of the password, so input like
+    /// `echo secret | app` works. Sources longer than [`MAX_READER_PASSWORD_LEN`] bytes are
+    /// rejected with [`HibpError::InvalidInput`]. The password is read into a buffer
+    /// allocated once at full size, so with the `zeroize` feature no copy of it is left
+    /// behind.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source to read the password from
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let count = hibp.check_password_reader(tokio::io::stdin()).await.unwrap();
+    /// println!("This password was found {} times in data breaches", count);
+    /// # }
+    /// ```
+    pub async fn check_password_reader<R: AsyncRead + Unpin>(
+        &self,
+        mut reader: R,
+    ) -> Result<u64, HibpError> {
+        // One spare byte tells a password of exactly the maximum length from a longer one
+        let mut password = secret(vec![0u8; MAX_READER_PASSWORD_LEN + 1]);
+        let mut len = 0;
+        loop {
+            let mut buf = ReadBuf::new(&mut password[len..]);
+            std::future::poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf)).await?;
+            let read = buf.filled().len();
+            if read == 0 {
+                break;
+            }
+            len += read;
+            if len > MAX_READER_PASSWORD_LEN {
+                return Err(HibpError::InvalidInput(format!(
+                    "The password is longer than {} bytes",
+                    MAX_READER_PASSWORD_LEN
+                )));
+            }
+        }
+
+        let hash_str = self.sha1_hex_bytes(trim_line_ending(&password[..len]));
+        drop(password);
+        self.count_sha1_hash(&hash_str).await
+    }
+
+    /// Returns how often an uppercase hex SHA-1 hash appears, using a padded range request.
+    pub(crate) async fn count_sha1_hash(&self, hash_str: &str) -> Result<u64, HibpError> {
+        self.count_hash(hash_str, RangeMode::Sha1, true).await
+    }
+
+    /// Returns how often an uppercase hex hash appears in its range.
+    async fn count_hash(
+        &self,
+        hash_str: &str,
+        mode: RangeMode,
+        padded: bool,
+    ) -> Result<u64, HibpError> {
+        let (prefix, suffix) = hash_str.split_at(5);
+        let range = self.fetch_compact_range(prefix, mode, padded).await?;
+        Ok(range_count(&range, suffix))
+    }
+
+    /// Looks up many uppercase hex hashes, fetching each 5-character prefix range only once.
+    ///
+    /// Ranges are requested with padding. Returns the breach counts in input order, without
+    /// keeping copies of the hashes.
+    pub(crate) async fn count_hashes(
+        &self,
+        hashes: &[String],
+        mode: RangeMode,
+    ) -> Result<Vec<u64>, HibpError> {
+        let mut counts = vec![0; hashes.len()];
+        for (prefix, indices) in group_by_prefix(hashes) {
+            let range = self.fetch_compact_range(prefix, mode, true).await?;
+            for index in indices {
+                counts[index] = range_count(&range, &hashes[index][5..]);
+            }
+        }
+
+        Ok(counts)
+    }
+}
+
+/// Groups uppercase hex hashes by their 5-character prefix, keeping the input index of
+/// each hash so results can be matched back to the input.
+pub(crate) fn group_by_prefix(hashes: &[String]) -> BTreeMap<&str, Vec<usize>> {
+    let mut groups: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (index, hash) in hashes.iter().enumerate() {
+        groups.entry(&hash[..5]).or_default().push(index);
+    }
+    groups
+}
+
+/// Returns how often a hash suffix appears in a range, or 0 if it doesn't.
+pub(crate) fn range_count(range: &[CompactPwnedPassword], hash_suffix: &str) -> u64 {
+    range
+        .iter()
+        .find(|p| p.matches(hash_suffix))
+        .map_or(0, |p| p.count as u64)
+}
+
+/// Parses the `SUFFIX:COUNT` lines of a range body while its chunks arrive.
+///
+/// Only the incomplete last line of a chunk is buffered, so the whole body is never held
+/// in memory. Lines the parse function rejects, e.g. malformed or not UTF-8, are skipped.
+pub(crate) struct RangeParser<T, F> {
+    parse: F,
+    partial: Vec<u8>,
+    entries: Vec<T>,
+}
+
+impl<T, F: FnMut(&str) -> Option<T>> RangeParser<T, F> {
+    /// Creates a parser turning each line into an entry with `parse`.
+    pub(crate) fn new(parse: F) -> Self {
+        RangeParser {
+            parse,
+            partial: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Parses a complete line, with or without its line ending.
+    fn parse_line(&mut self, line: &[u8]) {
+        if let Ok(line) = std::str::from_utf8(line)
+            && let Some(entry) = (self.parse)(line)
+        {
+            self.entries.push(entry);
+        }
+    }
+
+    /// Parses the last line, which has no line ending, and returns all entries.
+    pub(crate) fn finish(mut self) -> Vec<T> {
+        let partial = std::mem::take(&mut self.partial);
+        self.parse_line(&partial);
+        self.entries
+    }
+}
+
+impl<T, F: FnMut(&str) -> Option<T>> BodySink for RangeParser<T, F> {
+    fn write(&mut self, chunk: &[u8]) {
+        let mut lines = chunk.split(|&b| b == b'\n');
+        // The last piece has no line ending yet and continues in the next chunk
+        let rest = lines.next_back().unwrap_or_default();
+        for line in lines {
+            if self.partial.is_empty() {
+                self.parse_line(line);
+            } else {
+                let mut partial = std::mem::take(&mut self.partial);
+                partial.extend_from_slice(line);
+                self.parse_line(&partial);
+                partial.clear();
+                self.partial = partial;
+            }
+        }
+        self.partial.extend_from_slice(rest);
+    }
+
+    fn reset(&mut self) {
+        self.partial.clear();
+        self.entries.clear();
+    }
+}
+
+/// Returns the uppercase hex form of a SHA-1 digest, rejecting anything else.
+fn valid_sha1_hex<H: Sha1Digest + ?Sized>(hash: &H) -> Result<String, HibpError> {
+    hash.to_sha1_hex().ok_or_else(|| {
+        HibpError::InvalidInput("Hash must be a SHA-1 digest of 40 hex characters".into())
+    })
+}
+
+/// Strips a single trailing `\n` or `\r\n` from input read from a pipe or file.
+pub(crate) fn trim_line_ending(input: &[u8]) -> &[u8] {
+    let input = input.strip_suffix(b"\n").unwrap_or(input);
+    input.strip_suffix(b"\r").unwrap_or(input)
 }
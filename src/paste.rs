@@ -1,8 +1,8 @@
-use crate::HaveIBeenPwned;
-use urlencoding;
+use crate::{HaveIBeenPwned, HibpError};
+use chrono::{DateTime, Utc};
 
 /// Represents a paste returned by the HIBP API.
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct Paste {
     /// Source of the paste (e.g., "Pastebin").
     #[serde(rename = "Source")]
@@ -13,9 +13,9 @@ pub struct Paste {
     /// Title of the paste, if available.
     #[serde(rename = "Title")]
     pub title: Option<String>,
-    /// Date the paste was posted, if available.
+    /// Time the paste was posted, if available.
     #[serde(rename = "Date")]
-    pub date: Option<String>,
+    pub date: Option<DateTime<Utc>>,
     /// Number of emails found in the paste.
     #[serde(rename = "EmailCount")]
     pub email_count: u64,
@@ -38,26 +38,18 @@ impl HaveIBeenPwned {
     /// println!("{:?}", pastes);
     /// # }
     /// ```
-    pub async fn get_pastes_for_account(
-        &self,
-        account: &str,
-    ) -> Result<Vec<Paste>, Box<dyn std::error::Error>> {
-        if let Some(rate_limiter) = &self.rate_limiter {
-            rate_limiter.wait_if_needed().await;
-        }
-
+    pub async fn get_pastes_for_account(&self, account: &str) -> Result<Vec<Paste>, HibpError> {
         let encoded_account = urlencoding::encode(account.trim());
         let url = format!("{}/pasteaccount/{}", self.base_url, encoded_account);
-        let headers = self.create_headers()?;
-        let resp = self.client.get(&url).headers(headers).send().await?;
+        let resp = self.send_api_get("pasteaccount", &url).await?;
 
-        if resp.status().is_success() {
-            let pastes: Vec<Paste> = resp.json().await?;
+        if resp.status.is_success() {
+            let pastes: Vec<Paste> = self.decode_json(&resp)?;
             Ok(pastes)
-        } else if resp.status().as_u16() == 404 {
+        } else if resp.status.as_u16() == 404 {
             Ok(vec![])
         } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+            Err(resp.error())
         }
     }
 }
@@ -0,0 +1,128 @@
+use crate::runtime::Instant;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Progress of a long-running job, e.g. a [`crate::BatchExecutor`] run or a
+/// [`crate::CorpusDownloader`] sync.
+///
+/// Reported after every finished item to the callback set with `with_progress`, so CLIs
+/// and dashboards can show how far a job got and how long it will take.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{CorpusDownloader, HaveIBeenPwned};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let downloader = CorpusDownloader::new(HaveIBeenPwned::new_free_tier()).with_progress(|progress| {
+///     if progress.completed % 1000 == 0 {
+///         println!(
+///             "{}/{} ranges, {:.1} MB/s, ETA {:?}",
+///             progress.completed,
+///             progress.total.unwrap_or_default(),
+///             progress.bytes_per_second() / 1e6,
+///             progress.eta()
+///         );
+///     }
+/// });
+/// downloader.download_to_file("pwnedpasswords.txt").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of finished items, e.g. ranges or batch inputs.
+    pub completed: u64,
+    /// Number of items in total, if known up front.
+    pub total: Option<u64>,
+    /// Number of bytes received so far, 0 for jobs that don't count them.
+    pub bytes: u64,
+    /// Time since the job started.
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// Returns the average number of finished items per second.
+    pub fn rate(&self) -> f64 {
+        per_second(self.completed, self.elapsed)
+    }
+
+    /// Returns the average number of bytes received per second.
+    pub fn bytes_per_second(&self) -> f64 {
+        per_second(self.bytes, self.elapsed)
+    }
+
+    /// Estimates the time left at the average rate so far, or `None` if the total is
+    /// unknown or nothing finished yet.
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.total?.saturating_sub(self.completed);
+        if self.completed == 0 {
+            return None;
+        }
+        Some(
+            self.elapsed
+                .mul_f64(remaining as f64 / self.completed as f64),
+        )
+    }
+}
+
+/// Returns `count / elapsed`, or 0 before any time passed.
+fn per_second(count: u64, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        0.0
+    } else {
+        count as f64 / elapsed.as_secs_f64()
+    }
+}
+
+/// Callback receiving [`Progress`] reports.
+#[derive(Clone)]
+pub(crate) struct ProgressHook(Arc<dyn Fn(&Progress) + Send + Sync>);
+
+impl ProgressHook {
+    pub(crate) fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&Progress) + Send + Sync + 'static,
+    {
+        ProgressHook(Arc::new(hook))
+    }
+}
+
+impl std::fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ProgressHook")
+    }
+}
+
+/// Counts finished items of a job and reports them to an optional hook.
+#[derive(Debug)]
+pub(crate) struct ProgressTracker {
+    hook: Option<ProgressHook>,
+    started: Instant,
+    progress: Progress,
+}
+
+impl ProgressTracker {
+    pub(crate) fn new(hook: Option<ProgressHook>, total: Option<u64>) -> Self {
+        ProgressTracker {
+            hook,
+            started: Instant::now(),
+            progress: Progress {
+                completed: 0,
+                total,
+                bytes: 0,
+                elapsed: Duration::ZERO,
+            },
+        }
+    }
+
+    /// Counts a finished item with the bytes received for it and reports the progress.
+    pub(crate) fn complete(&mut self, bytes: u64) {
+        self.progress.completed += 1;
+        self.progress.bytes += bytes;
+        self.progress.elapsed = self.started.elapsed();
+        if let Some(hook) = &self.hook {
+            (hook.0)(&self.progress);
+        }
+    }
+}
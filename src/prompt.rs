@@ -0,0 +1,43 @@
+use crate::hashing::{secret, to_hex_upper};
+use crate::{HaveIBeenPwned, HibpError};
+
+impl HaveIBeenPwned {
+    /// Prompts for a password without echoing it and checks how often it appears in breaches.
+    ///
+    /// The password is read from the terminal, hashed right away and wiped from memory
+    /// before the lookup starts, so it never ends up in shell history, process listings or
+    /// long-lived buffers the way command-line arguments do. The lookup uses a padded range
+    /// request.
+    ///
+    /// Requires the `prompt` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - Text shown before the input, e.g. `"Password: "`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let hibp = HaveIBeenPwned::new("");
+    /// let count = hibp.prompt_and_check("Password to check: ").await?;
+    /// println!("This password was found {} times in data breaches", count);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn prompt_and_check(&self, prompt: &str) -> Result<u64, HibpError> {
+        let prompt = prompt.to_string();
+        let backend = self.hash_backend.clone();
+        // Reading the terminal blocks, so keep it off the async runtime
+        let hash = tokio::task::spawn_blocking(move || {
+            let password = secret(rpassword::prompt_password(prompt)?);
+            let digest = secret(backend.sha1(password.as_bytes()));
+            Ok::<_, std::io::Error>(secret(to_hex_upper(digest.as_slice())))
+        })
+        .await
+        .map_err(HibpError::other)??;
+
+        self.count_sha1_hash(&hash).await
+    }
+}
@@ -0,0 +1,492 @@
+use crate::password::RangeMode;
+use crate::runtime::Instant;
+use crate::{ApiResponse, HaveIBeenPwned, HibpError};
+use axum::Router;
+use axum::extract::{Path, RawQuery, State};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long responses are cached if the client has no response cache.
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Header identifying the consumer of a [`HibpProxy`].
+pub const PROXY_TOKEN_HEADER: &str = "X-HIBP-Proxy-Token";
+
+/// Request counts of one proxy consumer, returned by [`HibpProxy::usage`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsumerUsage {
+    /// Requests made by the consumer.
+    pub requests: u64,
+    /// Requests answered from the cache.
+    pub cache_hits: u64,
+    /// Requests forwarded to HIBP.
+    pub upstream_requests: u64,
+    /// Requests refused with 429 because the consumer exceeded its share.
+    pub rejected: u64,
+}
+
+/// A registered consumer and its upstream requests of the last minute.
+#[derive(Debug)]
+struct Consumer {
+    name: String,
+    usage: ConsumerUsage,
+    recent: VecDeque<Instant>,
+}
+
+/// A request forwarded to HIBP.
+enum Upstream {
+    /// A request to the HIBP API.
+    Api { endpoint: String, url: String },
+    /// A Pwned Passwords range request.
+    Range {
+        url: String,
+        headers: reqwest::header::HeaderMap,
+        padded: bool,
+    },
+}
+
+/// An HTTP server exposing HIBP-compatible routes backed by one client.
+///
+/// Internal apps point their HIBP base URL at the proxy instead of
+/// `https://haveibeenpwned.com/api/v3` and drop their own API keys: the proxy adds the key
+/// of its client and sends requests the way the client sends its own lookups. They're
+/// answered from the client's response cache, share in-flight requests with identical
+/// ones, and go through its rate limiter, request budget and retry policy. The following
+/// routes are served:
+///
+/// - `GET /api/v3/{*path}` - Forwarded to the client's `base_url`, including the query string
+/// - `GET /range/{prefix}` - Forwarded to the client's `passwords_base_url`, honoring `Add-Padding`
+///
+/// Configure caching on the client, e.g. a shared [`crate::CacheStore`] or
+/// [`HaveIBeenPwned::with_stale_while_revalidate`]; clients without a response cache get
+/// an in-memory one keeping responses for 5 minutes. The `Retry-After` and quota headers
+/// (`x-ratelimit-*`, `ratelimit*` and `hibp-*`) of upstream responses are passed on.
+///
+/// Once consumers are registered with [`HibpProxy::with_consumer`], every request must
+/// carry a consumer token in the [`PROXY_TOKEN_HEADER`] header. The client's rate limit is
+/// then shared fairly: each consumer may make its share of the requests per minute, split
+/// evenly between the consumers active in the last minute, and gets a 429 with
+/// `Retry-After` from the proxy when it exceeds it. Cache hits don't count towards the share.
+/// Without registered consumers the proxy does not authenticate its callers, so only
+/// expose it on a trusted network.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{HaveIBeenPwned, HibpProxy};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let hibp = HaveIBeenPwned::new_with_auto_rate_limit("your_api_key")
+///     .await?
+///     .with_response_cache(std::time::Duration::from_secs(3600));
+/// HibpProxy::new(hibp).serve("0.0.0.0:8080".parse()?).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HibpProxy {
+    client: HaveIBeenPwned,
+    consumers: Arc<Mutex<HashMap<String, Consumer>>>,
+}
+
+impl HibpProxy {
+    /// Creates a proxy forwarding requests with the given client.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client all upstream requests are made with. Gets an in-memory
+    ///   response cache if it has none.
+    pub fn new(client: HaveIBeenPwned) -> Self {
+        let client = if client.response_cache.is_some() {
+            client
+        } else {
+            client.with_response_cache(DEFAULT_CACHE_TTL)
+        };
+        HibpProxy {
+            client,
+            consumers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Registers a consumer, requiring all callers to identify themselves with a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name the consumer's usage is reported under.
+    /// * `token` - Secret the consumer sends in the [`PROXY_TOKEN_HEADER`] header.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hibp_rs::{HaveIBeenPwned, HibpProxy};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let hibp = HaveIBeenPwned::new_with_rate_limit("your_api_key", 100);
+    /// let proxy = HibpProxy::new(hibp)
+    ///     .with_consumer("helpdesk", "token-for-helpdesk")
+    ///     .with_consumer("signup", "token-for-signup");
+    /// proxy.serve("0.0.0.0:8080".parse()?).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_consumer<S: Into<String>, T: Into<String>>(self, name: S, token: T) -> Self {
+        self.lock_consumers().insert(
+            token.into(),
+            Consumer {
+                name: name.into(),
+                usage: ConsumerUsage::default(),
+                recent: VecDeque::new(),
+            },
+        );
+        self
+    }
+
+    /// Returns the request counts of all registered consumers, keyed by name.
+    pub fn usage(&self) -> HashMap<String, ConsumerUsage> {
+        self.lock_consumers()
+            .values()
+            .map(|consumer| (consumer.name.clone(), consumer.usage.clone()))
+            .collect()
+    }
+
+    /// Returns an axum router serving the proxy routes.
+    ///
+    /// Use this to mount the proxy into an existing application or to add middleware.
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/api/v3/{*path}", get(forward_api))
+            .route("/range/{prefix}", get(forward_range))
+            .with_state(self.clone())
+    }
+
+    /// Serves the proxy on the given address until the process exits.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Address to listen on.
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), HibpError> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+
+    /// Answers a request from the cache or forwards it upstream within the consumer's share.
+    async fn forward(&self, request_headers: &HeaderMap, upstream: Upstream) -> Response {
+        let token = match self.identify(request_headers) {
+            Ok(token) => token,
+            Err(()) => {
+                return (StatusCode::UNAUTHORIZED, "Missing or unknown proxy token")
+                    .into_response();
+            }
+        };
+
+        if let Some(response) = self.cached(&upstream).await {
+            let mut consumers = self.lock_consumers();
+            if let Some(consumer) = token.as_deref().and_then(|t| consumers.get_mut(t)) {
+                consumer.usage.cache_hits += 1;
+            }
+            return response;
+        }
+
+        if let Some(token) = &token
+            && let Err(retry_after) = self.admit(token)
+        {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(
+                    header::RETRY_AFTER,
+                    retry_after.as_secs().max(1).to_string(),
+                )],
+                "Consumer exceeded its share of the rate limit",
+            )
+                .into_response();
+        }
+
+        self.fetch(upstream).await
+    }
+
+    /// Answers a request from the client's response cache.
+    async fn cached(&self, upstream: &Upstream) -> Option<Response> {
+        match upstream {
+            Upstream::Api { endpoint, url } => self
+                .client
+                .cached_api_get(endpoint, url, &reqwest::header::HeaderMap::new())
+                .await
+                .map(api_response),
+            Upstream::Range {
+                url,
+                headers,
+                padded,
+            } => self
+                .client
+                .cached_range(url, headers, *padded)
+                .await
+                .map(range_response),
+        }
+    }
+
+    /// Sends a request upstream through the client, caching the response.
+    async fn fetch(&self, upstream: Upstream) -> Response {
+        match upstream {
+            Upstream::Api { endpoint, url } => match self
+                .client
+                .fetch_api_get(&endpoint, &url, reqwest::header::HeaderMap::new())
+                .await
+            {
+                Ok(resp) => api_response(resp),
+                Err(e) => error_response(&e),
+            },
+            Upstream::Range {
+                url,
+                headers,
+                padded,
+            } => {
+                let mut body = Vec::new();
+                match self
+                    .client
+                    .request_range(&url, headers, padded, &mut body)
+                    .await
+                {
+                    Ok(()) => range_response(body),
+                    Err(e) => error_response(&e),
+                }
+            }
+        }
+    }
+
+    /// Returns the token of the calling consumer, or `None` if no consumers are registered.
+    ///
+    /// Fails if consumers are registered but the caller is none of them.
+    fn identify(&self, request_headers: &HeaderMap) -> Result<Option<String>, ()> {
+        let mut consumers = self.lock_consumers();
+        if consumers.is_empty() {
+            return Ok(None);
+        }
+
+        let token = request_headers
+            .get(PROXY_TOKEN_HEADER)
+            .map(HeaderValue::as_bytes)
+            .unwrap_or_default();
+        // Every registered token is compared in constant time, so response times don't
+        // reveal how much of a token a caller guessed right
+        let mut matched = None;
+        for known in consumers.keys() {
+            if constant_time_eq(known.as_bytes(), token) {
+                matched = Some(known.clone());
+            }
+        }
+        let token = matched.ok_or(())?;
+        if let Some(consumer) = consumers.get_mut(&token) {
+            consumer.usage.requests += 1;
+        }
+        Ok(Some(token))
+    }
+
+    /// Records an upstream request of a consumer if it is within its share.
+    ///
+    /// Returns how long the consumer has to wait otherwise.
+    pub(crate) fn admit(&self, token: &str) -> Result<(), Duration> {
+        let window = Duration::from_secs(60);
+        let rpm = self.client.rpm();
+        let mut consumers = self.lock_consumers();
+        for consumer in consumers.values_mut() {
+            while consumer
+                .recent
+                .front()
+                .is_some_and(|t| t.elapsed() >= window)
+            {
+                consumer.recent.pop_front();
+            }
+        }
+
+        let active = consumers
+            .iter()
+            .filter(|(t, c)| !c.recent.is_empty() || t.as_str() == token)
+            .count();
+        let consumer = consumers.get_mut(token).expect("consumer was identified");
+        if let Some(rpm) = rpm {
+            let share = (rpm.max(1) as usize / active).max(1);
+            if consumer.recent.len() >= share {
+                consumer.usage.rejected += 1;
+                let oldest = consumer.recent.front().map(|t| t.elapsed());
+                return Err(window.saturating_sub(oldest.unwrap_or_default()));
+            }
+        }
+        consumer.recent.push_back(Instant::now());
+        consumer.usage.upstream_requests += 1;
+        Ok(())
+    }
+
+    fn lock_consumers(&self) -> std::sync::MutexGuard<'_, HashMap<String, Consumer>> {
+        self.consumers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Returns the rate limit of the client in requests per minute, if it is known.
+    fn rpm(&self) -> Option<i32> {
+        if let Some(rate_limiter) = &self.custom_rate_limiter {
+            return rate_limiter.rpm();
+        }
+        self.rate_limiter
+            .as_ref()
+            .or_else(|| self.lazy_rate_limiter.as_ref().and_then(|lazy| lazy.get()))
+            .map(|rate_limiter| rate_limiter.get_rpm())
+    }
+}
+
+/// Returns the headers of an upstream response that are passed on to proxy callers.
+pub(crate) fn forwarded_headers(
+    headers: &reqwest::header::HeaderMap,
+) -> reqwest::header::HeaderMap {
+    headers
+        .iter()
+        .filter(|(name, _)| {
+            let name = name.as_str();
+            name == "retry-after"
+                || name.starts_with("hibp-")
+                || name.starts_with("x-ratelimit-")
+                || name.starts_with("ratelimit")
+        })
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+/// Compares two byte strings in time depending only on their lengths.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Returns the statistics endpoint name of an API path, e.g. `stealerlog/email`.
+fn endpoint_name(path: &str) -> String {
+    let mut segments = path.split('/');
+    let first = segments.next().unwrap_or_default();
+    match (first, segments.next()) {
+        ("stealerlog" | "subscription", Some(second)) => format!("{}/{}", first, second),
+        _ => first.to_string(),
+    }
+}
+
+/// Answers with an upstream or cached API response.
+fn api_response(resp: ApiResponse) -> Response {
+    // reqwest and axum depend on different versions of the http crate
+    let status = StatusCode::from_u16(resp.status.as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut response = (
+        status,
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        )],
+        resp.body,
+    )
+        .into_response();
+    for (name, value) in &resp.forwarded_headers {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_str().as_bytes()),
+            HeaderValue::from_bytes(value.as_bytes()),
+        ) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    response
+}
+
+/// Answers with the body of a range.
+fn range_response(body: Vec<u8>) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"))],
+        body,
+    )
+        .into_response()
+}
+
+/// Answers a request the client failed, e.g. because the request budget is used up.
+fn error_response(e: &HibpError) -> Response {
+    let (status, retry_after) = match e {
+        HibpError::RateLimited { retry_after } => (StatusCode::TOO_MANY_REQUESTS, *retry_after),
+        HibpError::BudgetExceeded(budget) => {
+            (StatusCode::TOO_MANY_REQUESTS, Some(budget.resets_in))
+        }
+        HibpError::NotFound => (StatusCode::NOT_FOUND, None),
+        HibpError::Unauthorized { .. } => (StatusCode::UNAUTHORIZED, None),
+        HibpError::Forbidden { .. } => (StatusCode::FORBIDDEN, None),
+        HibpError::InvalidInput(_) => (StatusCode::BAD_REQUEST, None),
+        HibpError::Status(status) => (
+            StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::BAD_GATEWAY),
+            None,
+        ),
+        _ => (StatusCode::BAD_GATEWAY, None),
+    };
+    let mut response = (status, e.to_string()).into_response();
+    if let Some(retry_after) = retry_after {
+        response.headers_mut().insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(retry_after.as_secs().max(1)),
+        );
+    }
+    response
+}
+
+async fn forward_api(
+    State(proxy): State<HibpProxy>,
+    Path(path): Path<String>,
+    RawQuery(query): RawQuery,
+    request_headers: HeaderMap,
+) -> Response {
+    let encoded: Vec<String> = path
+        .split('/')
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect();
+    let mut url = format!("{}/{}", proxy.client.base_url, encoded.join("/"));
+    if let Some(query) = query {
+        url.push('?');
+        url.push_str(&query);
+    }
+
+    let upstream = Upstream::Api {
+        endpoint: endpoint_name(&path),
+        url,
+    };
+    proxy.forward(&request_headers, upstream).await
+}
+
+async fn forward_range(
+    State(proxy): State<HibpProxy>,
+    Path(prefix): Path<String>,
+    RawQuery(query): RawQuery,
+    request_headers: HeaderMap,
+) -> Response {
+    if prefix.len() != 5 || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Hash prefix must be exactly 5 hex characters",
+        )
+            .into_response();
+    }
+
+    let mode = match query.as_deref() {
+        Some("mode=ntlm") => RangeMode::Ntlm,
+        _ => RangeMode::Sha1,
+    };
+    let padded = request_headers
+        .get("Add-Padding")
+        .is_some_and(|v| v.as_bytes().eq_ignore_ascii_case(b"true"));
+    let (url, headers) = match proxy.client.range_request(&prefix, mode, padded) {
+        Ok(request) => request,
+        Err(e) => return error_response(&e),
+    };
+
+    let upstream = Upstream::Range {
+        url,
+        headers,
+        padded,
+    };
+    proxy.forward(&request_headers, upstream).await
+}
@@ -0,0 +1,100 @@
+use crate::{HaveIBeenPwned, HibpError, RateLimiter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Future returned by [`RateLimiting::acquire`].
+///
+/// `Send` on native targets so clients can be shared across tasks; WASM timers are not
+/// `Send`, so edge builds drop the bound.
+#[cfg(not(target_arch = "wasm32"))]
+pub type AcquireFuture<'a> = Pin<Box<dyn Future<Output = Result<(), HibpError>> + Send + 'a>>;
+
+/// Future returned by [`RateLimiting::acquire`].
+#[cfg(target_arch = "wasm32")]
+pub type AcquireFuture<'a> = Pin<Box<dyn Future<Output = Result<(), HibpError>> + 'a>>;
+
+/// Decides when the next HIBP API request may be sent.
+///
+/// The client uses the built-in [`RateLimiter`] unless another implementation is set with
+/// [`HaveIBeenPwned::with_rate_limiter`], e.g. to ask a central throttling service shared
+/// by several applications for permission. Pwned Passwords requests don't go through it.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{AcquireFuture, HaveIBeenPwned, RateLimiting};
+///
+/// #[derive(Debug)]
+/// struct CentralThrottle {
+///     endpoint: String,
+/// }
+///
+/// impl RateLimiting for CentralThrottle {
+///     fn acquire(&self) -> AcquireFuture<'_> {
+///         Box::pin(async move {
+///             // Ask the throttling service at self.endpoint for a permit here
+///             Ok(())
+///         })
+///     }
+/// }
+///
+/// let hibp = HaveIBeenPwned::new("your_api_key").with_rate_limiter(CentralThrottle {
+///     endpoint: "http://throttle.internal/hibp".to_string(),
+/// });
+/// ```
+pub trait RateLimiting: std::fmt::Debug + Send + Sync {
+    /// Waits until the next request may be sent.
+    ///
+    /// Errors abort the request and are returned to the caller, e.g. when the throttling
+    /// service is unreachable.
+    fn acquire(&self) -> AcquireFuture<'_>;
+
+    /// Returns the average number of requests allowed per minute, if known.
+    ///
+    /// Used by `HibpProxy` (`proxy` feature) to share the limit fairly between consumers.
+    fn rpm(&self) -> Option<i32> {
+        None
+    }
+}
+
+impl RateLimiting for RateLimiter {
+    fn acquire(&self) -> AcquireFuture<'_> {
+        Box::pin(async move {
+            self.wait_if_needed().await;
+            Ok(())
+        })
+    }
+
+    fn rpm(&self) -> Option<i32> {
+        Some(self.get_rpm())
+    }
+}
+
+/// Lets callers keep a handle to a limiter they share with the client.
+impl<R: RateLimiting + ?Sized> RateLimiting for Arc<R> {
+    fn acquire(&self) -> AcquireFuture<'_> {
+        (**self).acquire()
+    }
+
+    fn rpm(&self) -> Option<i32> {
+        (**self).rpm()
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Limits the request rate with a custom [`RateLimiting`] implementation.
+    ///
+    /// Replaces the built-in limiter of [`HaveIBeenPwned::new_with_rate_limit`] and
+    /// [`HaveIBeenPwned::new_with_lazy_rate_limit`]. Clones of the client share the
+    /// limiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate_limiter` - The rate limiter HIBP API requests wait for.
+    #[must_use]
+    pub fn with_rate_limiter<R: RateLimiting + 'static>(mut self, rate_limiter: R) -> Self {
+        self.custom_rate_limiter = Some(Arc::new(rate_limiter));
+        self
+    }
+}
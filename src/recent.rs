@@ -0,0 +1,91 @@
+use crate::{Breach, HaveIBeenPwned, HibpError};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Breach list kept between calls, newest first, shared by all clones of a client.
+pub(crate) type BreachSnapshot = Arc<Mutex<Option<Vec<Breach>>>>;
+
+impl HaveIBeenPwned {
+    /// Gets the breaches added to HIBP after the given time, newest first.
+    ///
+    /// The full breach list is downloaded on the first call and kept by the client. Later
+    /// calls only request the latest breach and download the list again if that breach is
+    /// not part of the snapshot yet, so polling for "what's new this week" stays cheap.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - Only return breaches added after this time.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let breaches = hibp
+    ///     .get_breaches_added_since("2024-01-01T00:00:00Z".parse()?)
+    ///     .await?;
+    /// for breach in breaches {
+    ///     println!("{} added {}", breach.name, breach.added_date);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_breaches_added_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Breach>, HibpError> {
+        let snapshot = self.fresh_breach_snapshot().await?;
+        let breaches = snapshot.as_ref().map_or(&[][..], Vec::as_slice);
+        Ok(breaches
+            .iter()
+            .take_while(|b| b.added_after(since))
+            .cloned()
+            .collect())
+    }
+
+    /// Gets the `n` breaches most recently added to HIBP, newest first.
+    ///
+    /// Uses the same snapshot and freshness check as
+    /// [`HaveIBeenPwned::get_breaches_added_since`].
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - Maximum number of breaches to return.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let breaches = hibp.get_most_recent_breaches(5).await.unwrap();
+    /// println!("{:?}", breaches);
+    /// # }
+    /// ```
+    pub async fn get_most_recent_breaches(&self, n: usize) -> Result<Vec<Breach>, HibpError> {
+        let snapshot = self.fresh_breach_snapshot().await?;
+        let breaches = snapshot.as_ref().map_or(&[][..], Vec::as_slice);
+        Ok(breaches.iter().take(n).cloned().collect())
+    }
+
+    /// Returns the breach snapshot, downloading the full list if the latest breach is missing from it.
+    async fn fresh_breach_snapshot(
+        &self,
+    ) -> Result<tokio::sync::MutexGuard<'_, Option<Vec<Breach>>>, HibpError> {
+        // Holding the lock while refreshing keeps concurrent callers from downloading twice
+        let mut snapshot = self.breach_snapshot.lock().await;
+        if let Some(breaches) = snapshot.as_ref() {
+            let latest = self.get_latest_breach().await?;
+            if breaches.iter().any(|b| b.name == latest.name) {
+                return Ok(snapshot);
+            }
+        }
+
+        let mut breaches = self.get_all_breaches().await?;
+        breaches.sort_by_key(|b| std::cmp::Reverse(b.added_date));
+        *snapshot = Some(breaches);
+        Ok(snapshot)
+    }
+}
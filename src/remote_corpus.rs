@@ -0,0 +1,147 @@
+use crate::limits::BodySink;
+use crate::offline::parse_line;
+use crate::password::RangeParser;
+use crate::{HibpError, PwnedPassword, hash};
+use object_store::ObjectStore;
+use object_store::path::Path;
+use std::sync::Arc;
+
+/// Answers Pwned Passwords lookups from a corpus in S3, GCS or Azure Blob Storage.
+///
+/// The corpus is stored like the directory layout of [`crate::OfflinePasswordStore`]: one
+/// `PREFIX.txt` object of `SUFFIX:COUNT` lines per range, below a common path. One node
+/// uploads it with [`crate::CorpusDownloader::download_to_remote`], and a fleet of
+/// services looks passwords up from the shared copy instead of each syncing the 40+ GB
+/// corpus. Each lookup reads a single range object. Ranges missing from the store are
+/// empty.
+///
+/// Stores are addressed by URL, e.g. `s3://bucket/corpus`, `gs://bucket/corpus` or
+/// `az://container/corpus`, with the `s3`, `gcs` or `azure` feature enabling the matching
+/// backend. Any other [`ObjectStore`] works through [`RemotePasswordStore::new`].
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::RemotePasswordStore;
+///
+/// # async fn example() -> Result<(), hibp_rs::HibpError> {
+/// let store = RemotePasswordStore::from_url("s3://security-datasets/pwnedpasswords")?;
+/// let count = store.check_password("password123").await?;
+/// println!("This password was found {} times in data breaches", count);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RemotePasswordStore {
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+}
+
+impl RemotePasswordStore {
+    /// Creates a store for the corpus below `path` in an object store.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The object store holding the corpus.
+    /// * `path` - The path of the range objects, without the trailing `/`.
+    pub fn new(store: Arc<dyn ObjectStore>, path: &str) -> Self {
+        RemotePasswordStore {
+            store,
+            path: Path::from(path),
+        }
+    }
+
+    /// Creates a store from a URL such as `s3://bucket/corpus`.
+    ///
+    /// Credentials and settings are read from the environment, e.g. `AWS_ACCESS_KEY_ID`,
+    /// `GOOGLE_SERVICE_ACCOUNT` or `AZURE_STORAGE_ACCOUNT_NAME`, like the official cloud
+    /// tools do.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the corpus.
+    pub fn from_url(url: &str) -> Result<Self, HibpError> {
+        let url = reqwest::Url::parse(url)
+            .map_err(|e| HibpError::InvalidInput(format!("Invalid corpus URL: {}", e)))?;
+        let options = std::env::vars().map(|(key, value)| (key.to_ascii_lowercase(), value));
+        let (store, path) =
+            object_store::parse_url_opts(&url, options).map_err(HibpError::other)?;
+        Ok(RemotePasswordStore {
+            store: Arc::from(store),
+            path,
+        })
+    }
+
+    /// Returns how often a password appears in a SHA-1 corpus.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to check
+    pub async fn check_password(&self, password: &str) -> Result<u64, HibpError> {
+        self.check_hash(&hash::sha1_hex(password)).await
+    }
+
+    /// Returns how often a password appears in an NTLM corpus.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to check
+    pub async fn check_password_ntlm(&self, password: &str) -> Result<u64, HibpError> {
+        self.check_hash(&hash::ntlm_hex(password)).await
+    }
+
+    /// Returns how often a hex SHA-1 or NTLM hash appears in the corpus.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The full hex digest, in any case
+    pub async fn check_hash(&self, hash: &str) -> Result<u64, HibpError> {
+        let (prefix, suffix) = hash::split_hash(hash).ok_or_else(|| {
+            HibpError::InvalidInput("Hash must be a hex SHA-1 or NTLM digest".into())
+        })?;
+        Ok(self
+            .range(&prefix)
+            .await?
+            .into_iter()
+            .find(|p| p.hash_suffix == suffix)
+            .map_or(0, |p| p.count))
+    }
+
+    /// Returns all hashes of a range, like [`crate::HaveIBeenPwned::search_password_range`].
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_prefix` - First 5 characters of a password hash
+    pub async fn search_range(&self, hash_prefix: &str) -> Result<Vec<PwnedPassword>, HibpError> {
+        if hash_prefix.len() != hash::PREFIX_LEN
+            || !hash_prefix.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            return Err(HibpError::InvalidInput(
+                "Hash prefix must be exactly 5 hex characters".into(),
+            ));
+        }
+        self.range(&hash_prefix.to_ascii_uppercase()).await
+    }
+
+    /// Reads the entries of an uppercase range prefix.
+    async fn range(&self, prefix: &str) -> Result<Vec<PwnedPassword>, HibpError> {
+        let body = match self.store.get(&self.range_path(prefix)).await {
+            Ok(object) => object.bytes().await.map_err(HibpError::other)?,
+            Err(object_store::Error::NotFound { .. }) => return Ok(Vec::new()),
+            Err(e) => return Err(HibpError::other(e)),
+        };
+        let mut parser = RangeParser::new(parse_line);
+        parser.write(&body);
+        Ok(parser.finish())
+    }
+
+    /// Returns the path of the object holding a range.
+    pub(crate) fn range_path(&self, prefix: &str) -> Path {
+        self.path.child(format!("{}.txt", prefix))
+    }
+
+    /// Returns the object store holding the corpus.
+    pub(crate) fn store(&self) -> &Arc<dyn ObjectStore> {
+        &self.store
+    }
+}
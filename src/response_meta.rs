@@ -0,0 +1,99 @@
+use crate::HaveIBeenPwned;
+use reqwest::header::HeaderMap;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Status and rate-limit headers of the most recent response a client received.
+///
+/// Returned by [`HaveIBeenPwned::last_response_meta`]. Lets schedulers outside the crate
+/// pace their own work, e.g. pause a queue for [`ResponseMeta::retry_after`] after a 429.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseMeta {
+    /// Endpoint the request was sent to, e.g. `breachedaccount` or `range`.
+    pub endpoint: String,
+    /// Response status.
+    pub status: u16,
+    /// How long to wait before the next request, from the `Retry-After` header.
+    pub retry_after: Option<Duration>,
+    /// Quota headers of the response (`x-ratelimit-*` and `ratelimit*`), keyed by their
+    /// lowercase name.
+    pub rate_limit_headers: BTreeMap<String, String>,
+}
+
+impl ResponseMeta {
+    /// Reads the metadata from the headers of a response.
+    pub(crate) fn from_response(
+        endpoint: &str,
+        status: reqwest::StatusCode,
+        headers: &HeaderMap,
+    ) -> Self {
+        let rate_limit_headers = headers
+            .iter()
+            .filter(|(name, _)| {
+                let name = name.as_str();
+                name.starts_with("x-ratelimit-") || name.starts_with("ratelimit")
+            })
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+        ResponseMeta {
+            endpoint: endpoint.to_string(),
+            status: status.as_u16(),
+            retry_after: crate::error::retry_after(headers),
+            rate_limit_headers,
+        }
+    }
+
+    /// Requests left in the current window, from `x-ratelimit-remaining` or
+    /// `ratelimit-remaining`, if the response had either.
+    pub fn remaining(&self) -> Option<u64> {
+        ["x-ratelimit-remaining", "ratelimit-remaining"]
+            .iter()
+            .find_map(|name| self.rate_limit_headers.get(*name)?.trim().parse().ok())
+    }
+}
+
+/// Shared slot for the metadata of the latest response, updated by all clones of a client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ResponseMetaRecorder {
+    last: Arc<Mutex<Option<ResponseMeta>>>,
+}
+
+impl ResponseMetaRecorder {
+    /// Replaces the metadata of the latest response.
+    pub(crate) fn record(&self, meta: ResponseMeta) {
+        *self.last.lock().unwrap_or_else(|e| e.into_inner()) = Some(meta);
+    }
+
+    /// Returns the metadata of the latest response.
+    pub(crate) fn last(&self) -> Option<ResponseMeta> {
+        self.last.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Returns the status, `Retry-After` and quota headers of the most recent response.
+    ///
+    /// Covers every request sent by this client and its clones, including retries and
+    /// Pwned Passwords lookups. Responses served from the response cache don't replace
+    /// it. Returns `None` until the first response arrives.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{HaveIBeenPwned, HibpError};
+    /// # async fn example() {
+    /// let hibp = HaveIBeenPwned::new("your_api_key");
+    /// if let Err(HibpError::RateLimited { .. }) = hibp.get_breaches_for_account("test@example.com").await {
+    ///     if let Some(meta) = hibp.last_response_meta() {
+    ///         println!("Pausing the queue for {:?}", meta.retry_after);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn last_response_meta(&self) -> Option<ResponseMeta> {
+        self.response_meta.last()
+    }
+}
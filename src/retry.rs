@@ -0,0 +1,196 @@
+use crate::{HaveIBeenPwned, HibpError};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// When and how often failed requests are retried.
+///
+/// Requests answered with 429 Too Many Requests are retried after the `Retry-After`
+/// delay sent by HIBP, unless it is longer than `max_backoff`: then the request fails with
+/// [`HibpError::RateLimited`] right away instead of sleeping for however long the server
+/// asked. Server errors (500, 502, 503, 504) and connection failures are
+/// retried with exponential backoff: the first retry waits `initial_backoff`, every
+/// further retry twice as long, up to `max_backoff`.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{HaveIBeenPwned, RetryPolicy};
+///
+/// let hibp = HaveIBeenPwned::new("your_api_key")
+///     .with_retry_policy(RetryPolicy::default().with_max_retries(5));
+/// let no_retries = HaveIBeenPwned::new("your_api_key").with_retry_policy(RetryPolicy::none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries per request.
+    pub max_retries: u32,
+    /// Delay before the first retry of a server error or connection failure.
+    pub initial_backoff: Duration,
+    /// Upper bound of the backoff delay.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Retries 3 times, backing off from 500ms up to 30s.
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns a policy that never retries.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the maximum number of retries per request.
+    #[must_use]
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry and the upper bound of the backoff.
+    #[must_use]
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Returns the exponential backoff delay before the given retry, counted from 0.
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+
+    /// Returns how long to wait before retrying a failed request, or `None` to give up.
+    ///
+    /// # Arguments
+    ///
+    /// * `retry` - Number of retries already made for the request.
+    /// * `error` - Error of the latest attempt.
+    pub(crate) fn delay_for(&self, retry: u32, error: &HibpError) -> Option<Duration> {
+        if retry >= self.max_retries {
+            return None;
+        }
+        match error {
+            HibpError::RateLimited {
+                retry_after: Some(retry_after),
+            } => (*retry_after <= self.max_backoff).then_some(*retry_after),
+            HibpError::RateLimited { retry_after: None } => Some(self.backoff(retry)),
+            HibpError::Status(status) if matches!(status.as_u16(), 500 | 502 | 503 | 504) => {
+                Some(self.backoff(retry))
+            }
+            HibpError::Http(e) if is_transient(e) => Some(self.backoff(retry)),
+            _ => None,
+        }
+    }
+}
+
+/// Signature of retry callbacks: retry number, delay and cause.
+type RetryCallback = dyn Fn(u32, Duration, &HibpError) + Send + Sync;
+
+/// Callback invoked before each retry, see [`HaveIBeenPwned::with_retry_hook`].
+#[derive(Clone)]
+pub(crate) struct RetryHook(Arc<RetryCallback>);
+
+impl RetryHook {
+    pub(crate) fn new<F>(hook: F) -> Self
+    where
+        F: Fn(u32, Duration, &HibpError) + Send + Sync + 'static,
+    {
+        RetryHook(Arc::new(hook))
+    }
+}
+
+impl std::fmt::Debug for RetryHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RetryHook")
+    }
+}
+
+/// Whether a network error is worth retrying, i.e. the server was unreachable or slow.
+#[cfg(not(target_arch = "wasm32"))]
+fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout()
+}
+
+/// Whether a network error is worth retrying. Fetch errors carry no details on WASM.
+#[cfg(target_arch = "wasm32")]
+fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_request() || error.is_timeout()
+}
+
+impl HaveIBeenPwned {
+    /// Sets when failed requests are retried.
+    ///
+    /// Clients retry 3 times by default, see [`RetryPolicy::default`]. The policy applies
+    /// to every endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The retry policy to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::{HaveIBeenPwned, RetryPolicy};
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_retry_policy(RetryPolicy::none());
+    /// ```
+    #[must_use]
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Calls `hook` before each retry, e.g. to tell users that HIBP is throttling them.
+    ///
+    /// The hook receives the number of the retry about to be made, starting at 1, the
+    /// delay before it and the error that caused it. It is called from async code, so it
+    /// should return quickly. Clones of the client share the hook.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Called with the retry number, delay and cause of each retry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_retry_hook(|attempt, delay, error| {
+    ///     eprintln!("{}, retrying in {:?} (attempt {})", error, delay, attempt);
+    /// });
+    /// ```
+    #[must_use]
+    pub fn with_retry_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(u32, Duration, &HibpError) + Send + Sync + 'static,
+    {
+        self.retry_hook = Some(RetryHook::new(hook));
+        self
+    }
+
+    /// Returns how long to wait before retrying a failed request, or `None` to give up,
+    /// calling the retry hook if the request is retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `retry` - Number of retries already made for the request.
+    /// * `error` - Error of the latest attempt.
+    pub(crate) fn retry_delay(&self, retry: u32, error: &HibpError) -> Option<Duration> {
+        let delay = self.retry_policy.delay_for(retry, error)?;
+        if let Some(hook) = &self.retry_hook {
+            (hook.0)(retry + 1, delay, error);
+        }
+        Some(delay)
+    }
+}
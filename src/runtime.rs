@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::{Instant, SystemTime};
+
+/// `std::time::Instant` panics on `wasm32-unknown-unknown`, so edge builds use `performance.now()`.
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::{Instant, SystemTime};
+
+/// Sleeps for the given duration on the tokio runtime.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Sleeps for the given duration using the host's `setTimeout`, as tokio timers are unavailable
+/// on WASM edge runtimes such as Cloudflare Workers.
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
@@ -0,0 +1,294 @@
+use crate::HibpError;
+use serde_json::{Value, json};
+use std::io::Write;
+
+/// SARIF schema URI emitted in every log.
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIF specification version emitted in every log.
+const SARIF_VERSION: &str = "2.1.0";
+
+/// Severity level of a SARIF result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SarifLevel {
+    /// Informational, not a problem.
+    None,
+    /// Worth noting, but not necessarily a problem.
+    Note,
+    /// A problem that should be looked at.
+    Warning,
+    /// A serious problem that should block the build.
+    Error,
+}
+
+impl SarifLevel {
+    /// Returns the SARIF string representation of the level.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SarifLevel::None => "none",
+            SarifLevel::Note => "note",
+            SarifLevel::Warning => "warning",
+            SarifLevel::Error => "error",
+        }
+    }
+}
+
+/// Describes a class of findings reported by a tool.
+#[derive(Debug, Clone)]
+pub struct SarifRule {
+    /// Stable identifier of the rule (e.g. "hibp/pwned-password").
+    pub id: String,
+    /// Human readable name of the rule.
+    pub name: String,
+    /// Short description shown by code scanning dashboards.
+    pub short_description: String,
+    /// Optional link to documentation about the rule.
+    pub help_uri: Option<String>,
+}
+
+impl SarifRule {
+    /// Creates a new rule.
+    pub fn new<S: Into<String>>(id: S, name: S, short_description: S) -> Self {
+        SarifRule {
+            id: id.into(),
+            name: name.into(),
+            short_description: short_description.into(),
+            help_uri: None,
+        }
+    }
+
+    /// Sets the documentation link of the rule.
+    #[must_use]
+    pub fn with_help_uri<S: Into<String>>(mut self, help_uri: S) -> Self {
+        self.help_uri = Some(help_uri.into());
+        self
+    }
+
+    /// Rule for passwords found in the Pwned Passwords corpus.
+    pub fn pwned_password() -> Self {
+        SarifRule::new(
+            "hibp/pwned-password",
+            "PwnedPassword",
+            "Password appears in the Pwned Passwords corpus",
+        )
+        .with_help_uri("https://haveibeenpwned.com/Passwords")
+    }
+}
+
+/// A single finding to be reported in a SARIF log.
+///
+/// Messages end up in CI logs and dashboards, so they must never contain the
+/// secret itself.
+#[derive(Debug, Clone)]
+pub struct SarifResult {
+    /// Identifier of the rule that produced the finding.
+    pub rule_id: String,
+    /// Severity of the finding.
+    pub level: SarifLevel,
+    /// Description of the finding.
+    pub message: String,
+    /// Path or URI of the artifact containing the finding, if any.
+    pub uri: Option<String>,
+    /// 1-based line number of the finding within the artifact, if known.
+    pub line: Option<u64>,
+}
+
+impl SarifResult {
+    /// Creates a new result without a location.
+    pub fn new<S: Into<String>>(rule_id: S, level: SarifLevel, message: S) -> Self {
+        SarifResult {
+            rule_id: rule_id.into(),
+            level,
+            message: message.into(),
+            uri: None,
+            line: None,
+        }
+    }
+
+    /// Attaches an artifact location to the result.
+    #[must_use]
+    pub fn with_location<S: Into<String>>(mut self, uri: S, line: Option<u64>) -> Self {
+        self.uri = Some(uri.into());
+        self.line = line;
+        self
+    }
+
+    fn to_json(&self, rule_index: usize) -> Value {
+        let mut result = json!({
+            "ruleId": self.rule_id,
+            "ruleIndex": rule_index,
+            "level": self.level.as_str(),
+            "message": { "text": self.message },
+        });
+
+        if let Some(uri) = &self.uri {
+            let mut physical_location = json!({
+                "artifactLocation": { "uri": uri },
+            });
+            if let Some(line) = self.line {
+                physical_location["region"] = json!({ "startLine": line });
+            }
+            result["locations"] = json!([{ "physicalLocation": physical_location }]);
+        }
+
+        result
+    }
+}
+
+/// A SARIF 2.1.0 log containing a single run of a tool.
+///
+/// The output can be uploaded to GitHub code scanning or any other dashboard
+/// that understands SARIF.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{SarifLevel, SarifLog, SarifResult, SarifRule};
+///
+/// let mut log = SarifLog::new("my-scanner").with_rule(SarifRule::pwned_password());
+/// log.add_result(
+///     SarifResult::new(
+///         "hibp/pwned-password",
+///         SarifLevel::Error,
+///         "Password found 3 times in data breaches",
+///     )
+///     .with_location("config/settings.yml", Some(12)),
+/// );
+/// let sarif = log.to_string_pretty().unwrap();
+/// assert!(sarif.contains("\"version\": \"2.1.0\""));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SarifLog {
+    /// Name of the tool reported in the log.
+    pub tool_name: String,
+    /// Version of the tool reported in the log.
+    pub tool_version: String,
+    /// Documentation or homepage of the tool, omitted from the log if unset.
+    pub information_uri: Option<String>,
+    /// Rules referenced by the results.
+    pub rules: Vec<SarifRule>,
+    /// Findings of the run.
+    pub results: Vec<SarifResult>,
+}
+
+impl SarifLog {
+    /// Creates an empty log for the given tool, versioned as this crate.
+    pub fn new<S: Into<String>>(tool_name: S) -> Self {
+        SarifLog {
+            tool_name: tool_name.into(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            information_uri: None,
+            rules: Vec::new(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Sets the version of the tool reported in the log.
+    #[must_use]
+    pub fn with_tool_version<S: Into<String>>(mut self, tool_version: S) -> Self {
+        self.tool_version = tool_version.into();
+        self
+    }
+
+    /// Sets the documentation or homepage link of the tool reported in the log.
+    #[must_use]
+    pub fn with_information_uri<S: Into<String>>(mut self, information_uri: S) -> Self {
+        self.information_uri = Some(information_uri.into());
+        self
+    }
+
+    /// Registers a rule with the log.
+    #[must_use]
+    pub fn with_rule(mut self, rule: SarifRule) -> Self {
+        self.add_rule(rule);
+        self
+    }
+
+    /// Registers a rule with the log, replacing any rule with the same id.
+    pub fn add_rule(&mut self, rule: SarifRule) {
+        match self.rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule,
+            None => self.rules.push(rule),
+        }
+    }
+
+    /// Adds a finding to the log.
+    ///
+    /// Results referencing a rule that was not registered get a minimal rule entry generated.
+    pub fn add_result(&mut self, result: SarifResult) {
+        if !self.rules.iter().any(|r| r.id == result.rule_id) {
+            let id = result.rule_id.clone();
+            self.rules.push(SarifRule::new(id.clone(), id.clone(), id));
+        }
+        self.results.push(result);
+    }
+
+    /// Builds the SARIF JSON document.
+    ///
+    /// Fails with [`HibpError::InvalidInput`] if a result references a rule that isn't in
+    /// [`SarifLog::rules`], which can only happen if the rules were edited directly.
+    pub fn to_json(&self) -> Result<Value, HibpError> {
+        let rules: Vec<Value> = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let mut value = json!({
+                    "id": rule.id,
+                    "name": rule.name,
+                    "shortDescription": { "text": rule.short_description },
+                });
+                if let Some(help_uri) = &rule.help_uri {
+                    value["helpUri"] = json!(help_uri);
+                }
+                value
+            })
+            .collect();
+
+        let results = self
+            .results
+            .iter()
+            .map(|result| {
+                let rule_index = self
+                    .rules
+                    .iter()
+                    .position(|r| r.id == result.rule_id)
+                    .ok_or_else(|| {
+                        HibpError::InvalidInput(format!(
+                            "SARIF result references unknown rule {}",
+                            result.rule_id
+                        ))
+                    })?;
+                Ok(result.to_json(rule_index))
+            })
+            .collect::<Result<Vec<Value>, HibpError>>()?;
+
+        let mut driver = json!({
+            "name": self.tool_name,
+            "version": self.tool_version,
+            "rules": rules,
+        });
+        if let Some(information_uri) = &self.information_uri {
+            driver["informationUri"] = json!(information_uri);
+        }
+
+        Ok(json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [{
+                "tool": { "driver": driver },
+                "results": results,
+            }]
+        }))
+    }
+
+    /// Serializes the log as pretty-printed JSON.
+    pub fn to_string_pretty(&self) -> Result<String, HibpError> {
+        Ok(serde_json::to_string_pretty(&self.to_json()?)?)
+    }
+
+    /// Writes the log as pretty-printed JSON to the given writer.
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), HibpError> {
+        serde_json::to_writer_pretty(writer, &self.to_json()?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,350 @@
+use crate::event::password_severity;
+use crate::{
+    Event, HaveIBeenPwned, HibpError, MessageCatalog, ResultSink, SarifLevel, SarifResult,
+};
+use regex::Regex;
+use secrecy::{ExposeSecret, SecretString};
+
+/// Default patterns used by [`SecretScanner::new`], as `(name, regex)` pairs.
+///
+/// Each pattern captures the candidate secret in its first capture group.
+const DEFAULT_PATTERNS: &[(&str, &str)] = &[
+    (
+        "password-assignment",
+        r#"(?i)(?:password|passwd|passphrase|pwd)[\w-]*["']?\s*[:=]\s*["']?([^\s"'`,;]+)"#,
+    ),
+    (
+        "secret-assignment",
+        r#"(?i)(?:secret|api_?key|token)[\w-]*["']?\s*[:=]\s*["']?([^\s"'`,;]+)"#,
+    ),
+    (
+        "url-credentials",
+        r#"[a-zA-Z][a-zA-Z0-9+.-]*://[^/\s:@]+:([^/\s@]+)@"#,
+    ),
+];
+
+/// A named regular expression that extracts candidate secrets from text.
+#[derive(Debug, Clone)]
+pub struct SecretPattern {
+    /// Name of the pattern, reported with every finding.
+    pub name: String,
+    regex: Regex,
+}
+
+impl SecretPattern {
+    /// Creates a new pattern.
+    ///
+    /// The candidate secret is taken from the capture group named `secret` if present,
+    /// otherwise from the first capture group.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the pattern.
+    /// * `pattern` - Regular expression with at least one capture group.
+    pub fn new<S: Into<String>>(name: S, pattern: &str) -> Result<Self, HibpError> {
+        let regex = Regex::new(pattern).map_err(|e| HibpError::InvalidInput(e.to_string()))?;
+        if regex.captures_len() < 2 {
+            return Err(HibpError::InvalidInput(
+                "Secret pattern must contain a capture group".into(),
+            ));
+        }
+        Ok(SecretPattern {
+            name: name.into(),
+            regex,
+        })
+    }
+}
+
+/// A candidate secret found in text, before it has been checked.
+#[derive(Clone)]
+pub struct SecretCandidate {
+    /// 1-based line number of the candidate.
+    pub line: u64,
+    /// Name of the pattern that matched.
+    pub pattern: String,
+    /// The candidate secret itself, wiped when dropped.
+    pub secret: SecretString,
+}
+
+impl std::fmt::Debug for SecretCandidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretCandidate")
+            .field("line", &self.line)
+            .field("pattern", &self.pattern)
+            .field("secret", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Extracts candidate credentials from files and strings using configurable patterns.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{ExposeSecret, SecretScanner};
+///
+/// let scanner = SecretScanner::new();
+/// let candidates = scanner.find_candidates("db_password = \"hunter22\"");
+/// assert_eq!(candidates.len(), 1);
+/// assert_eq!(candidates[0].secret.expose_secret(), "hunter22");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SecretScanner {
+    patterns: Vec<SecretPattern>,
+    min_length: usize,
+}
+
+impl Default for SecretScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecretScanner {
+    /// Creates a scanner with the built-in patterns (password/secret assignments and URL credentials).
+    pub fn new() -> Self {
+        let patterns = DEFAULT_PATTERNS
+            .iter()
+            .map(|(name, pattern)| {
+                SecretPattern::new(*name, pattern).expect("built-in secret patterns are valid")
+            })
+            .collect();
+        SecretScanner {
+            patterns,
+            min_length: 6,
+        }
+    }
+
+    /// Creates a scanner without any patterns.
+    pub fn empty() -> Self {
+        SecretScanner {
+            patterns: Vec::new(),
+            min_length: 6,
+        }
+    }
+
+    /// Adds a custom pattern to the scanner.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the pattern, reported with every finding.
+    /// * `pattern` - Regular expression with at least one capture group.
+    pub fn with_pattern<S: Into<String>>(
+        mut self,
+        name: S,
+        pattern: &str,
+    ) -> Result<Self, HibpError> {
+        self.patterns.push(SecretPattern::new(name, pattern)?);
+        Ok(self)
+    }
+
+    /// Sets the minimum length a candidate must have to be reported. Defaults to 6.
+    #[must_use]
+    pub fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Finds all candidate secrets in the given text without checking them.
+    pub fn find_candidates(&self, text: &str) -> Vec<SecretCandidate> {
+        let mut candidates = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            for pattern in &self.patterns {
+                for captures in pattern.regex.captures_iter(line) {
+                    let secret = captures
+                        .name("secret")
+                        .or_else(|| captures.get(1))
+                        .map(|m| m.as_str())
+                        .unwrap_or_default();
+                    if secret.chars().count() < self.min_length {
+                        continue;
+                    }
+                    candidates.push(SecretCandidate {
+                        line: index as u64 + 1,
+                        pattern: pattern.name.clone(),
+                        secret: SecretString::from(secret),
+                    });
+                }
+            }
+        }
+        candidates
+    }
+}
+
+/// A candidate secret that was found in the Pwned Passwords corpus.
+///
+/// Findings never contain the secret itself, so they are safe to log or publish.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ScanFinding {
+    /// Name of the scanned file or string.
+    pub source: String,
+    /// 1-based line number of the finding.
+    pub line: u64,
+    /// Name of the pattern that matched.
+    pub pattern: String,
+    /// Number of times the secret appears in the Pwned Passwords corpus.
+    pub count: u64,
+}
+
+impl ScanFinding {
+    /// Converts the finding into a SARIF result with an English message.
+    pub fn to_sarif_result(&self) -> SarifResult {
+        self.to_sarif_result_with(&MessageCatalog::english())
+    }
+
+    /// Converts the finding into a SARIF result, taking the message from the given catalog.
+    pub fn to_sarif_result_with(&self, catalog: &MessageCatalog) -> SarifResult {
+        SarifResult::new(
+            "hibp/pwned-password".to_string(),
+            SarifLevel::Error,
+            catalog.format(
+                "report.scan.breached",
+                &[("pattern", &self.pattern), ("count", &self.count)],
+            ),
+        )
+        .with_location(self.source.clone(), Some(self.line))
+    }
+
+    /// Converts the finding into a `secret-exposed` event with an English message.
+    pub fn to_event(&self) -> Event {
+        Event::new(
+            "secret-exposed",
+            password_severity(self.count),
+            MessageCatalog::english().format(
+                "report.scan.breached",
+                &[("pattern", &self.pattern), ("count", &self.count)],
+            ),
+        )
+        .with_count(self.count)
+        .with_location(format!("{}:{}", self.source, self.line))
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Scans text for candidate secrets and checks each one against Pwned Passwords.
+    ///
+    /// Candidates are checked with k-Anonymity and padding, so secrets never leave the machine.
+    /// Like [`HaveIBeenPwned::check_passwords`], they are grouped by hash prefix, so every
+    /// range is fetched only once. Only candidates found in the corpus are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `scanner` - The scanner holding the patterns to use.
+    /// * `source` - Name of the scanned content, reported with every finding.
+    /// * `text` - The content to scan.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{HaveIBeenPwned, SecretScanner};
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let scanner = SecretScanner::new();
+    /// let findings = hibp
+    ///     .scan_text(&scanner, "settings.ini", "password=password123")
+    ///     .await
+    ///     .unwrap();
+    /// for finding in findings {
+    ///     println!("{}:{} pwned {} times", finding.source, finding.line, finding.count);
+    /// }
+    /// # }
+    /// ```
+    pub async fn scan_text(
+        &self,
+        scanner: &SecretScanner,
+        source: &str,
+        text: &str,
+    ) -> Result<Vec<ScanFinding>, HibpError> {
+        let mut findings = Vec::new();
+        self.scan_text_into(scanner, source, text, &mut findings)
+            .await?;
+        Ok(findings)
+    }
+
+    /// Scans text for candidate secrets, writing the findings to a sink.
+    ///
+    /// Like [`HaveIBeenPwned::scan_text`], but findings are not collected in memory, so
+    /// many files can be scanned into one report. The sink is flushed but not closed.
+    /// Returns the number of findings.
+    ///
+    /// # Arguments
+    ///
+    /// * `scanner` - The scanner holding the patterns to use.
+    /// * `source` - Name of the scanned content, reported with every finding.
+    /// * `text` - The content to scan.
+    /// * `sink` - Destination of the findings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::{HaveIBeenPwned, NdjsonSink, ResultSink, SecretScanner};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let scanner = SecretScanner::new();
+    /// let mut sink = NdjsonSink::new(std::io::stdout());
+    /// for path in ["settings.ini", ".env"] {
+    ///     let text = std::fs::read_to_string(path)?;
+    ///     hibp.scan_text_into(&scanner, path, &text, &mut sink).await?;
+    /// }
+    /// sink.close().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn scan_text_into<S: ResultSink<ScanFinding>>(
+        &self,
+        scanner: &SecretScanner,
+        source: &str,
+        text: &str,
+        sink: &mut S,
+    ) -> Result<usize, HibpError> {
+        let candidates = scanner.find_candidates(text);
+        let secrets: Vec<&str> = candidates
+            .iter()
+            .map(|candidate| candidate.secret.expose_secret())
+            .collect();
+        let counts = self.check_passwords(&secrets).await?;
+        let mut findings = 0;
+
+        for (candidate, count) in candidates.iter().zip(counts) {
+            if count > 0 {
+                findings += 1;
+                sink.write(ScanFinding {
+                    source: source.to_string(),
+                    line: candidate.line,
+                    pattern: candidate.pattern.clone(),
+                    count,
+                })
+                .await?;
+            }
+        }
+        sink.flush().await?;
+
+        Ok(findings)
+    }
+
+    /// Scans a file for candidate secrets and checks each one against Pwned Passwords.
+    ///
+    /// Files that are not valid UTF-8 are skipped and yield no findings.
+    /// Not available on WASM targets, which have no file system.
+    ///
+    /// # Arguments
+    ///
+    /// * `scanner` - The scanner holding the patterns to use.
+    /// * `path` - Path of the file to scan.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn scan_file<P: AsRef<std::path::Path>>(
+        &self,
+        scanner: &SecretScanner,
+        path: P,
+    ) -> Result<Vec<ScanFinding>, HibpError> {
+        let path = path.as_ref();
+        let bytes = tokio::fs::read(path).await?;
+        match String::from_utf8(bytes) {
+            Ok(text) => {
+                self.scan_text(scanner, &path.display().to_string(), &text)
+                    .await
+            }
+            Err(_) => Ok(vec![]),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::HibpError;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Signature of migration steps: upgrades a stored document by one schema version.
+type MigrationStep<T> = dyn Fn(&mut T) -> Result<(), HibpError> + Send + Sync;
+
+/// Steps that upgrade persisted data written by older versions of this crate.
+///
+/// Persisted state and caches are stamped with a schema version when written, e.g.
+/// [`crate::AuditState::SCHEMA_VERSION`]. When older data is loaded, the step registered
+/// for each version from the stored one up to the current one runs in order, so months of
+/// monitor history survive an upgrade instead of failing to parse or being discarded.
+/// Data from a newer version of the crate is rejected rather than misread.
+///
+/// The crate registers a step for every schema change it makes. Register your own steps
+/// to override them, e.g. to migrate state that a fork or a script of yours modified.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{AuditState, Migrations};
+///
+/// # fn example() -> Result<(), hibp_rs::HibpError> {
+/// // State files from version 0 kept accounts in mixed case
+/// let migrations = AuditState::migrations().with_step(0, |state: &mut serde_json::Value| {
+///     if let Some(first_seen) = state.get_mut("first_seen").and_then(|v| v.as_object_mut()) {
+///         let entries = std::mem::take(first_seen);
+///         first_seen.extend(entries.into_iter().map(|(k, v)| (k.to_lowercase(), v)));
+///     }
+///     Ok(())
+/// });
+/// let state = AuditState::load_with(".hibp-state.json", &migrations)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Migrations<T> {
+    steps: BTreeMap<u32, Arc<MigrationStep<T>>>,
+}
+
+impl<T> Migrations<T> {
+    /// Creates a set of migrations without steps.
+    pub fn new() -> Self {
+        Migrations {
+            steps: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the step upgrading data from schema version `from` to `from + 1`,
+    /// replacing any step registered for that version before.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The schema version the step upgrades from.
+    /// * `step` - Rewrites the stored data in place, or fails if it can't be upgraded.
+    #[must_use]
+    pub fn with_step<F>(mut self, from: u32, step: F) -> Self
+    where
+        F: Fn(&mut T) -> Result<(), HibpError> + Send + Sync + 'static,
+    {
+        self.steps.insert(from, Arc::new(step));
+        self
+    }
+
+    /// Upgrades data stored with schema version `from` to version `to`.
+    ///
+    /// Versions without a registered step need no changes to their data.
+    pub(crate) fn migrate(&self, data: &mut T, from: u32, to: u32) -> Result<(), HibpError> {
+        check_supported(from, to)?;
+        for (_, step) in self.steps.range(from..to) {
+            step(data)?;
+        }
+        Ok(())
+    }
+}
+
+/// Rejects data stored by a newer version of the crate than this one supports.
+pub(crate) fn check_supported(version: u32, supported: u32) -> Result<(), HibpError> {
+    if version > supported {
+        return Err(HibpError::InvalidInput(format!(
+            "Stored schema version {} is newer than the supported version {}; \
+             upgrade hibp_rs to read it",
+            version, supported
+        )));
+    }
+    Ok(())
+}
+
+impl<T> Default for Migrations<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Migrations<T> {
+    fn clone(&self) -> Self {
+        Migrations {
+            steps: self.steps.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Migrations<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Migrations")
+            .field("versions", &self.steps.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
@@ -0,0 +1,48 @@
+use crate::HaveIBeenPwned;
+use std::sync::OnceLock;
+
+/// Process-wide client shared by [`static_client`] and [`static_client_with`].
+static STATIC_CLIENT: OnceLock<HaveIBeenPwned> = OnceLock::new();
+
+/// Returns a process-wide client that is created once and reused by every caller.
+///
+/// On first use the client is built from the `HIBP_API_KEY` environment variable with a lazy
+/// rate limit (see [`HaveIBeenPwned::new_with_lazy_rate_limit`]). Warm serverless invocations
+/// (Lambda, Cloud Functions, ...) reuse its connection pool and subscription lookup instead of
+/// paying for them on every request.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn handler() -> Result<(), Box<dyn std::error::Error>> {
+/// let count = hibp_rs::static_client().check_password("password123").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn static_client() -> &'static HaveIBeenPwned {
+    static_client_with(|| {
+        let api_key = std::env::var("HIBP_API_KEY").unwrap_or_default();
+        HaveIBeenPwned::new_with_lazy_rate_limit(api_key)
+    })
+}
+
+/// Returns the process-wide client, creating it with `init` on first use.
+///
+/// Once the client exists, later calls return it and `init` is not run.
+///
+/// # Arguments
+///
+/// * `init` - Builds the client if it does not exist yet.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::HaveIBeenPwned;
+///
+/// let hibp = hibp_rs::static_client_with(|| {
+///     HaveIBeenPwned::new_with_lazy_rate_limit("your_api_key").with_user_agent("my-lambda/1.0")
+/// });
+/// ```
+pub fn static_client_with<F: FnOnce() -> HaveIBeenPwned>(init: F) -> &'static HaveIBeenPwned {
+    STATIC_CLIENT.get_or_init(init)
+}
@@ -0,0 +1,106 @@
+use crate::{CompactPwnedPassword, HibpError, PwnedPassword};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// Calls in flight, shared by all clones of a client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InFlight {
+    /// HIBP API requests, by URL and extra headers
+    #[cfg(feature = "full")]
+    pub(crate) api: SingleFlight<crate::ApiResponse>,
+    /// Parsed Pwned Passwords ranges, by URL and padding
+    pub(crate) ranges: SingleFlight<Vec<PwnedPassword>>,
+    /// Compactly parsed Pwned Passwords ranges, by URL and padding
+    pub(crate) compact_ranges: SingleFlight<Vec<CompactPwnedPassword>>,
+}
+
+/// Shares the result of identical concurrent calls, so only the first one sends a request.
+///
+/// Callers arriving while a call with the same key is in flight wait for its result
+/// instead. Errors are not shared: if the first call fails or is cancelled, each waiting
+/// caller makes its own call, so retries and error details stay per caller.
+#[derive(Debug)]
+pub(crate) struct SingleFlight<T> {
+    calls: Arc<Mutex<HashMap<String, watch::Receiver<Option<T>>>>>,
+}
+
+impl<T> Clone for SingleFlight<T> {
+    fn clone(&self) -> Self {
+        SingleFlight {
+            calls: Arc::clone(&self.calls),
+        }
+    }
+}
+
+impl<T> Default for SingleFlight<T> {
+    fn default() -> Self {
+        SingleFlight {
+            calls: Arc::default(),
+        }
+    }
+}
+
+impl<T: Clone> SingleFlight<T> {
+    /// Runs `call`, unless a call with the same key is in flight, and returns its result.
+    pub(crate) async fn run<F, Fut>(&self, key: String, call: F) -> Result<T, HibpError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, HibpError>>,
+    {
+        let joined = {
+            let Ok(mut calls) = self.calls.lock() else {
+                return call().await;
+            };
+            match calls.get(&key) {
+                Some(receiver) => Err(receiver.clone()),
+                None => {
+                    let (sender, receiver) = watch::channel(None);
+                    calls.insert(key.clone(), receiver);
+                    Ok(sender)
+                }
+            }
+        };
+
+        match joined {
+            Ok(sender) => {
+                // Dropped before the sender, so later callers start a new call
+                let _leader = Leader {
+                    calls: &self.calls,
+                    key,
+                };
+                let result = call().await;
+                if let Ok(value) = &result {
+                    sender.send_replace(Some(value.clone()));
+                }
+                result
+            }
+            Err(mut receiver) => {
+                let shared = receiver
+                    .wait_for(Option::is_some)
+                    .await
+                    .ok()
+                    .and_then(|value| value.clone());
+                match shared {
+                    Some(value) => Ok(value),
+                    None => call().await,
+                }
+            }
+        }
+    }
+}
+
+/// Removes a call from the calls in flight when it finishes or is cancelled.
+struct Leader<'a, T> {
+    calls: &'a Mutex<HashMap<String, watch::Receiver<Option<T>>>>,
+    key: String,
+}
+
+impl<T> Drop for Leader<'_, T> {
+    fn drop(&mut self) {
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.remove(&self.key);
+        }
+    }
+}
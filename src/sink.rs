@@ -0,0 +1,181 @@
+use crate::HibpError;
+use std::io::Write;
+use std::marker::PhantomData;
+
+/// A destination that receives findings one at a time while a long job runs.
+///
+/// Bulk audits write each finding as soon as it is known, so results reach durable
+/// output incrementally instead of being collected in memory. Call
+/// [`ResultSink::close`] when the job is done.
+///
+/// Implementations are provided for CSV ([`CsvSink`]), JSON lines ([`NdjsonSink`]),
+/// tokio channels ([`ChannelSink`]) and `Vec<T>`.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{HibpError, ResultSink};
+///
+/// struct Count(usize);
+///
+/// impl<T> ResultSink<T> for Count {
+///     async fn write(&mut self, _finding: T) -> Result<(), HibpError> {
+///         self.0 += 1;
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait ResultSink<T> {
+    /// Writes one finding.
+    fn write(&mut self, finding: T) -> impl Future<Output = Result<(), HibpError>>;
+
+    /// Pushes buffered findings to the underlying output.
+    fn flush(&mut self) -> impl Future<Output = Result<(), HibpError>> {
+        async { Ok(()) }
+    }
+
+    /// Flushes and finishes the output. No findings may be written afterwards.
+    fn close(&mut self) -> impl Future<Output = Result<(), HibpError>> {
+        self.flush()
+    }
+}
+
+impl<T> ResultSink<T> for Vec<T> {
+    async fn write(&mut self, finding: T) -> Result<(), HibpError> {
+        self.push(finding);
+        Ok(())
+    }
+}
+
+/// Writes findings as CSV rows with a header row taken from the field names.
+///
+/// Findings must serialize to flat records, like [`crate::LegacyAuditEntry`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use hibp_rs::{CsvSink, HaveIBeenPwned, LegacyHashRecord, ResultSink};
+/// # async fn example(records: Vec<LegacyHashRecord>) -> Result<(), Box<dyn std::error::Error>> {
+/// # let hibp = HaveIBeenPwned::new("your_api_key");
+/// let mut sink = CsvSink::new(std::fs::File::create("reset.csv")?);
+/// hibp.audit_legacy_hashes_into(&records, &mut sink).await?;
+/// sink.close().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CsvSink<T, W: Write> {
+    writer: csv::Writer<W>,
+    findings: PhantomData<fn(T)>,
+}
+
+impl<T, W: Write> CsvSink<T, W> {
+    /// Creates a sink writing CSV to any [`Write`] implementation.
+    pub fn new(writer: W) -> Self {
+        CsvSink {
+            writer: csv::Writer::from_writer(writer),
+            findings: PhantomData,
+        }
+    }
+}
+
+impl<T: serde::Serialize, W: Write> ResultSink<T> for CsvSink<T, W> {
+    async fn write(&mut self, finding: T) -> Result<(), HibpError> {
+        self.writer.serialize(finding).map_err(HibpError::other)?;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), HibpError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Writes findings as newline-delimited JSON, one object per line.
+#[derive(Debug)]
+pub struct NdjsonSink<T, W: Write> {
+    writer: W,
+    findings: PhantomData<fn(T)>,
+}
+
+impl<T, W: Write> NdjsonSink<T, W> {
+    /// Creates a sink writing JSON lines to any [`Write`] implementation.
+    ///
+    /// Wrap unbuffered writers like files in a [`std::io::BufWriter`].
+    pub fn new(writer: W) -> Self {
+        NdjsonSink {
+            writer,
+            findings: PhantomData,
+        }
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<T: serde::Serialize, W: Write> ResultSink<T> for NdjsonSink<T, W> {
+    async fn write(&mut self, finding: T) -> Result<(), HibpError> {
+        serde_json::to_writer(&mut self.writer, &finding)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), HibpError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Sends findings to a tokio channel, e.g. to process them on another task.
+///
+/// Writing waits while the channel is full, so a slow consumer slows the job down instead
+/// of findings piling up in memory. Closing the sink closes the channel.
+///
+/// # Example
+///
+/// ```
+/// # use hibp_rs::{ChannelSink, ResultSink};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+/// let mut sink = ChannelSink::new(tx);
+/// sink.write("finding").await?;
+/// sink.close().await?;
+/// assert_eq!(rx.recv().await, Some("finding"));
+/// assert_eq!(rx.recv().await, None);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ChannelSink<T> {
+    sender: Option<tokio::sync::mpsc::Sender<T>>,
+}
+
+impl<T> ChannelSink<T> {
+    /// Creates a sink sending findings to the channel.
+    pub fn new(sender: tokio::sync::mpsc::Sender<T>) -> Self {
+        ChannelSink {
+            sender: Some(sender),
+        }
+    }
+}
+
+impl<T> ResultSink<T> for ChannelSink<T> {
+    async fn write(&mut self, finding: T) -> Result<(), HibpError> {
+        let sender = self
+            .sender
+            .as_ref()
+            .ok_or_else(|| HibpError::other("Result sink is closed"))?;
+        sender
+            .send(finding)
+            .await
+            .map_err(|_| HibpError::other("Result receiver was dropped"))?;
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<(), HibpError> {
+        self.sender = None;
+        Ok(())
+    }
+}
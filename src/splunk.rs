@@ -0,0 +1,209 @@
+use crate::{Event, HibpError, Notification, Notifier, runtime};
+use reqwest::Client;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Sends events to a Splunk HTTP Event Collector (HEC) in batches.
+///
+/// Events are buffered and posted once `batch_size` events are queued, or on
+/// [`SplunkHecSink::flush`]. Failed posts (network errors, 429 and 5xx responses) are
+/// retried with exponential backoff. Only one batch is in flight at a time, and callers
+/// of [`SplunkHecSink::send`] wait while a full batch is delivered, so a slow collector
+/// slows producers down instead of growing the buffer without bound.
+///
+/// Each event body is the ECS document from [`Event::to_ecs_json`].
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::SplunkHecSink;
+///
+/// # async fn example(findings: Vec<hibp_rs::ExportAuditEntry>) -> Result<(), Box<dyn std::error::Error>> {
+/// let sink = SplunkHecSink::new("https://splunk.example.com:8088", "your-hec-token")
+///     .with_index("security");
+/// for finding in findings.iter().filter(|f| f.is_exposed()) {
+///     sink.send(finding.to_event()).await?;
+/// }
+/// sink.flush().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SplunkHecSink {
+    url: String,
+    token: String,
+    index: Option<String>,
+    sourcetype: String,
+    batch_size: usize,
+    max_retries: u32,
+    retry_delay: Duration,
+    client: Client,
+    buffer: Mutex<Vec<Event>>,
+    in_flight: Mutex<()>,
+}
+
+impl std::fmt::Debug for SplunkHecSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SplunkHecSink")
+            .field("url", &self.url)
+            .field("token", &"<redacted>")
+            .field("index", &self.index)
+            .field("sourcetype", &self.sourcetype)
+            .field("batch_size", &self.batch_size)
+            .field("max_retries", &self.max_retries)
+            .field("retry_delay", &self.retry_delay)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SplunkHecSink {
+    /// Creates a sink posting to the collector at the given base URL.
+    ///
+    /// Batches hold 100 events and failed posts are retried 3 times, starting after 500ms.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - Base URL of the collector, e.g. `https://splunk.example.com:8088`.
+    /// * `token` - HEC token.
+    pub fn new<U: Into<String>, T: Into<String>>(base_url: U, token: T) -> Self {
+        let base_url = base_url.into();
+        SplunkHecSink {
+            url: format!(
+                "{}/services/collector/event",
+                base_url.trim_end_matches('/')
+            ),
+            token: token.into(),
+            index: None,
+            sourcetype: "hibp:finding".to_string(),
+            batch_size: 100,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(500),
+            client: Client::new(),
+            buffer: Mutex::new(Vec::new()),
+            in_flight: Mutex::new(()),
+        }
+    }
+
+    /// Sets the index events are written to, instead of the token's default index.
+    #[must_use]
+    pub fn with_index<S: Into<String>>(mut self, index: S) -> Self {
+        self.index = Some(index.into());
+        self
+    }
+
+    /// Sets the sourcetype of the events. Defaults to `hibp:finding`.
+    #[must_use]
+    pub fn with_sourcetype<S: Into<String>>(mut self, sourcetype: S) -> Self {
+        self.sourcetype = sourcetype.into();
+        self
+    }
+
+    /// Sets how many events are sent per request. Values below 1 are treated as 1.
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets how often a failed post is retried and the delay before the first retry.
+    ///
+    /// The delay doubles with every further retry.
+    #[must_use]
+    pub fn with_retries(mut self, max_retries: u32, retry_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Queues an event, delivering the batch once it is full.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to send.
+    pub async fn send(&self, event: Event) -> Result<(), HibpError> {
+        let full = {
+            let mut buffer = self.buffer.lock().await;
+            buffer.push(event);
+            buffer.len() >= self.batch_size
+        };
+        if full {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Delivers all queued events.
+    ///
+    /// If delivery fails after all retries, the events are dropped and the error returned.
+    pub async fn flush(&self) -> Result<(), HibpError> {
+        let _in_flight = self.in_flight.lock().await;
+        let events = std::mem::take(&mut *self.buffer.lock().await);
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let body = self.payload(&events)?;
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .client
+                .post(&self.url)
+                .header(
+                    reqwest::header::AUTHORIZATION,
+                    format!("Splunk {}", self.token),
+                )
+                .body(body.clone())
+                .send()
+                .await;
+
+            let retryable = match result {
+                Ok(resp) if resp.status().is_success() => return Ok(()),
+                Ok(resp) => {
+                    let status = resp.status();
+                    if status.as_u16() != 429 && !status.is_server_error() {
+                        return Err(HibpError::other(format!(
+                            "Splunk HEC request failed with status: {}",
+                            status
+                        )));
+                    }
+                    format!("Splunk HEC request failed with status: {}", status)
+                }
+                Err(e) => e.to_string(),
+            };
+
+            if attempt >= self.max_retries {
+                return Err(HibpError::other(retryable));
+            }
+            attempt += 1;
+            runtime::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+
+    /// Builds the HEC request body: one JSON object per event, concatenated.
+    pub(crate) fn payload(&self, events: &[Event]) -> Result<String, HibpError> {
+        let mut body = String::new();
+        for event in events {
+            let mut entry = serde_json::json!({
+                "source": "hibp_rs",
+                "sourcetype": self.sourcetype,
+                "event": event.to_ecs_json(),
+            });
+            if let Some(index) = &self.index {
+                entry["index"] = index.clone().into();
+            }
+            body.push_str(&serde_json::to_string(&entry)?);
+            body.push('\n');
+        }
+        Ok(body)
+    }
+}
+
+impl Notifier for SplunkHecSink {
+    async fn notify(&self, notification: &Notification) -> Result<(), HibpError> {
+        for event in notification.events() {
+            self.send(event).await?;
+        }
+        self.flush().await
+    }
+}
@@ -0,0 +1,264 @@
+use crate::cache::unix_now;
+use crate::schema;
+use crate::{
+    AuditState, CacheSerializer, CacheStore, CachedResponse, HibpError, JsonCacheSerializer,
+    Migrations, StateStore,
+};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long a write waits for another process holding the database lock.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Keeps cached responses and audit state in one SQLite database, giving single-node
+/// deployments durable caching and monitor state without running a server.
+///
+/// Responses are rows of the `cache` table, keyed by request URL and encoded with a
+/// [`CacheSerializer`], JSON unless set with [`SqliteStore::with_serializer`]. Expired
+/// rows are removed when written. The [`AuditState`] is a JSON document in the `state`
+/// table, upgraded from older schema versions like [`AuditState::load`] does. Share the
+/// store between the client and your audit code through an [`Arc`].
+///
+/// Responses for account lookups reveal which breaches an account is in, so on Unix new
+/// database files are created readable by their owner only. Calls block, so the client
+/// makes them on tokio's blocking pool, see [`CacheStore::is_blocking`].
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{HaveIBeenPwned, SqliteStore, StateStore};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// # async fn example() -> Result<(), hibp_rs::HibpError> {
+/// let store = Arc::new(SqliteStore::open("hibp.sqlite3")?);
+/// let hibp = HaveIBeenPwned::new("your_api_key").with_cache_store(store.clone(), Duration::from_secs(3600));
+///
+/// let mut state = store.load_state()?;
+/// let since = state.start_run();
+/// let breaches = hibp.get_breaches_for_account_since("test@example.com", since).await?;
+/// store.save_state(&state)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SqliteStore {
+    connection: Mutex<Connection>,
+    serializer: Arc<dyn CacheSerializer>,
+    migrations: Migrations<serde_json::Value>,
+}
+
+impl SqliteStore {
+    /// Schema version of the database tables, kept in `PRAGMA user_version`.
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    /// Opens a database file, creating it and its tables if needed.
+    ///
+    /// Databases of newer schema versions are rejected with [`HibpError::InvalidInput`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The database file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, HibpError> {
+        let path = path.as_ref();
+        create_private(path)?;
+        Self::setup(Connection::open(path).map_err(HibpError::other)?)
+    }
+
+    /// Opens a database that only lives as long as the store, e.g. for tests.
+    pub fn open_in_memory() -> Result<Self, HibpError> {
+        Self::setup(Connection::open_in_memory().map_err(HibpError::other)?)
+    }
+
+    fn setup(connection: Connection) -> Result<Self, HibpError> {
+        connection
+            .busy_timeout(BUSY_TIMEOUT)
+            .map_err(HibpError::other)?;
+        let version: u32 = connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(HibpError::other)?;
+        schema::check_supported(version, Self::SCHEMA_VERSION)?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS cache (
+                     key TEXT PRIMARY KEY,
+                     expires INTEGER NOT NULL,
+                     value BLOB NOT NULL
+                 );
+                 CREATE INDEX IF NOT EXISTS cache_expires ON cache (expires);
+                 CREATE TABLE IF NOT EXISTS state (
+                     id INTEGER PRIMARY KEY CHECK (id = 0),
+                     document BLOB NOT NULL
+                 );",
+            )
+            .map_err(HibpError::other)?;
+        connection
+            .pragma_update(None, "user_version", Self::SCHEMA_VERSION)
+            .map_err(HibpError::other)?;
+        Ok(SqliteStore {
+            connection: Mutex::new(connection),
+            serializer: Arc::new(JsonCacheSerializer),
+            migrations: AuditState::migrations(),
+        })
+    }
+
+    /// Encodes responses with another serializer, e.g. to encrypt them.
+    ///
+    /// Rows written with another serializer can't be read and are fetched again.
+    ///
+    /// # Arguments
+    ///
+    /// * `serializer` - Encodes the stored responses.
+    #[must_use]
+    pub fn with_serializer<S: CacheSerializer + 'static>(mut self, serializer: S) -> Self {
+        self.serializer = Arc::new(serializer);
+        self
+    }
+
+    /// Upgrades saved audit state of older schema versions with custom migrations
+    /// instead of [`AuditState::migrations`].
+    ///
+    /// # Arguments
+    ///
+    /// * `migrations` - Steps upgrading the JSON document of older versions.
+    #[must_use]
+    pub fn with_state_migrations(mut self, migrations: Migrations<serde_json::Value>) -> Self {
+        self.migrations = migrations;
+        self
+    }
+
+    /// Runs statements on the connection, ignoring failures like other cache stores do.
+    fn with_connection<T>(
+        &self,
+        statements: impl FnOnce(&mut Connection) -> rusqlite::Result<T>,
+    ) -> Option<T> {
+        let mut connection = self.connection.lock().ok()?;
+        statements(&mut connection).ok()
+    }
+
+    /// Runs statements on the connection, for state that must not be lost silently.
+    fn try_with_connection<T>(
+        &self,
+        statements: impl FnOnce(&mut Connection) -> rusqlite::Result<T>,
+    ) -> Result<T, HibpError> {
+        let mut connection = self
+            .connection
+            .lock()
+            .map_err(|_| HibpError::other("SQLite connection poisoned by a panic"))?;
+        statements(&mut connection).map_err(HibpError::other)
+    }
+}
+
+/// Creates an empty database file that only its owner can read on Unix, unless it exists.
+fn create_private(path: &Path) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    match options.open(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(()),
+        result => result.map(drop),
+    }
+}
+
+impl CacheStore for SqliteStore {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let value: Vec<u8> = self.with_connection(|connection| {
+            connection
+                .query_row(
+                    "SELECT value FROM cache WHERE key = ?1 AND expires > ?2",
+                    params![key, unix_now()],
+                    |row| row.get(0),
+                )
+                .optional()
+        })??;
+        self.serializer.deserialize(&value).ok()
+    }
+
+    fn put(&self, key: &str, response: CachedResponse, ttl: Duration) {
+        let Ok(value) = self.serializer.serialize(&response) else {
+            return;
+        };
+        let now = unix_now();
+        // SQLite integers are signed
+        let expires = now
+            .saturating_add(ttl.as_millis() as u64)
+            .min(i64::MAX as u64);
+        self.with_connection(|connection| {
+            let transaction = connection.transaction()?;
+            transaction.execute("DELETE FROM cache WHERE expires <= ?1", params![now])?;
+            transaction.execute(
+                "INSERT OR REPLACE INTO cache (key, expires, value) VALUES (?1, ?2, ?3)",
+                params![key, expires, value],
+            )?;
+            transaction.commit()
+        });
+    }
+
+    fn invalidate(&self, key: &str) {
+        self.with_connection(|connection| {
+            connection.execute("DELETE FROM cache WHERE key = ?1", params![key])
+        });
+    }
+
+    fn invalidate_matching(&self, matches: &dyn Fn(&str) -> bool) {
+        self.with_connection(|connection| {
+            let transaction = connection.transaction()?;
+            let keys: Vec<String> = transaction
+                .prepare("SELECT key FROM cache")?
+                .query_map([], |row| row.get(0))?
+                .filter_map(Result::ok)
+                .filter(|key: &String| matches(key))
+                .collect();
+            for key in keys {
+                transaction.execute("DELETE FROM cache WHERE key = ?1", params![key])?;
+            }
+            transaction.commit()
+        });
+    }
+
+    fn clear(&self) {
+        self.with_connection(|connection| connection.execute("DELETE FROM cache", []));
+    }
+
+    fn is_blocking(&self) -> bool {
+        true
+    }
+}
+
+impl StateStore for SqliteStore {
+    fn load_state(&self) -> Result<AuditState, HibpError> {
+        let document: Option<Vec<u8>> = self.try_with_connection(|connection| {
+            connection
+                .query_row("SELECT document FROM state WHERE id = 0", [], |row| {
+                    row.get(0)
+                })
+                .optional()
+        })?;
+        match document {
+            Some(document) => AuditState::from_json(&document, &self.migrations),
+            None => Ok(AuditState::default()),
+        }
+    }
+
+    fn save_state(&self, state: &AuditState) -> Result<(), HibpError> {
+        let document = state.to_json()?;
+        self.try_with_connection(|connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO state (id, document) VALUES (0, ?1)",
+                params![document],
+            )
+        })?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for SqliteStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteStore")
+            .field("serializer", &self.serializer)
+            .field("migrations", &self.migrations)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Cumulative request statistics of a client, shared by all of its clones.
+///
+/// Returned by [`crate::HaveIBeenPwned::stats`]. Useful to find out why a job is slow
+/// (throttling, rate limiting, large responses) without wiring up external metrics.
+///
+/// # Example
+///
+/// ```no_run
+/// # use hibp_rs::HaveIBeenPwned;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let hibp = HaveIBeenPwned::new_with_rate_limit("your_api_key", 10);
+/// hibp.get_breaches_for_account("test@example.com").await?;
+///
+/// let stats = hibp.stats();
+/// println!(
+///     "{} requests, {} bytes, {:?} throttled",
+///     stats.total_requests(),
+///     stats.bytes_received,
+///     stats.throttle_wait
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientStats {
+    /// Number of requests sent, keyed by endpoint (e.g. `breachedaccount`, `range`).
+    pub requests_by_endpoint: HashMap<String, u64>,
+    /// Number of failed requests, keyed by class (`network`, `rate_limited`, `client_error`,
+    /// `server_error`, `too_large`, `blocked`, `budget` or `decode`). Not-found responses are
+    /// not counted as errors.
+    pub errors_by_class: HashMap<String, u64>,
+    /// Number of responses with status 429 Too Many Requests.
+    pub rate_limited: u64,
+    /// Number of requests repeated by the client's [`crate::RetryPolicy`].
+    pub retries: u64,
+    /// Total size of all response bodies, in bytes.
+    pub bytes_received: u64,
+    /// Total time spent waiting for the client-side rate limiter.
+    pub throttle_wait: Duration,
+}
+
+impl ClientStats {
+    /// Total number of requests sent across all endpoints.
+    pub fn total_requests(&self) -> u64 {
+        self.requests_by_endpoint.values().sum()
+    }
+
+    /// Total number of failed requests across all error classes.
+    pub fn total_errors(&self) -> u64 {
+        self.errors_by_class.values().sum()
+    }
+}
+
+/// Shared, thread-safe recorder behind [`ClientStats`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatsRecorder {
+    stats: Arc<Mutex<ClientStats>>,
+}
+
+impl StatsRecorder {
+    /// Returns a copy of the current totals.
+    pub(crate) fn snapshot(&self) -> ClientStats {
+        self.lock().clone()
+    }
+
+    /// Records a request sent to the given endpoint.
+    pub(crate) fn record_request(&self, endpoint: &str) {
+        *self
+            .lock()
+            .requests_by_endpoint
+            .entry(endpoint.to_string())
+            .or_default() += 1;
+    }
+
+    /// Records a failed request of the given class.
+    pub(crate) fn record_error(&self, class: &str) {
+        *self
+            .lock()
+            .errors_by_class
+            .entry(class.to_string())
+            .or_default() += 1;
+    }
+
+    /// Records an unsuccessful HTTP status, classifying it by status code.
+    ///
+    /// 404 responses mean "nothing found" for most endpoints and are not recorded.
+    pub(crate) fn record_status(&self, status: reqwest::StatusCode) {
+        if status.as_u16() == 429 {
+            self.lock().rate_limited += 1;
+            self.record_error("rate_limited");
+        } else if status.is_server_error() {
+            self.record_error("server_error");
+        } else if status.is_client_error() && status.as_u16() != 404 {
+            self.record_error("client_error");
+        }
+    }
+
+    /// Records a request being repeated after a failure.
+    #[cfg(feature = "full")]
+    pub(crate) fn record_retry(&self) {
+        self.lock().retries += 1;
+    }
+
+    /// Records the size of a response body.
+    pub(crate) fn record_bytes(&self, bytes: usize) {
+        self.lock().bytes_received += bytes as u64;
+    }
+
+    /// Records time spent waiting for the rate limiter.
+    #[cfg(feature = "full")]
+    pub(crate) fn record_throttle_wait(&self, wait: Duration) {
+        self.lock().throttle_wait += wait;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ClientStats> {
+        // Statistics stay usable even if a recording thread panicked.
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
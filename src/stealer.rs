@@ -1,9 +1,7 @@
-use crate::HaveIBeenPwned;
-use reqwest::header::{HeaderMap, HeaderValue};
-use urlencoding;
+use crate::{HaveIBeenPwned, HibpError};
 
 /// Represents a stealer log email address.
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct StealerLogEmail {
     /// The email address found in stealer logs.
     #[serde(rename = "email")]
@@ -11,7 +9,7 @@ pub struct StealerLogEmail {
 }
 
 /// Represents a stealer log email alias.
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct StealerLogAlias {
     /// The email alias found in stealer logs.
     #[serde(rename = "alias")]
@@ -19,7 +17,7 @@ pub struct StealerLogAlias {
 }
 
 /// Represents a stealer log domain for an email address.
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct StealerLogDomain {
     /// The domain found in stealer logs for the email address.
     #[serde(rename = "domain")]
@@ -46,26 +44,21 @@ impl HaveIBeenPwned {
     pub async fn get_stealer_log_emails_for_domain(
         &self,
         domain: &str,
-    ) -> Result<Vec<StealerLogEmail>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<StealerLogEmail>, HibpError> {
         let encoded_domain = urlencoding::encode(domain.trim());
         let url = format!("{}/stealerlog/domain/{}", self.base_url, encoded_domain);
 
-        let mut headers = HeaderMap::new();
-        headers.insert("hibp-api-key", HeaderValue::from_str(&self.api_key)?);
-        headers.insert(
-            reqwest::header::USER_AGENT,
-            HeaderValue::from_str(&self.user_agent)?,
-        );
+        let headers = self.create_headers()?;
 
-        let resp = self.client.get(&url).headers(headers).send().await?;
+        let resp = self.send_get("stealerlog/domain", &url, headers).await?;
 
-        if resp.status().is_success() {
-            let emails: Vec<StealerLogEmail> = resp.json().await?;
+        if resp.status.is_success() {
+            let emails: Vec<StealerLogEmail> = self.decode_json(&resp)?;
             Ok(emails)
-        } else if resp.status().as_u16() == 404 {
+        } else if resp.status.as_u16() == 404 {
             Ok(vec![])
         } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+            Err(resp.error())
         }
     }
 
@@ -88,26 +81,21 @@ impl HaveIBeenPwned {
     pub async fn get_stealer_log_aliases_for_domain(
         &self,
         domain: &str,
-    ) -> Result<Vec<StealerLogAlias>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<StealerLogAlias>, HibpError> {
         let encoded_domain = urlencoding::encode(domain.trim());
         let url = format!("{}/stealerlog/alias/{}", self.base_url, encoded_domain);
 
-        let mut headers = HeaderMap::new();
-        headers.insert("hibp-api-key", HeaderValue::from_str(&self.api_key)?);
-        headers.insert(
-            reqwest::header::USER_AGENT,
-            HeaderValue::from_str(&self.user_agent)?,
-        );
+        let headers = self.create_headers()?;
 
-        let resp = self.client.get(&url).headers(headers).send().await?;
+        let resp = self.send_get("stealerlog/alias", &url, headers).await?;
 
-        if resp.status().is_success() {
-            let aliases: Vec<StealerLogAlias> = resp.json().await?;
+        if resp.status.is_success() {
+            let aliases: Vec<StealerLogAlias> = self.decode_json(&resp)?;
             Ok(aliases)
-        } else if resp.status().as_u16() == 404 {
+        } else if resp.status.as_u16() == 404 {
             Ok(vec![])
         } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+            Err(resp.error())
         }
     }
 
@@ -130,26 +118,21 @@ impl HaveIBeenPwned {
     pub async fn get_stealer_log_domains_for_email(
         &self,
         email: &str,
-    ) -> Result<Vec<StealerLogDomain>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<StealerLogDomain>, HibpError> {
         let encoded_email = urlencoding::encode(email.trim());
         let url = format!("{}/stealerlog/email/{}", self.base_url, encoded_email);
 
-        let mut headers = HeaderMap::new();
-        headers.insert("hibp-api-key", HeaderValue::from_str(&self.api_key)?);
-        headers.insert(
-            reqwest::header::USER_AGENT,
-            HeaderValue::from_str(&self.user_agent)?,
-        );
+        let headers = self.create_headers()?;
 
-        let resp = self.client.get(&url).headers(headers).send().await?;
+        let resp = self.send_get("stealerlog/email", &url, headers).await?;
 
-        if resp.status().is_success() {
-            let domains: Vec<StealerLogDomain> = resp.json().await?;
+        if resp.status.is_success() {
+            let domains: Vec<StealerLogDomain> = self.decode_json(&resp)?;
             Ok(domains)
-        } else if resp.status().as_u16() == 404 {
+        } else if resp.status.as_u16() == 404 {
             Ok(vec![])
         } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+            Err(resp.error())
         }
     }
 }
@@ -0,0 +1,207 @@
+use crate::{HaveIBeenPwned, HibpError, RequestBlocked, blocked};
+use bytes::Bytes;
+use futures_core::Stream;
+
+impl HaveIBeenPwned {
+    /// Streams the raw body of a Pwned Passwords range as it arrives.
+    ///
+    /// Lets callers pipe range files straight to disk or into their own parser without
+    /// buffering the whole response. Each line has the form `SUFFIX:COUNT`, separated by
+    /// CRLF; chunk boundaries can fall anywhere, including inside a line. Streamed bodies
+    /// are not subject to [`HaveIBeenPwned::with_max_response_size`].
+    ///
+    /// Requires the `stream` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_prefix` - First 5 characters of a SHA-1 password hash
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// use futures_util::StreamExt;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let mut stream = std::pin::pin!(hibp.stream_password_range("CBF2D").await?);
+    /// let mut file = std::fs::File::create("CBF2D.txt")?;
+    /// while let Some(chunk) = stream.next().await {
+    ///     std::io::Write::write_all(&mut file, &chunk?)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stream_password_range(
+        &self,
+        hash_prefix: &str,
+    ) -> Result<impl Stream<Item = Result<Bytes, reqwest::Error>> + use<>, HibpError> {
+        if hash_prefix.len() != 5 {
+            return Err(HibpError::InvalidInput(
+                "Hash prefix must be exactly 5 characters".into(),
+            ));
+        }
+
+        let url = format!("{}/range/{}", self.passwords_base_url, hash_prefix);
+        let headers = self.create_headers()?;
+        #[cfg(feature = "full")]
+        self.wait_for_passwords_rate_limit("range").await?;
+        self.send_get_stream("range", &url, headers).await
+    }
+
+    /// Streams the raw JSON body of the full breach list as it arrives.
+    ///
+    /// Lets callers store the catalogue or feed it to a streaming JSON parser without
+    /// buffering it first. Streamed bodies are not subject to
+    /// [`HaveIBeenPwned::with_max_response_size`].
+    ///
+    /// Requires the `stream` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// use futures_util::StreamExt;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let hibp = HaveIBeenPwned::new("your_api_key");
+    /// let mut stream = std::pin::pin!(hibp.stream_all_breaches().await?);
+    /// let mut size = 0;
+    /// while let Some(chunk) = stream.next().await {
+    ///     size += chunk?.len();
+    /// }
+    /// println!("Breach list is {} bytes", size);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "full")]
+    pub async fn stream_all_breaches(
+        &self,
+    ) -> Result<impl Stream<Item = Result<Bytes, reqwest::Error>> + use<>, HibpError> {
+        self.wait_for_rate_limit("breaches").await?;
+
+        let url = format!("{}/breaches", self.base_url);
+        let headers = self.create_headers()?;
+        self.send_get_stream("breaches", &url, headers).await
+    }
+
+    /// Sends a GET request and returns its body as a stream once a successful status arrived.
+    ///
+    /// Failed attempts are retried according to the client's [`crate::RetryPolicy`] before
+    /// any of the body is streamed.
+    async fn send_get_stream(
+        &self,
+        endpoint: &str,
+        url: &str,
+        headers: reqwest::header::HeaderMap,
+    ) -> Result<impl Stream<Item = Result<Bytes, reqwest::Error>> + use<>, HibpError> {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+            let span = crate::telemetry::request_span(endpoint);
+            let start = crate::runtime::Instant::now();
+            let result = self
+                .send_get_stream_retrying(endpoint, url, headers)
+                .instrument(span.clone())
+                .await;
+            crate::telemetry::record_outcome(&span, start, result.as_ref().err());
+            result
+        }
+        #[cfg(not(feature = "tracing"))]
+        self.send_get_stream_retrying(endpoint, url, headers).await
+    }
+
+    /// Sends a GET request and returns its body as a stream, retrying failed attempts.
+    #[cfg(feature = "full")]
+    async fn send_get_stream_retrying(
+        &self,
+        endpoint: &str,
+        url: &str,
+        headers: reqwest::header::HeaderMap,
+    ) -> Result<impl Stream<Item = Result<Bytes, reqwest::Error>> + use<>, HibpError> {
+        let mut retry = 0;
+        loop {
+            let error = match self
+                .send_get_stream_once(endpoint, url, headers.clone())
+                .await
+            {
+                Ok(stream) => return Ok(stream),
+                Err(e) => e,
+            };
+            let Some(delay) = self.retry_delay(retry, &error) else {
+                return Err(error);
+            };
+            retry += 1;
+            self.stats.record_retry();
+            #[cfg(feature = "tracing")]
+            crate::telemetry::record_retry(retry, delay);
+            crate::runtime::sleep(delay).await;
+        }
+    }
+
+    /// Sends a GET request and returns its body as a stream, without retries as minimal
+    /// builds have no timers.
+    #[cfg(not(feature = "full"))]
+    async fn send_get_stream_retrying(
+        &self,
+        endpoint: &str,
+        url: &str,
+        headers: reqwest::header::HeaderMap,
+    ) -> Result<impl Stream<Item = Result<Bytes, reqwest::Error>> + use<>, HibpError> {
+        self.send_get_stream_once(endpoint, url, headers).await
+    }
+
+    /// Sends a GET request once and returns its body as a stream if the status is successful.
+    async fn send_get_stream_once(
+        &self,
+        endpoint: &str,
+        url: &str,
+        headers: reqwest::header::HeaderMap,
+    ) -> Result<impl Stream<Item = Result<Bytes, reqwest::Error>> + use<>, HibpError> {
+        self.check_free_tier(endpoint)?;
+        #[cfg(feature = "full")]
+        if let Err(e) = self.acquire_request_budget(endpoint) {
+            self.stats.record_error("budget");
+            return Err(e.into());
+        }
+        self.stats.record_request(endpoint);
+
+        let resp = match self.dispatch(self.client.get(url).headers(headers)).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                self.stats.record_error("network");
+                return Err(e);
+            }
+        };
+
+        let status = resp.status();
+        #[cfg(feature = "tracing")]
+        crate::telemetry::record_status(status);
+        self.response_meta
+            .record(crate::ResponseMeta::from_response(
+                endpoint,
+                status,
+                resp.headers(),
+            ));
+        if status.is_success() {
+            return Ok(resp.bytes_stream());
+        }
+
+        // Error bodies are small, read them to recognise block pages
+        let headers = resp.headers().clone();
+        let body = resp.bytes().await.unwrap_or_default();
+        if let Some(reason) = blocked::detect(status, &headers, &body) {
+            self.stats.record_error("blocked");
+            return Err(RequestBlocked {
+                endpoint: endpoint.to_string(),
+                status: status.as_u16(),
+                reason,
+            }
+            .into());
+        }
+        self.stats.record_status(status);
+        Err(HibpError::from_status(
+            status,
+            crate::error::retry_after(&headers),
+            crate::error::api_message(&body),
+        ))
+    }
+}
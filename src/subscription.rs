@@ -1,10 +1,15 @@
-use crate::HaveIBeenPwned;
+#[cfg(all(feature = "governor", not(target_arch = "wasm32")))]
+use crate::gcra::Bucket;
+#[cfg(not(all(feature = "governor", not(target_arch = "wasm32"))))]
+use crate::runtime::{self, Instant};
+use crate::{HaveIBeenPwned, HibpError};
 use serde::Deserialize;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+#[cfg(not(all(feature = "governor", not(target_arch = "wasm32"))))]
 use tokio::sync::Mutex;
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
 pub struct SubscriptionStatus {
     #[serde(rename = "SubscriptionName")]
     pub subscription_name: String,
@@ -21,7 +26,7 @@ pub struct SubscriptionStatus {
 }
 
 /// Represents a domain subscription returned by the HIBP API.
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize)]
 pub struct SubscribedDomain {
     /// The domain name.
     #[serde(rename = "domainName")]
@@ -35,37 +40,137 @@ pub struct SubscribedDomain {
 }
 
 /// Rate limiter to ensure we don't exceed API limits
+///
+/// A token bucket refilled at the configured rate. By default it holds a single token, so
+/// requests are spaced evenly; [`RateLimiter::with_burst`] lets short bursts through at once
+/// while still averaging the configured rate. With the `governor` feature, the bucket is
+/// implemented with the `governor` crate's GCRA instead of the built-in one.
 #[derive(Debug, Clone)]
 pub struct RateLimiter {
     rpm: i32,
-    last_request: Arc<Mutex<Instant>>,
+    burst: u32,
+    bucket: Arc<Bucket>,
+    #[cfg(all(feature = "governor", not(target_arch = "wasm32")))]
+    jitter: Duration,
 }
 
 impl RateLimiter {
     pub fn new(rpm: i32) -> Self {
         RateLimiter {
             rpm,
-            last_request: Arc::new(Mutex::new(Instant::now())),
+            burst: 1,
+            bucket: Arc::new(Bucket::new(rpm, 1)),
+            #[cfg(all(feature = "governor", not(target_arch = "wasm32")))]
+            jitter: Duration::ZERO,
         }
     }
 
+    /// Allows up to `burst` requests at once, e.g. the whole per-minute budget.
+    ///
+    /// The bucket starts full, and one token is added every `60 / rpm` seconds. Values
+    /// below 1 are treated as 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `burst` - Maximum number of requests sent without waiting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::RateLimiter;
+    /// // Pwned 3 allows 100 requests per minute; send up to 10 at once
+    /// let limiter = RateLimiter::new(100).with_burst(10);
+    /// assert_eq!(limiter.get_burst(), 10);
+    /// ```
+    #[must_use]
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self.bucket = Arc::new(Bucket::new(self.rpm, self.burst));
+        self
+    }
+
+    /// Delays each wait by a random duration of up to `max` (`governor` feature, native
+    /// targets only).
+    ///
+    /// Spreads out requests of many clients that would otherwise wake up at the same time,
+    /// e.g. workers started together.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - Maximum extra delay.
+    #[cfg(all(feature = "governor", not(target_arch = "wasm32")))]
+    #[must_use]
+    pub fn with_jitter(mut self, max: Duration) -> Self {
+        self.jitter = max;
+        self
+    }
+
     /// Gets the configured rate limit in requests per minute
     pub fn get_rpm(&self) -> i32 {
         self.rpm
     }
 
+    /// Gets the maximum number of requests sent without waiting
+    pub fn get_burst(&self) -> u32 {
+        self.burst
+    }
+
     /// Waits if necessary to ensure we don't exceed the rate limit
     pub async fn wait_if_needed(&self) {
-        let mut last_request = self.last_request.lock().await;
-        let time_since_last = last_request.elapsed();
-        let min_interval = Duration::from_secs_f32(60.0 / self.rpm as f32);
+        #[cfg(all(feature = "governor", not(target_arch = "wasm32")))]
+        self.bucket.acquire(self.jitter).await;
+        #[cfg(not(all(feature = "governor", not(target_arch = "wasm32"))))]
+        self.bucket.acquire().await;
+    }
+}
 
-        if time_since_last < min_interval {
-            let wait_time = min_interval - time_since_last;
-            tokio::time::sleep(wait_time).await;
+/// Token bucket behind a [`RateLimiter`].
+#[cfg(not(all(feature = "governor", not(target_arch = "wasm32"))))]
+#[derive(Debug)]
+struct Bucket {
+    rpm: i32,
+    burst: u32,
+    state: Mutex<BucketState>,
+}
+
+/// Tokens available in a [`Bucket`], as of the last refill.
+#[cfg(not(all(feature = "governor", not(target_arch = "wasm32"))))]
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled: Instant,
+}
+
+#[cfg(not(all(feature = "governor", not(target_arch = "wasm32"))))]
+impl Bucket {
+    /// Creates a full bucket.
+    fn new(rpm: i32, burst: u32) -> Self {
+        Bucket {
+            rpm,
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst as f64,
+                refilled: Instant::now(),
+            }),
         }
+    }
+
+    /// Takes a token, waiting for one to be added if the bucket is empty.
+    async fn acquire(&self) {
+        let mut state = self.state.lock().await;
+        let per_second = self.rpm.max(1) as f64 / 60.0;
+        let refilled = state.tokens + state.refilled.elapsed().as_secs_f64() * per_second;
+        state.tokens = refilled.min(self.burst as f64);
+        state.refilled = Instant::now();
 
-        *last_request = Instant::now();
+        if state.tokens < 1.0 {
+            // Holding the lock while sleeping queues other callers behind this one
+            runtime::sleep(Duration::from_secs_f64((1.0 - state.tokens) / per_second)).await;
+            state.tokens = 1.0;
+            state.refilled = Instant::now();
+        }
+
+        state.tokens -= 1.0;
     }
 }
 
@@ -82,42 +187,37 @@ impl HaveIBeenPwned {
     /// println!("Subscription: {}, RPM: {}", status.subscription_name, status.rpm);
     /// # }
     /// ```
-    pub async fn get_subscription_status(
-        &self,
-    ) -> Result<SubscriptionStatus, Box<dyn std::error::Error>> {
-        if let Some(rate_limiter) = &self.rate_limiter {
-            rate_limiter.wait_if_needed().await;
-        }
+    pub async fn get_subscription_status(&self) -> Result<SubscriptionStatus, HibpError> {
+        self.wait_for_rate_limit("subscription/status").await?;
+        self.fetch_subscription_status().await
+    }
 
+    /// Fetches the subscription status without waiting for the rate limiter.
+    ///
+    /// Used to configure lazy rate limiters, which cannot wait on themselves.
+    pub(crate) async fn fetch_subscription_status(&self) -> Result<SubscriptionStatus, HibpError> {
         let url = format!("{}/subscription/status", self.base_url);
         let headers = self.create_headers()?;
-        let resp = self.client.get(&url).headers(headers).send().await?;
+        let resp = self.send_get("subscription/status", &url, headers).await?;
 
-        if resp.status().is_success() {
-            let status: SubscriptionStatus = resp.json().await?;
+        if resp.status.is_success() {
+            let status: SubscriptionStatus = self.decode_json(&resp)?;
             Ok(status)
         } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+            Err(resp.error())
         }
     }
 
     /// Gets all domains the API key is subscribed to.
-    pub async fn get_all_subscribed_domains(
-        &self,
-    ) -> Result<Vec<SubscribedDomain>, Box<dyn std::error::Error>> {
-        if let Some(rate_limiter) = &self.rate_limiter {
-            rate_limiter.wait_if_needed().await;
-        }
-
+    pub async fn get_all_subscribed_domains(&self) -> Result<Vec<SubscribedDomain>, HibpError> {
         let url = format!("{}/subscribed", self.base_url);
-        let headers = self.create_headers()?;
-        let resp = self.client.get(&url).headers(headers).send().await?;
+        let resp = self.send_api_get("subscribed", &url).await?;
 
-        if resp.status().is_success() {
-            let domains: Vec<SubscribedDomain> = resp.json().await?;
+        if resp.status.is_success() {
+            let domains: Vec<SubscribedDomain> = self.decode_json(&resp)?;
             Ok(domains)
         } else {
-            Err(format!("API request failed with status: {}", resp.status()).into())
+            Err(resp.error())
         }
     }
 }
@@ -0,0 +1,193 @@
+use crate::{AlertSeverity, Event, HibpError, Notification, Notifier};
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+
+/// Wire format of a [`SyslogSink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SyslogFormat {
+    /// RFC 5424 with event fields as structured data.
+    Rfc5424,
+    /// systemd-journald native protocol with event fields as journal fields.
+    Journald,
+}
+
+/// Writes events to the local syslog daemon or systemd-journald.
+///
+/// Event fields are kept structured: as RFC 5424 structured data (`[hibp@32473 ...]`) for
+/// syslog, and as `HIBP_*` journal fields for journald, so they can be filtered with e.g.
+/// `journalctl HIBP_ACTION=breach-alert`.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{DedupNotifier, SyslogSink};
+///
+/// # fn example(findings: Vec<hibp_rs::ScanFinding>) -> Result<(), Box<dyn std::error::Error>> {
+/// let sink = SyslogSink::journald();
+/// for finding in &findings {
+///     sink.emit(&finding.to_event())?;
+/// }
+///
+/// // Sinks are also notifiers, so monitor alerts can be logged too
+/// let notifier = DedupNotifier::new(SyslogSink::new());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SyslogSink {
+    path: PathBuf,
+    format: SyslogFormat,
+    facility: u8,
+    app_name: String,
+}
+
+impl Default for SyslogSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyslogSink {
+    /// Creates a sink writing RFC 5424 messages to `/dev/log` with the `auth` facility.
+    pub fn new() -> Self {
+        SyslogSink {
+            path: PathBuf::from("/dev/log"),
+            format: SyslogFormat::Rfc5424,
+            facility: 4,
+            app_name: "hibp_rs".to_string(),
+        }
+    }
+
+    /// Creates a sink writing to the systemd-journald native socket.
+    pub fn journald() -> Self {
+        SyslogSink {
+            path: PathBuf::from("/run/systemd/journal/socket"),
+            format: SyslogFormat::Journald,
+            ..Self::new()
+        }
+    }
+
+    /// Sets the socket to write to.
+    #[must_use]
+    pub fn with_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Sets the syslog facility code, e.g. `10` for `authpriv` or `16` for `local0`.
+    #[must_use]
+    pub fn with_facility(mut self, facility: u8) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    /// Sets the application name (`SYSLOG_IDENTIFIER` for journald).
+    #[must_use]
+    pub fn with_app_name<S: Into<String>>(mut self, app_name: S) -> Self {
+        self.app_name = app_name.into();
+        self
+    }
+
+    /// Writes a single event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to write.
+    pub fn emit(&self, event: &Event) -> Result<(), HibpError> {
+        let socket = UnixDatagram::unbound()?;
+        socket.send_to(&self.format(event), &self.path)?;
+        Ok(())
+    }
+
+    /// Encodes an event in the sink's wire format.
+    pub(crate) fn format(&self, event: &Event) -> Vec<u8> {
+        let fields = event_fields(event);
+        let severity = match event.severity {
+            AlertSeverity::Critical => 2,
+            AlertSeverity::High => 3,
+            AlertSeverity::Medium => 4,
+            AlertSeverity::Low => 5,
+            AlertSeverity::Info => 6,
+        };
+
+        match self.format {
+            SyslogFormat::Rfc5424 => {
+                let data: String = fields
+                    .iter()
+                    .map(|(name, value)| format!(" {}=\"{}\"", name, escape_param(value)))
+                    .collect();
+                format!(
+                    "<{}>1 {} - {} {} {} [hibp@32473{}] {}",
+                    u32::from(self.facility) * 8 + severity,
+                    event.timestamp,
+                    self.app_name,
+                    std::process::id(),
+                    event.action,
+                    data,
+                    event.message
+                )
+                .into_bytes()
+            }
+            SyslogFormat::Journald => {
+                let mut entry = format!(
+                    "MESSAGE={}\nPRIORITY={}\nSYSLOG_FACILITY={}\nSYSLOG_IDENTIFIER={}\n",
+                    single_line(&event.message),
+                    severity,
+                    self.facility,
+                    self.app_name
+                );
+                for (name, value) in fields {
+                    entry.push_str(&format!(
+                        "HIBP_{}={}\n",
+                        name.to_ascii_uppercase(),
+                        single_line(&value)
+                    ));
+                }
+                entry.into_bytes()
+            }
+        }
+    }
+}
+
+/// Returns the structured fields of an event, skipping empty ones.
+fn event_fields(event: &Event) -> Vec<(&'static str, String)> {
+    let mut fields = vec![
+        ("action", event.action.clone()),
+        ("severity", format!("{:?}", event.severity).to_lowercase()),
+    ];
+    if let Some(account) = &event.account {
+        fields.push(("account", account.clone()));
+    }
+    if let Some(breach) = &event.breach {
+        fields.push(("breach", breach.clone()));
+    }
+    if let Some(count) = event.count {
+        fields.push(("count", count.to_string()));
+    }
+    if let Some(location) = &event.location {
+        fields.push(("location", location.clone()));
+    }
+    fields
+}
+
+/// Escapes an RFC 5424 structured data parameter value.
+fn escape_param(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+/// Journald's simple field format ends a value at the first newline.
+fn single_line(value: &str) -> String {
+    value.replace('\n', " ")
+}
+
+impl Notifier for SyslogSink {
+    async fn notify(&self, notification: &Notification) -> Result<(), HibpError> {
+        for event in notification.events() {
+            self.emit(&event)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use crate::HibpError;
+use crate::runtime::Instant;
+use std::time::Duration;
+use tracing::field::Empty;
+
+/// Creates the span covering one HIBP request, including its retries.
+///
+/// URLs are left out, as they contain the accounts and domains looked up.
+pub(crate) fn request_span(endpoint: &str) -> tracing::Span {
+    tracing::info_span!(
+        "hibp_request",
+        endpoint,
+        status = Empty,
+        retries = 0u32,
+        latency_ms = Empty,
+        error = Empty,
+    )
+}
+
+/// Records the outcome of a request on its span.
+///
+/// # Arguments
+///
+/// * `span` - The span from [`request_span`].
+/// * `start` - When the first attempt was sent.
+/// * `error` - The error returned to the caller, if any.
+pub(crate) fn record_outcome(span: &tracing::Span, start: Instant, error: Option<&HibpError>) {
+    span.record("latency_ms", start.elapsed().as_millis() as u64);
+    if let Some(error) = error {
+        span.record("error", tracing::field::display(error));
+    }
+}
+
+/// Records the HTTP status of the latest attempt of the current request.
+pub(crate) fn record_status(status: reqwest::StatusCode) {
+    tracing::Span::current().record("status", status.as_u16());
+}
+
+/// Records that the current request is retried after a failure.
+pub(crate) fn record_retry(retry: u32, delay: Duration) {
+    tracing::Span::current().record("retries", retry);
+    tracing::debug!(
+        retry,
+        delay_ms = delay.as_millis() as u64,
+        "retrying HIBP request"
+    );
+}
@@ -0,0 +1,122 @@
+use crate::{HibpError, Notification};
+use minijinja::{AutoEscape, Environment, Value};
+use std::collections::BTreeMap;
+
+/// Renders notification bodies from user-supplied minijinja templates.
+///
+/// The alert template sees the alert as `alert`; the digest template sees the rolled-up
+/// alerts as `alerts` and their number as `count`. Values added with
+/// [`NotificationTemplate::with_context`] are available to both. The `tojson` filter
+/// makes it easy to build JSON payloads such as Slack blocks.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{Alert, AlertSeverity, Notification, NotificationTemplate};
+///
+/// let template = NotificationTemplate::new(
+///     "{{ alert.account }} appears in {{ alert.title }} ({{ team }})",
+///     "{{ count }} accounts appear in new breaches",
+/// )
+/// .unwrap()
+/// .with_context("team", "security");
+///
+/// let alert = Alert {
+///     account: "alice@example.com".to_string(),
+///     breach: "Adobe".to_string(),
+///     title: "Adobe".to_string(),
+///     added_date: "2013-12-04T00:00:00Z".to_string(),
+///     pwn_count: 152445165,
+///     data_classes: vec!["Passwords".to_string()],
+///     severity: AlertSeverity::Medium,
+/// };
+/// assert_eq!(
+///     template.render(&Notification::Alert(alert)).unwrap(),
+///     "alice@example.com appears in Adobe (security)"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct NotificationTemplate {
+    env: Environment<'static>,
+    context: BTreeMap<String, Value>,
+}
+
+impl NotificationTemplate {
+    /// Creates a template renderer producing plain text or JSON, without escaping.
+    ///
+    /// # Arguments
+    ///
+    /// * `alert` - Template for single alerts.
+    /// * `digest` - Template for digests of many alerts.
+    pub fn new(alert: &str, digest: &str) -> Result<Self, HibpError> {
+        let mut env = Environment::new();
+        env.set_auto_escape_callback(|_| AutoEscape::None);
+        Self::with_environment(env, alert, digest)
+    }
+
+    /// Creates a template renderer producing HTML, e.g. for email bodies.
+    ///
+    /// All substituted values are HTML-escaped.
+    ///
+    /// # Arguments
+    ///
+    /// * `alert` - Template for single alerts.
+    /// * `digest` - Template for digests of many alerts.
+    pub fn html(alert: &str, digest: &str) -> Result<Self, HibpError> {
+        let mut env = Environment::new();
+        env.set_auto_escape_callback(|_| AutoEscape::Html);
+        Self::with_environment(env, alert, digest)
+    }
+
+    fn with_environment(
+        mut env: Environment<'static>,
+        alert: &str,
+        digest: &str,
+    ) -> Result<Self, HibpError> {
+        env.add_template_owned("alert", alert.to_string())
+            .map_err(HibpError::other)?;
+        env.add_template_owned("digest", digest.to_string())
+            .map_err(HibpError::other)?;
+        Ok(NotificationTemplate {
+            env,
+            context: BTreeMap::new(),
+        })
+    }
+
+    /// Adds a value that both templates can refer to by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the variable in the templates.
+    /// * `value` - Any serializable value.
+    #[must_use]
+    pub fn with_context<V: serde::Serialize>(mut self, name: &str, value: V) -> Self {
+        self.context
+            .insert(name.to_string(), Value::from_serialize(value));
+        self
+    }
+
+    /// Renders the body of a notification.
+    ///
+    /// # Arguments
+    ///
+    /// * `notification` - The notification to render.
+    pub fn render(&self, notification: &Notification) -> Result<String, HibpError> {
+        let mut context = self.context.clone();
+        let name = match notification {
+            Notification::Alert(alert) => {
+                context.insert("alert".to_string(), Value::from_serialize(alert));
+                "alert"
+            }
+            Notification::Digest { alerts } => {
+                context.insert("alerts".to_string(), Value::from_serialize(alerts));
+                context.insert("count".to_string(), Value::from(alerts.len()));
+                "digest"
+            }
+        };
+        self.env
+            .get_template(name)
+            .and_then(|template| template.render(context))
+            .map_err(HibpError::other)
+    }
+}
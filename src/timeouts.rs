@@ -0,0 +1,60 @@
+use crate::HaveIBeenPwned;
+use reqwest::Client;
+use std::time::Duration;
+
+/// Connect and request timeouts of a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Timeouts {
+    /// Time allowed to establish a connection.
+    pub(crate) connect: Duration,
+    /// Time allowed for a whole request, from connecting to reading the body.
+    pub(crate) request: Duration,
+}
+
+impl Default for Timeouts {
+    /// Allows 10 seconds to connect and 30 seconds per request.
+    fn default() -> Self {
+        Timeouts {
+            connect: Duration::from_secs(10),
+            request: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Builds an HTTP client with the given connect timeout.
+pub(crate) fn http_client(connect_timeout: Duration) -> Result<Client, reqwest::Error> {
+    Client::builder().connect_timeout(connect_timeout).build()
+}
+
+/// Builds the HTTP client of a new [`HaveIBeenPwned`] with the default connect timeout.
+pub(crate) fn default_http_client() -> Client {
+    // Fails in the same cases `Client::new` panics in
+    http_client(Timeouts::default().connect).expect("Client::new()")
+}
+
+impl HaveIBeenPwned {
+    /// Sets the time allowed for each request, from connecting to reading the whole body.
+    ///
+    /// Requests time out after 30 seconds by default, so a hung connection can't stall a
+    /// signup flow. Timed out requests fail with [`crate::HibpError::Http`] and are
+    /// retried like other connection failures. Byte streams are not limited, as large
+    /// bodies may take longer to download. Not available on WASM, where requests are
+    /// subject to the host's fetch limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Maximum duration of a request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hibp_rs::HaveIBeenPwned;
+    /// use std::time::Duration;
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_request_timeout(Duration::from_secs(5));
+    /// ```
+    #[must_use]
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.request = timeout;
+        self
+    }
+}
@@ -0,0 +1,309 @@
+use crate::{HaveIBeenPwned, HibpError};
+use reqwest::Client;
+use std::time::Duration;
+
+/// PEM encoded client certificate chain and PKCS#8 private key.
+#[derive(Clone)]
+pub(crate) struct ClientIdentity {
+    cert_pem: Vec<u8>,
+    key_pem: Vec<u8>,
+}
+
+impl std::fmt::Debug for ClientIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientIdentity")
+            .field("key_pem", &"<redacted>")
+            .finish_non_exhaustive()
+    }
+}
+
+/// TLS settings the HTTP client of a [`HaveIBeenPwned`] is built from.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TlsSettings {
+    /// SHA-256 hashes of the pinned SubjectPublicKeyInfo structures.
+    #[cfg(feature = "tls-pinning")]
+    pins: Vec<[u8; 32]>,
+    /// Client certificate presented during the handshake.
+    identity: Option<ClientIdentity>,
+}
+
+impl TlsSettings {
+    /// Builds an HTTP client applying the settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `connect_timeout` - Time allowed to establish a connection.
+    fn build_client(&self, connect_timeout: Duration) -> Result<Client, HibpError> {
+        #[allow(unused_mut)]
+        let mut builder = Client::builder().connect_timeout(connect_timeout);
+        #[cfg(feature = "tls-pinning")]
+        if !self.pins.is_empty() {
+            let config = pinning::client_config(self.pins.clone(), self.identity.as_ref())?;
+            return Ok(builder.use_preconfigured_tls(config).build()?);
+        }
+        #[cfg(feature = "mtls")]
+        if let Some(identity) = &self.identity {
+            builder = builder.identity(reqwest::Identity::from_pkcs8_pem(
+                &identity.cert_pem,
+                &identity.key_pem,
+            )?);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+impl HaveIBeenPwned {
+    /// Only accepts TLS connections whose certificate chain contains one of the given public keys.
+    ///
+    /// The certificate chain is validated as usual first; in addition, the SHA-256 hash of the
+    /// SubjectPublicKeyInfo of the server certificate or of an intermediate CA it was issued by
+    /// must match a pin. Intermediates only count if the server certificate's signatures chain
+    /// up to them, not if the server merely sends them along. This detects TLS interception by
+    /// proxies whose root certificate was installed on the host. Pass several pins, e.g. the
+    /// current and a backup key or the intermediate CA, so certificate rotation doesn't break
+    /// the client. Root CAs aren't sent in handshakes and can't be pinned. The pins apply to
+    /// every host the client connects to, including `api.pwnedpasswords.com`.
+    ///
+    /// Pins use the `sha256/<base64>` format known from HPKP, which can be computed with:
+    ///
+    /// ```text
+    /// openssl s_client -connect haveibeenpwned.com:443 </dev/null 2>/dev/null \
+    ///   | openssl x509 -pubkey -noout | openssl pkey -pubin -outform der \
+    ///   | openssl dgst -sha256 -binary | base64
+    /// ```
+    ///
+    /// Requires the `tls-pinning` feature, which switches the client to rustls.
+    ///
+    /// # Arguments
+    ///
+    /// * `pins` - Accepted public key hashes in `sha256/<base64>` format.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hibp_rs::HaveIBeenPwned;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_pinned_public_keys([
+    ///     "sha256/AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA=",
+    ///     "sha256/BBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBBB=",
+    /// ])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tls-pinning")]
+    pub fn with_pinned_public_keys<I, S>(mut self, pins: I) -> Result<Self, HibpError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let pins = pins
+            .into_iter()
+            .map(|pin| pinning::parse_pin(pin.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if pins.is_empty() {
+            return Err(HibpError::InvalidInput(
+                "At least one public key pin is required".into(),
+            ));
+        }
+        self.tls.pins = pins;
+        self.client = self.tls.build_client(self.timeouts.connect)?;
+        Ok(self)
+    }
+
+    /// Presents a client certificate during TLS handshakes.
+    ///
+    /// For deployments that route HIBP traffic through an egress gateway enforcing mutual
+    /// TLS; point [`HaveIBeenPwned::base_url`] at the gateway if it doesn't proxy transparently.
+    /// The private key must be an unencrypted PKCS#8 key, which other formats can be
+    /// converted to with `openssl pkcs8 -topk8 -nocrypt -in key.pem -out key.pk8.pem`.
+    ///
+    /// Requires the `mtls` feature. Combines with
+    /// [`HaveIBeenPwned::with_pinned_public_keys`] in either order.
+    ///
+    /// # Arguments
+    ///
+    /// * `cert_pem` - PEM encoded client certificate, optionally followed by its chain.
+    /// * `key_pem` - PEM encoded PKCS#8 private key of the certificate.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use hibp_rs::HaveIBeenPwned;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let hibp = HaveIBeenPwned::new("your_api_key").with_client_identity(
+    ///     &std::fs::read("client.pem")?,
+    ///     &std::fs::read("client.key")?,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "mtls")]
+    pub fn with_client_identity(
+        mut self,
+        cert_pem: &[u8],
+        key_pem: &[u8],
+    ) -> Result<Self, HibpError> {
+        self.tls.identity = Some(ClientIdentity {
+            cert_pem: cert_pem.to_vec(),
+            key_pem: key_pem.to_vec(),
+        });
+        self.client = self.tls.build_client(self.timeouts.connect)?;
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "tls-pinning")]
+pub(crate) mod pinning {
+    use super::ClientIdentity;
+    use crate::HibpError;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use rustls::client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+    use rustls::{
+        Certificate, ClientConfig, OwnedTrustAnchor, PrivateKey, RootCertStore, ServerName,
+    };
+    use sha2::{Digest, Sha256};
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    /// Validates certificates against the web PKI, then requires a pinned public key.
+    pub(crate) struct PinningVerifier {
+        webpki: WebPkiVerifier,
+        pins: Vec<[u8; 32]>,
+    }
+
+    impl PinningVerifier {
+        /// Creates a verifier trusting `roots` and enforcing `pins`.
+        pub(crate) fn new(roots: RootCertStore, pins: Vec<[u8; 32]>) -> Self {
+            PinningVerifier {
+                webpki: WebPkiVerifier::new(roots, None),
+                pins,
+            }
+        }
+
+        fn is_pinned(&self, cert: &Certificate) -> bool {
+            spki_of(&cert.0).is_some_and(|spki| {
+                let hash: [u8; 32] = Sha256::digest(spki).into();
+                self.pins.contains(&hash)
+            })
+        }
+
+        /// Whether the server certificate is pinned or its signatures chain up to a pinned
+        /// intermediate. A pinned intermediate is only trusted as the anchor of a second
+        /// validation, so one the server sends next to an unrelated chain doesn't count.
+        fn chains_to_pin(
+            &self,
+            end_entity: &Certificate,
+            intermediates: &[Certificate],
+            server_name: &ServerName,
+            now: SystemTime,
+        ) -> bool {
+            if self.is_pinned(end_entity) {
+                return true;
+            }
+            intermediates
+                .iter()
+                .filter(|cert| self.is_pinned(cert))
+                .any(|pinned| {
+                    let mut anchor = RootCertStore::empty();
+                    anchor.add(pinned).is_ok()
+                        && WebPkiVerifier::new(anchor, None)
+                            .verify_server_cert(
+                                end_entity,
+                                intermediates,
+                                server_name,
+                                &mut std::iter::empty(),
+                                &[],
+                                now,
+                            )
+                            .is_ok()
+                })
+        }
+    }
+
+    impl ServerCertVerifier for PinningVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &Certificate,
+            intermediates: &[Certificate],
+            server_name: &ServerName,
+            scts: &mut dyn Iterator<Item = &[u8]>,
+            ocsp_response: &[u8],
+            now: SystemTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            let verified = self.webpki.verify_server_cert(
+                end_entity,
+                intermediates,
+                server_name,
+                scts,
+                ocsp_response,
+                now,
+            )?;
+
+            if self.chains_to_pin(end_entity, intermediates, server_name, now) {
+                Ok(verified)
+            } else {
+                Err(rustls::Error::General(
+                    "Certificate chain matches none of the pinned public keys".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Builds a rustls configuration trusting the Mozilla roots and enforcing the pins.
+    pub(crate) fn client_config(
+        pins: Vec<[u8; 32]>,
+        identity: Option<&ClientIdentity>,
+    ) -> Result<ClientConfig, HibpError> {
+        let mut roots = RootCertStore::empty();
+        roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+            OwnedTrustAnchor::from_subject_spki_name_constraints(
+                anchor.subject,
+                anchor.spki,
+                anchor.name_constraints,
+            )
+        }));
+        let verifier = PinningVerifier::new(roots, pins);
+
+        let builder = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(verifier));
+        let Some(identity) = identity else {
+            return Ok(builder.with_no_client_auth());
+        };
+
+        let certs = rustls_pemfile::certs(&mut identity.cert_pem.as_slice())?
+            .into_iter()
+            .map(Certificate)
+            .collect();
+        let key = rustls_pemfile::pkcs8_private_keys(&mut identity.key_pem.as_slice())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| HibpError::InvalidInput("No PKCS#8 private key found".into()))?;
+        builder
+            .with_client_auth_cert(certs, PrivateKey(key))
+            .map_err(HibpError::other)
+    }
+
+    /// Parses a `sha256/<base64>` pin into the hash it contains.
+    pub(crate) fn parse_pin(pin: &str) -> Result<[u8; 32], HibpError> {
+        let encoded = pin.strip_prefix("sha256/").ok_or_else(|| {
+            HibpError::InvalidInput(format!("Pin must start with sha256/: {}", pin))
+        })?;
+        STANDARD
+            .decode(encoded)
+            .ok()
+            .and_then(|hash| hash.try_into().ok())
+            .ok_or_else(|| {
+                HibpError::InvalidInput(format!("Pin is not a base64 SHA-256 hash: {}", pin))
+            })
+    }
+
+    /// Returns the DER encoded SubjectPublicKeyInfo of a DER encoded X.509 certificate, or
+    /// `None` if the certificate can't be parsed.
+    pub(crate) fn spki_of(cert: &[u8]) -> Option<&[u8]> {
+        use x509_parser::prelude::{FromDer, X509Certificate};
+        let (_, certificate) = X509Certificate::from_der(cert).ok()?;
+        Some(certificate.tbs_certificate.subject_pki.raw)
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{Breach, HaveIBeenPwned, HibpError};
+
+/// Domains where dots in the local part are ignored and which are aliases of each other.
+const GMAIL_DOMAINS: &[&str] = &["gmail.com", "googlemail.com"];
+
+/// Breaches found for one alias variant of an email address.
+#[derive(Debug, Clone)]
+pub struct VariantExposure {
+    /// The variant that was checked.
+    pub email: String,
+    /// Breaches the variant appears in.
+    pub breaches: Vec<Breach>,
+}
+
+/// Expands an email address into alias variants that deliver to the same mailbox.
+///
+/// The result starts with the address itself, followed by the address without its
+/// plus-tag, the address with each of the given tags, and for Gmail the dotless local
+/// part on both `gmail.com` and `googlemail.com`. Variants are lowercase and unique.
+///
+/// # Arguments
+///
+/// * `email` - The email address to expand.
+/// * `tags` - Additional plus-tags to try, e.g. `["shopping", "newsletter"]`.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::email_variants;
+/// let variants = email_variants("Jane.Doe+news@gmail.com", &["shop"]);
+/// assert_eq!(
+///     variants,
+///     [
+///         "jane.doe+news@gmail.com",
+///         "jane.doe@gmail.com",
+///         "jane.doe+shop@gmail.com",
+///         "janedoe@gmail.com",
+///         "janedoe@googlemail.com",
+///         "jane.doe@googlemail.com",
+///     ]
+/// );
+/// ```
+pub fn email_variants(email: &str, tags: &[&str]) -> Vec<String> {
+    let email = email.trim().to_ascii_lowercase();
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return vec![email];
+    };
+    let base = local.split_once('+').map_or(local, |(base, _)| base);
+
+    let mut variants = vec![email.clone(), format!("{}@{}", base, domain)];
+    variants.extend(
+        tags.iter()
+            .map(|tag| format!("{}+{}@{}", base, tag, domain)),
+    );
+    if GMAIL_DOMAINS.contains(&domain) {
+        let dotless = base.replace('.', "");
+        for domain in GMAIL_DOMAINS {
+            variants.push(format!("{}@{}", dotless, domain));
+            variants.push(format!("{}@{}", base, domain));
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    variants.retain(|variant| seen.insert(variant.clone()));
+    variants
+}
+
+impl HaveIBeenPwned {
+    /// Checks every alias variant of an email address for breaches.
+    ///
+    /// Variants come from [`email_variants`] and are checked one after another, so the
+    /// client's rate limiter applies to each request. Useful for investigating signups
+    /// made with plus-addressing or Gmail dot aliases. Returns one entry per variant,
+    /// including variants without breaches.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - The email address to expand and check.
+    /// * `tags` - Additional plus-tags to try.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use hibp_rs::HaveIBeenPwned;
+    /// # async fn example() {
+    /// # let hibp = HaveIBeenPwned::new_with_rate_limit("your_api_key", 10);
+    /// let exposures = hibp
+    ///     .check_email_variants("jane.doe+news@gmail.com", &["shop"])
+    ///     .await
+    ///     .unwrap();
+    /// for exposure in exposures.iter().filter(|e| !e.breaches.is_empty()) {
+    ///     println!("{} is in {} breaches", exposure.email, exposure.breaches.len());
+    /// }
+    /// # }
+    /// ```
+    pub async fn check_email_variants(
+        &self,
+        email: &str,
+        tags: &[&str],
+    ) -> Result<Vec<VariantExposure>, HibpError> {
+        let mut exposures = Vec::new();
+        for variant in email_variants(email, tags) {
+            let breaches = self.get_breaches_for_account(&variant).await?;
+            exposures.push(VariantExposure {
+                email: variant,
+                breaches,
+            });
+        }
+        Ok(exposures)
+    }
+}
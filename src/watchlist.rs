@@ -0,0 +1,231 @@
+use crate::{AuditState, Breach, HaveIBeenPwned, HibpError, Paste, ResultSink};
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+/// Something newly observed for a watched account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchlistEvent {
+    /// The account was found in a breach for the first time.
+    Breach {
+        /// The watched account.
+        account: String,
+        /// The breach the account was found in.
+        breach: Breach,
+    },
+    /// The account was found in a paste for the first time.
+    Paste {
+        /// The watched account.
+        account: String,
+        /// The paste the account was found in.
+        paste: Paste,
+    },
+}
+
+impl WatchlistEvent {
+    /// Returns the account the event is about.
+    pub fn account(&self) -> &str {
+        match self {
+            WatchlistEvent::Breach { account, .. } | WatchlistEvent::Paste { account, .. } => {
+                account
+            }
+        }
+    }
+}
+
+/// Re-checks a set of accounts and reports breaches and pastes not observed before.
+///
+/// What was observed is kept in an [`AuditState`], which can be saved and passed to
+/// [`Watchlist::with_state`] on the next run so nothing is reported twice. Without saved
+/// state, the first check reports every breach of each account. Requests wait for the
+/// client's rate limiter.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{AuditState, HaveIBeenPwned, Watchlist};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let hibp = HaveIBeenPwned::new_with_rate_limit("your_api_key", 10);
+/// let mut watchlist = Watchlist::new(hibp)
+///     .with_state(AuditState::load("watchlist.json")?)
+///     .with_pastes();
+/// watchlist.add("ceo@example.com");
+/// watchlist.add("cfo@example.com");
+///
+/// for event in watchlist.check().await? {
+///     println!("{:?}", event);
+/// }
+/// watchlist.state().save("watchlist.json")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Watchlist {
+    client: HaveIBeenPwned,
+    accounts: BTreeSet<String>,
+    state: AuditState,
+    include_pastes: bool,
+    poll_interval: Duration,
+}
+
+impl Watchlist {
+    /// Creates an empty watchlist that checks breaches only, every hour when streamed.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to check accounts with.
+    pub fn new(client: HaveIBeenPwned) -> Self {
+        Watchlist {
+            client,
+            accounts: BTreeSet::new(),
+            state: AuditState::default(),
+            include_pastes: false,
+            poll_interval: Duration::from_secs(60 * 60),
+        }
+    }
+
+    /// Continues from the state of a previous run.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - What was observed before, e.g. from [`AuditState::load`].
+    #[must_use]
+    pub fn with_state(mut self, state: AuditState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Checks the pastes of each account as well.
+    #[must_use]
+    pub fn with_pastes(mut self) -> Self {
+        self.include_pastes = true;
+        self
+    }
+
+    /// Sets the time between checks when streaming events.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - How long to wait after each check.
+    #[must_use]
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Adds an account to the watchlist.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The email address to watch.
+    pub fn add<S: Into<String>>(&mut self, account: S) {
+        let account: String = account.into();
+        self.accounts.insert(account.trim().to_string());
+    }
+
+    /// Removes an account from the watchlist, keeping what was observed for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `account` - The email address to stop watching.
+    pub fn remove(&mut self, account: &str) {
+        self.accounts.remove(account.trim());
+    }
+
+    /// Returns the watched accounts, in order.
+    pub fn accounts(&self) -> impl Iterator<Item = &str> {
+        self.accounts.iter().map(String::as_str)
+    }
+
+    /// Returns what was observed so far, e.g. to save it for the next run.
+    pub fn state(&self) -> &AuditState {
+        &self.state
+    }
+
+    /// Checks every account once and returns what was not observed before.
+    ///
+    /// The state is only updated once all accounts were checked, so after an error the
+    /// next check reports the same findings again rather than losing them.
+    pub async fn check(&mut self) -> Result<Vec<WatchlistEvent>, HibpError> {
+        let mut results = Vec::with_capacity(self.accounts.len());
+        for account in &self.accounts {
+            let breaches = self.client.get_breaches_for_account(account).await?;
+            let pastes = if self.include_pastes {
+                self.client.get_pastes_for_account(account).await?
+            } else {
+                Vec::new()
+            };
+            results.push((account.clone(), breaches, pastes));
+        }
+
+        let mut events = Vec::new();
+        for (account, breaches, pastes) in results {
+            for observed in self.state.observe(&account, breaches) {
+                if observed.is_new {
+                    events.push(WatchlistEvent::Breach {
+                        account: account.clone(),
+                        breach: observed.breach,
+                    });
+                }
+            }
+            for paste in self.state.observe_pastes(&account, pastes) {
+                events.push(WatchlistEvent::Paste {
+                    account: account.clone(),
+                    paste,
+                });
+            }
+        }
+        Ok(events)
+    }
+
+    /// Checks every account once and writes what was not observed before to a sink.
+    ///
+    /// Like [`Watchlist::check`]: nothing is written if a check fails. The sink is flushed
+    /// but not closed. Returns the number of events written.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - Destination of the events.
+    pub async fn check_into<S: ResultSink<WatchlistEvent>>(
+        &mut self,
+        sink: &mut S,
+    ) -> Result<usize, HibpError> {
+        let events = self.check().await?;
+        let count = events.len();
+        for event in events {
+            sink.write(event).await?;
+        }
+        sink.flush().await?;
+        Ok(count)
+    }
+
+    /// Checks the accounts until a permanent error, yielding each new event once.
+    ///
+    /// The first check happens right away, then every poll interval. Failed checks are
+    /// retried after the poll interval, unless the error is permanent, see
+    /// [`HibpError::is_permanent`]: then it is yielded and the stream ends.
+    ///
+    /// Requires the `monitor` feature.
+    #[cfg(feature = "monitor")]
+    pub fn into_stream(
+        self,
+    ) -> impl futures_core::Stream<Item = Result<WatchlistEvent, HibpError>> {
+        let state = (Some(self), std::collections::VecDeque::new(), true);
+        futures_util::stream::unfold(state, |(watchlist, mut pending, mut first)| async move {
+            let mut watchlist = watchlist?;
+            while pending.is_empty() {
+                if !first {
+                    crate::runtime::sleep(watchlist.poll_interval).await;
+                }
+                first = false;
+                match watchlist.check().await {
+                    Ok(events) => pending.extend(events),
+                    Err(e) if e.is_permanent() => return Some((Err(e), (None, pending, first))),
+                    Err(_) => {}
+                }
+            }
+            let event = pending.pop_front()?;
+            Some((Ok(event), (Some(watchlist), pending, first)))
+        })
+    }
+}
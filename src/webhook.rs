@@ -0,0 +1,180 @@
+use crate::runtime::SystemTime;
+use crate::{HibpError, Notification, Notifier};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use sha2::Sha256;
+use std::time::Duration;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Header carrying the Unix timestamp a webhook was signed at.
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "X-HIBP-Timestamp";
+
+/// Header carrying the `sha256=<hex>` signature of a webhook.
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-HIBP-Signature";
+
+/// Computes the `sha256=<hex>` signature of a webhook body.
+///
+/// The signature is an HMAC-SHA256 over `"{timestamp}.{body}"`, so a captured request
+/// cannot be replayed with a fresh timestamp.
+///
+/// # Arguments
+///
+/// * `secret` - Secret shared with the receiver.
+/// * `timestamp` - Unix timestamp sent in [`WEBHOOK_TIMESTAMP_HEADER`].
+/// * `body` - Raw request body.
+pub fn sign_webhook(secret: &[u8], timestamp: u64, body: &[u8]) -> String {
+    let mac = webhook_mac(secret, timestamp, body);
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+/// Verifies the signature of a received webhook in constant time.
+///
+/// Returns `false` if the signature doesn't match or the timestamp is further than
+/// `tolerance` from the current time.
+///
+/// # Arguments
+///
+/// * `secret` - Secret shared with the sender.
+/// * `timestamp` - Value of the [`WEBHOOK_TIMESTAMP_HEADER`] header.
+/// * `body` - Raw request body, exactly as received.
+/// * `signature` - Value of the [`WEBHOOK_SIGNATURE_HEADER`] header.
+/// * `tolerance` - Maximum accepted clock difference, e.g. five minutes.
+///
+/// # Example
+///
+/// ```
+/// use hibp_rs::{sign_webhook, verify_webhook};
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+/// let body = br#"{"kind":"digest","alerts":[]}"#;
+/// let signature = sign_webhook(b"secret", now, body);
+///
+/// assert!(verify_webhook(b"secret", &now.to_string(), body, &signature, Duration::from_secs(300)));
+/// assert!(!verify_webhook(b"wrong", &now.to_string(), body, &signature, Duration::from_secs(300)));
+/// ```
+pub fn verify_webhook(
+    secret: &[u8],
+    timestamp: &str,
+    body: &[u8],
+    signature: &str,
+    tolerance: Duration,
+) -> bool {
+    let Ok(timestamp) = timestamp.trim().parse::<u64>() else {
+        return false;
+    };
+    if unix_time().abs_diff(timestamp) > tolerance.as_secs() {
+        return false;
+    }
+    let Some(expected) = signature
+        .trim()
+        .strip_prefix("sha256=")
+        .and_then(decode_hex)
+    else {
+        return false;
+    };
+    webhook_mac(secret, timestamp, body)
+        .verify_slice(&expected)
+        .is_ok()
+}
+
+fn webhook_mac(secret: &[u8], timestamp: u64, body: &[u8]) -> HmacSha256 {
+    // HMAC accepts keys of any length, so this cannot fail
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Posts notifications as JSON to a webhook, signed with a shared secret.
+///
+/// Every request carries the signing time in [`WEBHOOK_TIMESTAMP_HEADER`] and an
+/// HMAC-SHA256 signature in [`WEBHOOK_SIGNATURE_HEADER`]. Receivers check both with
+/// [`verify_webhook`] against the raw body before parsing it.
+///
+/// # Example
+///
+/// ```no_run
+/// use hibp_rs::{DedupNotifier, WebhookNotifier};
+///
+/// let notifier = DedupNotifier::new(WebhookNotifier::new(
+///     "https://hooks.example.com/hibp",
+///     "shared-secret",
+/// ));
+/// ```
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    url: String,
+    secret: Vec<u8>,
+    client: Client,
+}
+
+impl std::fmt::Debug for WebhookNotifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookNotifier")
+            .field("url", &self.url)
+            .field("secret", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl WebhookNotifier {
+    /// Creates a notifier posting to the given URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Endpoint receiving the notifications.
+    /// * `secret` - Secret shared with the receiver for signing.
+    pub fn new<U: Into<String>, S: AsRef<[u8]>>(url: U, secret: S) -> Self {
+        WebhookNotifier {
+            url: url.into(),
+            secret: secret.as_ref().to_vec(),
+            client: Client::new(),
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, notification: &Notification) -> Result<(), HibpError> {
+        let body = serde_json::to_vec(notification)?;
+        let timestamp = unix_time();
+        let signature = sign_webhook(&self.secret, timestamp, &body);
+
+        let resp = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+            .header(WEBHOOK_SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(HibpError::other(format!(
+                "Webhook request failed with status: {}",
+                resp.status()
+            )))
+        }
+    }
+}